@@ -0,0 +1,221 @@
+#![allow(non_snake_case)]
+
+use as_for_fde::{
+    AS_scheme, AdaptorStatement, AdaptorWitness, AumayrECDSA, DiscreteLog, PreSignRequest,
+    PreSignatureItem, PublicKey, Schnorr, Sigma, Sigma_prime, Sigma_prime_Aumayr,
+    Sigma_prime_ECDSA, Sign_scheme, SignatureItem, ECDSA,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use k256::{elliptic_curve::ff::Field, Scalar};
+use rand_core::OsRng;
+
+/// Batch sizes compared against the single-item path.
+const SIZES: [usize; 3] = [1, 64, 4096];
+
+fn message(i: usize) -> Vec<u8> {
+    format!("transaction {i}").into_bytes()
+}
+
+fn signatures(c: &mut Criterion) {
+    let n = SIZES[SIZES.len() - 1];
+    let messages: Vec<Vec<u8>> = (0..n).map(message).collect();
+    let (keys, sigmas): (Vec<PublicKey>, Vec<Sigma>) = messages
+        .iter()
+        .map(|m| {
+            let (p, P) = Schnorr::new().keygen(&mut OsRng);
+            (P, Schnorr::new().sign(&p, m, None).unwrap())
+        })
+        .unzip();
+    let items: Vec<SignatureItem> = (0..n)
+        .map(|i| SignatureItem {
+            sigma: &sigmas[i],
+            P: &keys[i],
+            m: &messages[i],
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("verify_sign");
+    group.sample_size(10);
+    for size in SIZES {
+        let items = &items[..size];
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("single", size), items, |b, items| {
+            b.iter(|| {
+                items
+                    .iter()
+                    .all(|item| Schnorr::new().verify_sign(item.sigma, item.P, item.m))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", size), items, |b, items| {
+            b.iter(|| Schnorr::new().verify_sign_batch(items, &mut OsRng))
+        });
+    }
+    group.finish();
+}
+
+fn pre_signatures(c: &mut Criterion) {
+    let n = SIZES[SIZES.len() - 1];
+    let messages: Vec<Vec<u8>> = (0..n).map(message).collect();
+    let mut keys: Vec<PublicKey> = Vec::new();
+    let mut statements: Vec<AdaptorStatement> = Vec::new();
+    let mut pre_signatures: Vec<Sigma_prime> = Vec::new();
+    for m in &messages {
+        let (p, P) = Schnorr::new().keygen(&mut OsRng);
+        let (_, T, _) = Schnorr::new().gen_statement(&mut OsRng, false);
+        pre_signatures.push(Schnorr::new().pre_sign(&p, m, &T, None).unwrap());
+        keys.push(P);
+        statements.push(T);
+    }
+    let items: Vec<PreSignatureItem<AdaptorStatement>> = (0..n)
+        .map(|i| PreSignatureItem {
+            P: &keys[i],
+            m: &messages[i],
+            T: &statements[i],
+            sigma_prime: &pre_signatures[i],
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("verify_pre_sign");
+    group.sample_size(10);
+    for size in SIZES {
+        let items = &items[..size];
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("single", size), items, |b, items| {
+            b.iter(|| {
+                items.iter().all(|item| {
+                    Schnorr::new().verify_pre_sign(item.P, item.m, item.T, item.sigma_prime)
+                })
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", size), items, |b, items| {
+            b.iter(|| Schnorr::new().verify_pre_sign_batch(items, &mut OsRng))
+        });
+    }
+    group.finish();
+}
+
+/// Pre-signs `n` messages, each with its own key and statement.
+fn pre_sign_many<S: AS_scheme<Relation = DiscreteLog>>(
+    scheme: &S,
+    messages: &[Vec<u8>],
+) -> (Vec<PublicKey>, Vec<AdaptorStatement>, Vec<S::PreSignature>) {
+    let mut keys = Vec::new();
+    let mut statements = Vec::new();
+    let mut pre_signatures = Vec::new();
+    for m in messages {
+        let (p, P) = scheme.keygen(&mut OsRng);
+        let (_, T, _) = scheme.gen_statement(&mut OsRng, false);
+        pre_signatures.push(scheme.pre_sign(&p, m, &T, None).unwrap());
+        keys.push(P);
+        statements.push(T);
+    }
+    (keys, statements, pre_signatures)
+}
+
+/// ECDSA and Aumayr pre-signatures as they are exchanged, with their DLEQ proofs.
+fn dleq_pre_signatures(c: &mut Criterion) {
+    let n = SIZES[SIZES.len() - 1];
+    let messages: Vec<Vec<u8>> = (0..n).map(message).collect();
+    let (ecdsa_keys, ecdsa_statements, ecdsa_pre_signatures) =
+        pre_sign_many(&ECDSA::new(), &messages);
+    let ecdsa_items: Vec<PreSignatureItem<AdaptorStatement, Sigma_prime_ECDSA>> = (0..n)
+        .map(|i| PreSignatureItem {
+            P: &ecdsa_keys[i],
+            m: &messages[i],
+            T: &ecdsa_statements[i],
+            sigma_prime: &ecdsa_pre_signatures[i],
+        })
+        .collect();
+    let (aumayr_keys, aumayr_statements, aumayr_pre_signatures) =
+        pre_sign_many(&AumayrECDSA::new(), &messages);
+    let aumayr_items: Vec<PreSignatureItem<AdaptorStatement, Sigma_prime_Aumayr>> = (0..n)
+        .map(|i| PreSignatureItem {
+            P: &aumayr_keys[i],
+            m: &messages[i],
+            T: &aumayr_statements[i],
+            sigma_prime: &aumayr_pre_signatures[i],
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("verify_pre_sign_ecdsa");
+    group.sample_size(10);
+    for size in SIZES {
+        let items = &ecdsa_items[..size];
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("single", size), items, |b, items| {
+            b.iter(|| {
+                items.iter().all(|item| {
+                    ECDSA::new().verify_pre_sign(item.P, item.m, item.T, item.sigma_prime)
+                })
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", size), items, |b, items| {
+            b.iter(|| ECDSA::new().verify_pre_sign_batch(items, &mut OsRng))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("verify_pre_sign_aumayr");
+    group.sample_size(10);
+    for size in SIZES {
+        let items = &aumayr_items[..size];
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("single", size), items, |b, items| {
+            b.iter(|| {
+                items.iter().all(|item| {
+                    AumayrECDSA::new().verify_pre_sign(item.P, item.m, item.T, item.sigma_prime)
+                })
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", size), items, |b, items| {
+            b.iter(|| AumayrECDSA::new().verify_pre_sign_batch(items, &mut OsRng))
+        });
+    }
+    group.finish();
+}
+
+/// ECDSA pre-signatures of many messages under one statement.
+fn pre_signing(c: &mut Criterion) {
+    let n = SIZES[SIZES.len() - 1];
+    let messages: Vec<Vec<u8>> = (0..n).map(message).collect();
+    let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+    let requests: Vec<PreSignRequest<AdaptorStatement>> = messages
+        .iter()
+        .map(|m| PreSignRequest { m, T: &T })
+        .collect();
+    let (p, _) = ECDSA::new().keygen(&mut OsRng);
+
+    let mut group = c.benchmark_group("pre_sign");
+    group.sample_size(10);
+    for size in SIZES {
+        let requests = &requests[..size];
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("single", size), requests, |b, requests| {
+            b.iter(|| {
+                requests
+                    .iter()
+                    .map(|request| ECDSA::new().pre_sign(&p, request.m, request.T, None))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", size), requests, |b, requests| {
+            b.iter(|| ECDSA::new().pre_sign_batch(&p, requests, None))
+        });
+        #[cfg(feature = "parallel")]
+        group.bench_with_input(
+            BenchmarkId::new("parallel", size),
+            requests,
+            |b, requests| b.iter(|| ECDSA::new().pre_sign_batch_parallel(&p, requests, None, None)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    signatures,
+    pre_signatures,
+    dleq_pre_signatures,
+    pre_signing
+);
+criterion_main!(benches);
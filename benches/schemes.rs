@@ -0,0 +1,57 @@
+#![allow(non_snake_case)]
+
+use as_for_fde::{
+    AS_scheme, AdaptorWitness, AumayrECDSA, DiscreteLog, PointTable, Schnorr, Sign_scheme, ECDSA,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use k256::{elliptic_curve::ff::Field, Scalar};
+use rand_core::OsRng;
+
+const MESSAGE: &[u8] = b"transaction";
+
+/// Signs, verifies, pre-signs and verifies pre-signatures with `scheme`.
+fn operations<S: AS_scheme<Relation = DiscreteLog>>(c: &mut Criterion, name: &str, scheme: &S) {
+    let (p, P) = scheme.keygen(&mut OsRng);
+    let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+    let sigma = scheme.sign(&p, MESSAGE, None).unwrap();
+    let sigma_prime = scheme.pre_sign(&p, MESSAGE, &T, None).unwrap();
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("sign", |b| b.iter(|| scheme.sign(&p, MESSAGE, None)));
+    group.bench_function("verify_sign", |b| {
+        b.iter(|| scheme.verify_sign(&sigma, &P, MESSAGE))
+    });
+    group.bench_function("pre_sign", |b| {
+        b.iter(|| scheme.pre_sign(&p, MESSAGE, &T, None))
+    });
+    group.bench_function("verify_pre_sign", |b| {
+        b.iter(|| scheme.verify_pre_sign(&P, MESSAGE, &T, &sigma_prime))
+    });
+    group.finish();
+}
+
+fn schemes(c: &mut Criterion) {
+    operations(c, "schnorr", &Schnorr::new());
+    operations(c, "ecdsa", &ECDSA::new());
+    operations(c, "aumayr", &AumayrECDSA::new());
+}
+
+/// Pre-signs with a precomputed table of a statement `T`, against building the table once.
+fn tables(c: &mut Criterion) {
+    let (p, _) = ECDSA::new().keygen(&mut OsRng);
+    let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+    let table = PointTable::new(T.as_point());
+
+    let mut group = c.benchmark_group("point_table");
+    group.bench_function("new", |b| b.iter(|| PointTable::new(T.as_point())));
+    group.bench_function("ecdsa_pre_sign_with_table", |b| {
+        b.iter(|| ECDSA::new().pre_sign_with_table(&p, MESSAGE, &T, &table, None))
+    });
+    group.bench_function("aumayr_pre_sign_with_table", |b| {
+        b.iter(|| AumayrECDSA::new().pre_sign_with_table(&p, MESSAGE, &T, &table, None))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, schemes, tables);
+criterion_main!(benches);
@@ -0,0 +1,566 @@
+use k256::{
+    elliptic_curve::{ff::Field, group::Curve, group::GroupEncoding},
+    AffinePoint, ProjectivePoint, Scalar,
+};
+use rand_core::{CryptoRng, RngCore};
+use std::collections::HashMap;
+
+use crate::ciphersuite::Ciphersuite;
+use crate::ecdsa::{dleq_statement, pre_signature_context, pre_signature_dleq_challenge};
+use crate::msm::{msm_vartime, PointTable};
+use crate::sealed::KeyPair;
+use crate::utils::get_x;
+use crate::{ecdsa, ecdsa_aumayr};
+use crate::{
+    pre_sign_nonce, AS_scheme, AumayrECDSA, Error, HardRelation, PiCommitment, PublicKey, Schnorr,
+    SecretKey, Sigma, Sigma_prime, Sigma_prime_Aumayr, Sigma_prime_ECDSA, VerifyError, ECDSA, ZKP,
+};
+
+/// A signature to check with [`Schnorr::verify_sign_batch`], with the arguments of
+/// [`Sign_scheme::verify_sign`](crate::Sign_scheme::verify_sign).
+#[derive(Debug, Clone, Copy)]
+pub struct SignatureItem<'a> {
+    pub sigma: &'a Sigma,
+    pub P: &'a PublicKey,
+    pub m: &'a [u8],
+}
+
+/// A pre-signature to check with [`Schnorr::verify_pre_sign_batch`],
+/// [`ECDSA::verify_pre_sign_batch`] or [`AumayrECDSA::verify_pre_sign_batch`], with the
+/// arguments of [`AS_scheme::verify_pre_sign`].
+#[derive(Debug, Clone, Copy)]
+pub struct PreSignatureItem<'a, Statement, PreSignature = Sigma_prime> {
+    pub P: &'a PublicKey,
+    pub m: &'a [u8],
+    pub T: &'a Statement,
+    pub sigma_prime: &'a PreSignature,
+}
+
+/// A DLEQ proof in commitment form to check with [`ECDSA::verify_proof_batch`], with the
+/// arguments of [`ECDSA::verify_proof_committed`].
+#[derive(Debug, Clone, Copy)]
+pub struct ProofItem<'a> {
+    pub P: &'a ProjectivePoint,
+    pub Z: &'a ProjectivePoint,
+    pub T: &'a ProjectivePoint,
+    pub pi: &'a PiCommitment,
+    pub context: Option<&'a [u8]>,
+}
+
+/// A message to pre-sign with [`AS_scheme::pre_sign_batch`], locked to the statement `T`.
+#[derive(Debug, Clone, Copy)]
+pub struct PreSignRequest<'a, Statement> {
+    pub m: &'a [u8],
+    pub T: &'a Statement,
+}
+
+/// Indices of the rejected items of a batch, in increasing order, each with the reason it is
+/// rejected.
+pub type BatchFailures = Vec<(usize, VerifyError)>;
+
+/// Verification equation of one batch item, already multiplied by its random weight `a`:
+/// $g \cdot G + \sum_j k_j X_j = 0$.
+struct Equation<const N: usize> {
+    index: usize,
+    g: Scalar,
+    terms: [(ProjectivePoint, Scalar); N],
+}
+
+impl<const N: usize> Equation<N> {
+    /// Weights the equation $g \cdot G + \sum_j k_j X_j = 0$ of item `index` by `a`.
+    fn new(index: usize, a: &Scalar, g: Scalar, terms: [(ProjectivePoint, Scalar); N]) -> Self {
+        Self {
+            index,
+            g: g * a,
+            terms: terms.map(|(X, k)| (X, k * a)),
+        }
+    }
+}
+
+/// Checks that the weighted equations sum to zero with a single multi-scalar multiplication.
+fn holds<const N: usize>(equations: &[Equation<N>]) -> bool {
+    let mut terms = Vec::with_capacity(equations.len() * N + 1);
+    for equation in equations {
+        terms.extend_from_slice(&equation.terms); // add a_i k_ij X_ij
+    }
+    let g: Scalar = equations.iter().map(|equation| equation.g).sum();
+    terms.push((ProjectivePoint::GENERATOR, g)); // add (sum a_i g_i) G
+    msm_vartime(&terms) == ProjectivePoint::IDENTITY
+}
+
+/// Finds the equations that do not hold by bisection, rejecting their items with `reason`:
+/// a half whose combination holds is valid as a whole, so `k` invalid items out of `n` cost
+/// about $2k \log_2(n)$ checks.
+fn find_invalid<const N: usize>(
+    equations: &[Equation<N>],
+    reason: VerifyError,
+    failures: &mut BatchFailures,
+) {
+    if equations.is_empty() || holds(equations) {
+        return;
+    }
+    if let [equation] = equations {
+        failures.push((equation.index, reason));
+        return;
+    }
+    let (left, right) = equations.split_at(equations.len() / 2);
+    find_invalid(left, reason, failures);
+    find_invalid(right, reason, failures);
+}
+
+/// Verifies `equations` together, falling back to [`find_invalid`] when the combination
+/// does not hold, and merges the result with the items already rejected in `failures`.
+fn finish<const N: usize>(
+    equations: &[Equation<N>],
+    reason: VerifyError,
+    mut failures: BatchFailures,
+) -> Result<(), BatchFailures> {
+    find_invalid(equations, reason, &mut failures);
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        failures.sort_unstable_by_key(|(index, _)| *index);
+        Err(failures)
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> Schnorr<Rel, Suite> {
+    /// Verifies many signatures at once. The equations $sG = R + eP$ are multiplied by random
+    /// weights $a_i$ drawn from `rng` and summed, so the batch costs one multi-scalar
+    /// multiplication of $2n + 1$ terms instead of $n$ separate checks. A forged signature
+    /// passes only if it cancels out in the sum, which happens with probability at most
+    /// $2^{-255}$ over the weights.
+    ///
+    /// # Arguments
+    /// * `items` - Signatures with their public keys and messages
+    /// * `rng` - Source of the weights, which must be unpredictable to the signers
+    ///
+    /// # Returns
+    /// * `Result<(), BatchFailures>` - `Ok(())` if every signature is valid, otherwise the
+    ///   indices of the invalid ones with the reason each is rejected
+    pub fn verify_sign_batch<R: RngCore + CryptoRng>(
+        &self,
+        items: &[SignatureItem],
+        rng: &mut R,
+    ) -> Result<(), BatchFailures> {
+        let mut failures = BatchFailures::new();
+        let mut equations = Vec::with_capacity(items.len());
+        for (index, item) in items.iter().enumerate() {
+            let P = *item.P.as_point();
+            if P == ProjectivePoint::IDENTITY || item.sigma.R == ProjectivePoint::IDENTITY {
+                failures.push((index, VerifyError::IdentityPoint));
+                continue;
+            }
+            match self.hash_challenge(&item.sigma.R, item.P, item.m) {
+                // sG - R - eP = 0
+                Ok(e) => equations.push(Equation::new(
+                    index,
+                    &Scalar::random(&mut *rng),
+                    item.sigma.s,
+                    [(item.sigma.R, -Scalar::ONE), (P, -e)],
+                )),
+                Err(error) => failures.push((index, error.into())),
+            }
+        }
+        finish(&equations, VerifyError::EquationMismatch, failures)
+    }
+
+    /// Verifies many pre-signatures at once, combining the equations $s'G = R' - T + eP$ with
+    /// random weights into one multi-scalar multiplication of $3n + 1$ terms, see
+    /// [`Schnorr::verify_sign_batch`].
+    ///
+    /// # Arguments
+    /// * `items` - Pre-signatures with their public keys, messages and statements
+    /// * `rng` - Source of the weights, which must be unpredictable to the signers
+    ///
+    /// # Returns
+    /// * `Result<(), BatchFailures>` - `Ok(())` if every pre-signature is valid, otherwise the
+    ///   indices of the invalid ones with the reason each is rejected
+    pub fn verify_pre_sign_batch<R: RngCore + CryptoRng>(
+        &self,
+        items: &[PreSignatureItem<Rel::Statement>],
+        rng: &mut R,
+    ) -> Result<(), BatchFailures> {
+        let mut failures = BatchFailures::new();
+        let mut equations = Vec::with_capacity(items.len());
+        for (index, item) in items.iter().enumerate() {
+            let P = *item.P.as_point();
+            let R_prime = item.sigma_prime.R_prime;
+            if P == ProjectivePoint::IDENTITY || R_prime == ProjectivePoint::IDENTITY {
+                failures.push((index, VerifyError::IdentityPoint));
+                continue;
+            }
+            match self.hash_challenge(&R_prime, item.P, item.m) {
+                // s'G - R' + T - eP = 0
+                Ok(e) => equations.push(Equation::new(
+                    index,
+                    &Scalar::random(&mut *rng),
+                    item.sigma_prime.s_prime,
+                    [
+                        (R_prime, -Scalar::ONE),
+                        (Rel::adaptor_point(item.T), Scalar::ONE),
+                        (P, -e),
+                    ],
+                )),
+                Err(error) => failures.push((index, error.into())),
+            }
+        }
+        finish(&equations, VerifyError::EquationMismatch, failures)
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> ECDSA<Rel, Suite> {
+    /// Verifies many pre-signatures at once, with their DLEQ proofs. Each pre-signature gives
+    /// the equation $s'R' = eT + rZ$ with $r = x(R')$, and its proof `(J, J', i)` the
+    /// equations $iG = J + cP$ and $iT = J' + cZ$, with `c` recomputed from `J` and `J'`. The
+    /// proof equations are multiplied by random weights $b_i$ and $b'_i$, the sum of the three
+    /// by a random weight $a_i$, and all items are summed into one multi-scalar multiplication
+    /// of $6n + 1$ terms, see [`Schnorr::verify_sign_batch`].
+    ///
+    /// # Arguments
+    /// * `items` - Pre-signatures with their public keys, messages and statements
+    /// * `rng` - Source of the weights, which must be unpredictable to the signers
+    ///
+    /// # Returns
+    /// * `Result<(), BatchFailures>` - `Ok(())` if every pre-signature is valid, otherwise the
+    ///   indices of the invalid ones with the reason each is rejected, which is
+    ///   `VerifyError::EquationMismatch` for a pre-signature or proof that does not verify
+    pub fn verify_pre_sign_batch<R: RngCore + CryptoRng>(
+        &self,
+        items: &[PreSignatureItem<Rel::Statement, Sigma_prime_ECDSA>],
+        rng: &mut R,
+    ) -> Result<(), BatchFailures> {
+        let mut failures = BatchFailures::new();
+        let mut equations = Vec::with_capacity(items.len());
+        for (index, item) in items.iter().enumerate() {
+            match self.pre_sign_equation(index, item, rng) {
+                Ok(equation) => equations.push(equation),
+                Err(error) => failures.push((index, error)),
+            }
+        }
+        finish(&equations, VerifyError::EquationMismatch, failures)
+    }
+
+    /// Weighted equation $a(s'R' - eT - rZ + b(iG - J - cP) + b'(iT - J' - cZ)) = 0$ of
+    /// item `index`, or the reason it is rejected before the batch check.
+    fn pre_sign_equation<R: RngCore + CryptoRng>(
+        &self,
+        index: usize,
+        item: &PreSignatureItem<Rel::Statement, Sigma_prime_ECDSA>,
+        rng: &mut R,
+    ) -> Result<Equation<6>, VerifyError> {
+        let e = self.hash_message(item.m)?;
+        let (P, T) = (*item.P.as_point(), Rel::adaptor_point(item.T));
+        let Sigma_prime_ECDSA {
+            s_prime,
+            R_prime,
+            Z,
+            pi,
+        } = item.sigma_prime;
+        if P == ProjectivePoint::IDENTITY
+            || *R_prime == ProjectivePoint::IDENTITY
+            || *Z == ProjectivePoint::IDENTITY
+        {
+            return Err(VerifyError::IdentityPoint);
+        }
+        if bool::from(s_prime.is_zero()) {
+            return Err(VerifyError::ZeroScalar);
+        }
+        let r = get_x(R_prime)?;
+        let context = pre_signature_context(&e, R_prime);
+        let c = pre_signature_dleq_challenge(
+            ecdsa::DLEQ_LABEL,
+            &dleq_statement(&P, Z, &T),
+            pi,
+            Some(&context),
+        );
+        let (b, b_prime) = (Scalar::random(&mut *rng), Scalar::random(&mut *rng));
+        Ok(Equation::new(
+            index,
+            &Scalar::random(&mut *rng),
+            b * pi.i,
+            [
+                (*R_prime, *s_prime),
+                (T, b_prime * pi.i - e),
+                (*Z, -r - b_prime * c),
+                (pi.J, -b),
+                (P, -b * c),
+                (pi.J_prime, -b_prime),
+            ],
+        ))
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> AumayrECDSA<Rel, Suite> {
+    /// Verifies many pre-signatures at once, with their DLEQ proofs. Each pre-signature gives
+    /// the equation $s'K = eG + rP$ with $r = x(R')$, and its proof `(J, J', i)` the
+    /// equations $iG = J + cK$ and $iT = J' + cR'$, combined and summed into one multi-scalar
+    /// multiplication of $6n + 1$ terms, see [`ECDSA::verify_pre_sign_batch`].
+    ///
+    /// # Arguments
+    /// * `items` - Pre-signatures with their public keys, messages and statements
+    /// * `rng` - Source of the weights, which must be unpredictable to the signers
+    ///
+    /// # Returns
+    /// * `Result<(), BatchFailures>` - `Ok(())` if every pre-signature is valid, otherwise the
+    ///   indices of the invalid ones with the reason each is rejected, which is
+    ///   `VerifyError::EquationMismatch` for a pre-signature or proof that does not verify
+    pub fn verify_pre_sign_batch<R: RngCore + CryptoRng>(
+        &self,
+        items: &[PreSignatureItem<Rel::Statement, Sigma_prime_Aumayr>],
+        rng: &mut R,
+    ) -> Result<(), BatchFailures> {
+        let mut failures = BatchFailures::new();
+        let mut equations = Vec::with_capacity(items.len());
+        for (index, item) in items.iter().enumerate() {
+            match self.pre_sign_equation(index, item, rng) {
+                Ok(equation) => equations.push(equation),
+                Err(error) => failures.push((index, error)),
+            }
+        }
+        finish(&equations, VerifyError::EquationMismatch, failures)
+    }
+
+    /// Weighted equation $a(s'K - eG - rP + b(iG - J - cK) + b'(iT - J' - cR')) = 0$ of
+    /// item `index`, or the reason it is rejected before the batch check.
+    fn pre_sign_equation<R: RngCore + CryptoRng>(
+        &self,
+        index: usize,
+        item: &PreSignatureItem<Rel::Statement, Sigma_prime_Aumayr>,
+        rng: &mut R,
+    ) -> Result<Equation<6>, VerifyError> {
+        let e = Self::ECDSA.hash_message(item.m)?;
+        let (P, T) = (*item.P.as_point(), Rel::adaptor_point(item.T));
+        let Sigma_prime_Aumayr {
+            s_prime,
+            R_prime,
+            K,
+            pi,
+        } = item.sigma_prime;
+        if P == ProjectivePoint::IDENTITY
+            || *K == ProjectivePoint::IDENTITY
+            || *R_prime == ProjectivePoint::IDENTITY
+        {
+            return Err(VerifyError::IdentityPoint);
+        }
+        if bool::from(s_prime.is_zero()) {
+            return Err(VerifyError::ZeroScalar);
+        }
+        let r = get_x(R_prime)?;
+        let context = pre_signature_context(&e, R_prime);
+        let c = pre_signature_dleq_challenge(
+            ecdsa_aumayr::DLEQ_LABEL,
+            &dleq_statement(K, R_prime, &T),
+            pi,
+            Some(&context),
+        );
+        let (b, b_prime) = (Scalar::random(&mut *rng), Scalar::random(&mut *rng));
+        Ok(Equation::new(
+            index,
+            &Scalar::random(&mut *rng),
+            b * pi.i - e,
+            [
+                (*K, *s_prime - b * c),
+                (P, -r),
+                (pi.J, -b),
+                (T, b_prime * pi.i),
+                (pi.J_prime, -b_prime),
+                (*R_prime, -b_prime * c),
+            ],
+        ))
+    }
+}
+
+impl<Rel, Suite> ECDSA<Rel, Suite> {
+    /// Verifies many DLEQ proofs in commitment form at once. Each proof `(J, J', i)` gives
+    /// the equations $iG = J + eP$ and $iT = J' + eZ$, with `e` recomputed from `J` and `J'`.
+    /// The second equation is multiplied by a random weight $b_i$, the sum of both by a
+    /// random weight $a_i$, and all items are summed into one multi-scalar multiplication of
+    /// $5n + 1$ terms, see [`Schnorr::verify_sign_batch`]. Proofs `(e, i)` hash their
+    /// commitment into `e` and cannot be summed, so the batch takes the proofs of
+    /// [`ECDSA::gen_proof_committed`].
+    ///
+    /// # Arguments
+    /// * `items` - Proofs with their statements and session contexts
+    /// * `rng` - Source of the weights, which must be unpredictable to the provers
+    ///
+    /// # Returns
+    /// * `Result<(), BatchFailures>` - `Ok(())` if every proof is valid, otherwise the indices
+    ///   of the invalid ones with `VerifyError::InvalidProof`
+    pub fn verify_proof_batch<R: RngCore + CryptoRng>(
+        &self,
+        items: &[ProofItem],
+        rng: &mut R,
+    ) -> Result<(), BatchFailures> {
+        let equations: Vec<Equation<5>> = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let pi = item.pi;
+                let e = self.compute_challenge(
+                    item.P,
+                    item.Z,
+                    item.T,
+                    &pi.J,
+                    &pi.J_prime,
+                    item.context,
+                );
+                let b = Scalar::random(&mut *rng);
+                // iG - J - eP + b (iT - J' - eZ) = 0
+                Equation::new(
+                    index,
+                    &Scalar::random(&mut *rng),
+                    pi.i,
+                    [
+                        (pi.J, -Scalar::ONE),
+                        (*item.P, -e),
+                        (*item.T, b * pi.i),
+                        (pi.J_prime, -b),
+                        (*item.Z, -b * e),
+                    ],
+                )
+            })
+            .collect();
+        finish(&equations, VerifyError::InvalidProof, BatchFailures::new())
+    }
+}
+
+/// Statements used by at least this many requests of a pre-signing batch get a
+/// [`PointTable`]. A table costs about as much as the multiplications by `T` it saves in two
+/// or three ECDSA pre-signatures.
+const TABLE_MIN_USES: usize = 3;
+
+/// Work shared by the requests of a pre-signing batch: the public key, the adaptor points of
+/// the statements and their tables.
+struct PreSignBatch<'a, S: AS_scheme + ?Sized> {
+    scheme: &'a S,
+    key: KeyPair<'a>,
+    aux_rand: Option<&'a [u8; 32]>,
+    points: Vec<ProjectivePoint>,
+    tables: Vec<PointTable>,
+    table_of: Vec<Option<usize>>,
+}
+
+impl<'a, S: AS_scheme + ?Sized> PreSignBatch<'a, S> {
+    /// Computes the public key of `p`, and a table for each statement used by at least
+    /// [`TABLE_MIN_USES`] requests if the scheme uses tables.
+    fn new(
+        scheme: &'a S,
+        p: &'a SecretKey,
+        requests: &[PreSignRequest<<S::Relation as HardRelation>::Statement>],
+        aux_rand: Option<&'a [u8; 32]>,
+    ) -> Self {
+        let points: Vec<ProjectivePoint> = requests
+            .iter()
+            .map(|request| S::Relation::adaptor_point(request.T))
+            .collect();
+        // group the requests by statement, normalizing all points with one inversion; the
+        // identity has no affine form and gets no table, and `batch_normalize` takes neither
+        // it nor an empty slice
+        let finite: Vec<ProjectivePoint> = points
+            .iter()
+            .filter(|T| **T != ProjectivePoint::IDENTITY)
+            .copied()
+            .collect();
+        let mut affine = vec![AffinePoint::IDENTITY; finite.len()];
+        if !finite.is_empty() {
+            ProjectivePoint::batch_normalize(&finite, &mut affine);
+        }
+        let mut affine = affine.iter();
+        let mut uses: HashMap<_, Vec<usize>> = HashMap::new();
+        for (index, T) in points.iter().enumerate() {
+            if *T != ProjectivePoint::IDENTITY {
+                let T = affine
+                    .next()
+                    .expect("one affine point per finite statement");
+                uses.entry(T.to_bytes()).or_default().push(index);
+            }
+        }
+
+        let mut tables = Vec::new();
+        let mut table_of = vec![None; requests.len()];
+        for indices in uses
+            .values()
+            .filter(|indices| indices.len() >= TABLE_MIN_USES)
+        {
+            if let Some(table) = scheme.statement_table(requests[indices[0]].T) {
+                for &index in indices {
+                    table_of[index] = Some(tables.len());
+                }
+                tables.push(table);
+            }
+        }
+        Self {
+            scheme,
+            key: KeyPair::new(p),
+            aux_rand,
+            points,
+            tables,
+            table_of,
+        }
+    }
+
+    /// Pre-signs request `index` with the nonce [`AS_scheme::pre_sign`] derives for it.
+    fn pre_sign(
+        &self,
+        index: usize,
+        request: &PreSignRequest<<S::Relation as HardRelation>::Statement>,
+    ) -> Result<S::PreSignature, Error> {
+        let r_prime = pre_sign_nonce(
+            self.scheme,
+            self.key.p,
+            request.m,
+            &self.points[index],
+            self.aux_rand,
+        );
+        let table = self.table_of[index].map(|table| &self.tables[table]);
+        self.scheme
+            .pre_sign_with_key(&self.key, request.m, request.T, &r_prime, table)
+    }
+}
+
+/// Pre-signs `requests` one after the other, see [`AS_scheme::pre_sign_batch`].
+pub(crate) fn pre_sign_batch<S: AS_scheme + ?Sized>(
+    scheme: &S,
+    p: &SecretKey,
+    requests: &[PreSignRequest<<S::Relation as HardRelation>::Statement>],
+    aux_rand: Option<&[u8; 32]>,
+) -> Vec<Result<S::PreSignature, Error>> {
+    let batch = PreSignBatch::new(scheme, p, requests, aux_rand);
+    requests
+        .iter()
+        .enumerate()
+        .map(|(index, request)| batch.pre_sign(index, request))
+        .collect()
+}
+
+/// Pre-signs `requests` on `pool`, or on the global pool, see
+/// [`AS_scheme::pre_sign_batch_parallel`].
+#[cfg(feature = "parallel")]
+pub(crate) fn pre_sign_batch_parallel<S>(
+    scheme: &S,
+    p: &SecretKey,
+    requests: &[PreSignRequest<<S::Relation as HardRelation>::Statement>],
+    aux_rand: Option<&[u8; 32]>,
+    pool: Option<&rayon::ThreadPool>,
+) -> Vec<Result<S::PreSignature, Error>>
+where
+    S: AS_scheme + Sync + ?Sized,
+    S::PreSignature: Send,
+    <S::Relation as HardRelation>::Statement: Sync,
+{
+    use rayon::prelude::*;
+
+    let run = || {
+        let batch = PreSignBatch::new(scheme, p, requests, aux_rand);
+        requests
+            .par_iter()
+            .enumerate()
+            .map(|(index, request)| batch.pre_sign(index, request))
+            .collect() // in input order
+    };
+    match pool {
+        Some(pool) => pool.install(run),
+        None => run(),
+    }
+}
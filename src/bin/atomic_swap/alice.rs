@@ -1,51 +1,59 @@
-use as_for_fde::{AS_scheme, Sigma, Sigma_prime, Scheme, Sign_scheme};
-use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
-use rand_core::OsRng;
+use as_for_fde::{
+    AS_scheme, AdaptorStatement, AdaptorWitness, Error, NonceManager, NonceManagerError, PoK,
+    PublicKey, Scheme, SchemePreSignature, SchemeSignature, SecretKey, Sign_scheme, VerifyError,
+};
+use rand_core::{OsRng, RngCore};
 
 /// `Alice` represents a party in an atomic swap protocol.
 /// She holds a secret key `sk`, a temporary scalar `t`, and uses a selected signature `Scheme`.
 pub struct Alice {
     /// Secret key
-    sk: Scalar,
+    sk: SecretKey,
     /// Public key (derived from `sk`)
-    pub pk: ProjectivePoint,
+    pub pk: PublicKey,
     /// Temporary secret used for adaptor signing
-    t: Scalar,
+    t: AdaptorWitness,
     /// Public counterpart of `t`
-    pub T: ProjectivePoint,
+    pub T: AdaptorStatement,
+    /// Proof that Alice knows `t`, checked by Bob before he pre-signs against `T`
+    pub pi_T: PoK,
     /// The cryptographic signing scheme used (e.g., Schnorr or ECDSA)
     scheme: Scheme,
+    /// Store of the pre-signatures Alice has handed out
+    nonces: NonceManager,
 }
 
 impl Alice {
     /// Constructs a new `Alice` instance by randomly generating her secret key `sk` and adaptor secret `t`.  
-    /// It also computes the corresponding public keys `pk` and `T`, and stores the chosen signature scheme.
+    /// It also computes the corresponding public keys `pk` and `T`, a proof of knowledge of `t`, and stores the chosen signature scheme.
     ///
     /// # Arguments
     ///
     /// * `scheme` - The cryptographic signature scheme to use.
+    /// * `nonces` - The store of the pre-signatures she hands out.
     ///
     /// # Returns
     ///
     /// * A new `Alice` instance.
-    pub fn new(scheme: Scheme) -> Self {
-        let sk = Scalar::random(&mut OsRng);
-        let pk = ProjectivePoint::GENERATOR * sk;
-
-        let t = Scalar::random(&mut OsRng);
-        let T = ProjectivePoint::GENERATOR * t;
+    pub fn new(scheme: Scheme, nonces: NonceManager) -> Self {
+        let (sk, pk) = scheme.keygen(&mut OsRng);
+        let (t, T, pi_T) = scheme.gen_statement(&mut OsRng, true);
+        let pi_T = pi_T.expect("gen_statement returns a proof when asked for one");
 
         Self {
             sk,
             pk,
             t,
             T,
+            pi_T,
             scheme,
+            nonces,
         }
     }
 
-    /// Generates a pre-signature (`Sigma_prime`) for a given transaction `tx`.  
-    /// Returns the pre-signature and the corresponding tweak point `T`.
+    /// Generates a pre-signature (`SchemePreSignature`) for a given transaction `tx`.  
+    /// Returns the pre-signature and the corresponding tweak point `T`. Asked again for the same
+    /// `tx`, e.g. after a restart, she returns the pre-signature she already handed out.
     ///
     /// # Arguments
     ///
@@ -54,12 +62,19 @@ impl Alice {
     /// # Returns
     ///
     /// * A tuple containing:
-    ///   - The pre-signature (`Sigma_prime`)
+    ///   - The pre-signature (`SchemePreSignature`)
     ///   - The tweak point `T`
-    pub fn generate_presig(&self, tx: &str) -> (Sigma_prime, ProjectivePoint) {
-        let r_prime = Scalar::random(&mut OsRng);
-        let sigma_prime = self.scheme.pre_sign(&self.sk, tx, &self.T, &r_prime);
-        (sigma_prime, self.T)
+    /// * A `NonceManagerError` if pre-signing fails or the nonce store cannot be written.
+    pub fn generate_presig(
+        &mut self,
+        tx: &[u8],
+    ) -> Result<(SchemePreSignature, AdaptorStatement), NonceManagerError> {
+        let mut aux_rand = [0u8; 32];
+        OsRng.fill_bytes(&mut aux_rand);
+        let sigma_prime =
+            self.nonces
+                .pre_sign(&self.scheme, &self.sk, tx, &self.T, Some(&aux_rand))?;
+        Ok((sigma_prime, self.T))
     }
 
     /// Verifies a pre-signature against a provided public key and transaction.
@@ -72,9 +87,15 @@ impl Alice {
     ///
     /// # Returns
     ///
-    /// * `true` if the pre-signature is valid; `false` otherwise.
-    pub fn verify_presig(&self, sigma_prime: &Sigma_prime, pk: &ProjectivePoint, tx: &str) -> bool {
-        self.scheme.verify_pre_sign(pk, tx, &self.T, sigma_prime)
+    /// * `Ok(())` if the pre-signature is valid; otherwise the `VerifyError` explaining why it was rejected.
+    pub fn verify_presig(
+        &self,
+        sigma_prime: &SchemePreSignature,
+        pk: &PublicKey,
+        tx: &[u8],
+    ) -> Result<(), VerifyError> {
+        self.scheme
+            .verify_pre_sign_detailed(pk, tx, &self.T, sigma_prime)
     }
 
     /// Generates Alice's full signature for a transaction, and adapts a pre-signature from Bob
@@ -88,13 +109,19 @@ impl Alice {
     /// # Returns
     ///
     /// * A tuple containing:
-    ///   - Alice’s full signature (`Sigma`)
-    ///   - The adapted full signature for Bob (`Sigma`)
-    pub fn generate_sig_and_adapt(&self, tx: &str, sigma_prime_b: &Sigma_prime) -> (Sigma, Sigma) {
-        let r_a = Scalar::random(&mut OsRng);
-        let sigma_a = self.scheme.sign(&self.sk, tx, &r_a);
-        let sigma_b = self.scheme.adapt_signature(sigma_prime_b, &self.t);
+    ///   - Alice’s full signature (`SchemeSignature`)
+    ///   - The adapted full signature for Bob (`SchemeSignature`)
+    /// * An `Error` if signing or adapting fails.
+    pub fn generate_sig_and_adapt(
+        &self,
+        tx: &[u8],
+        sigma_prime_b: &SchemePreSignature,
+    ) -> Result<(SchemeSignature, SchemeSignature), Error> {
+        let mut aux_rand = [0u8; 32];
+        OsRng.fill_bytes(&mut aux_rand);
+        let sigma_a = self.scheme.sign(&self.sk, tx, Some(&aux_rand))?;
+        let sigma_b = self.scheme.adapt_signature(sigma_prime_b, &self.t)?;
 
-        (sigma_a, sigma_b)
+        Ok((sigma_a, sigma_b))
     }
 }
@@ -1,16 +1,22 @@
-use as_for_fde::{AS_scheme, Sigma, Sigma_prime, Scheme, Sign_scheme};
-use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
-use rand_core::OsRng;
+use crate::common::Rejected;
+use as_for_fde::{
+    AS_scheme, AdaptorStatement, AdaptorWitness, Error, NonceManager, NonceManagerError, PoK,
+    PublicKey, Scheme, SchemePreSignature, SchemeSignature, SecretKey, Sign_scheme, VerifyError,
+};
+use rand_core::{OsRng, RngCore};
+use std::path::Path;
 
 /// `Bob` represents a party in an atomic swap protocol.
 /// He holds a secret key `sk`, and uses a selected signature `Scheme`.
 pub struct Bob {
     /// Secret key
-    sk: Scalar,
+    sk: SecretKey,
     /// Public key (derived from `sk`)
-    pub pk: ProjectivePoint,
+    pub pk: PublicKey,
     /// The cryptographic signing scheme used (e.g., Schnorr or ECDSA)
     scheme: Scheme,
+    /// Store of the pre-signatures Bob has handed out
+    nonces: NonceManager,
 }
 
 impl Bob {
@@ -19,30 +25,77 @@ impl Bob {
     /// # Arguments
     ///
     /// * `scheme` - The signing scheme to be used (e.g., `Scheme::Schnorr`, `Scheme::ECDSA`)
+    /// * `nonces` - The store of the pre-signatures he hands out.
     ///
     /// # Returns
     ///
     /// * A new `Bob` instance with generated keys and the specified scheme.
-    pub fn new(scheme: Scheme) -> Self {
-        let sk = Scalar::random(&mut OsRng);
-        let pk = ProjectivePoint::GENERATOR * sk;
-        Self { sk, pk, scheme }
+    pub fn new(scheme: Scheme, nonces: NonceManager) -> Self {
+        let (sk, pk) = scheme.keygen(&mut OsRng);
+        Self {
+            sk,
+            pk,
+            scheme,
+            nonces,
+        }
     }
 
-    /// Generates a pre-signature `Sigma'` using Bob's secret key.
+    /// Simulates a restart of Bob after a crash: the store of the pre-signatures he
+    /// handed out is closed and opened again from `path`. The key is kept, as a real party
+    /// would load it from its own storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the store.
+    ///
+    /// # Returns
+    ///
+    /// * The restarted `Bob`, or a `NonceManagerError` if the store cannot be opened.
+    pub fn restart(self, path: &Path) -> Result<Self, NonceManagerError> {
+        let Self {
+            sk,
+            pk,
+            scheme,
+            nonces,
+        } = self;
+        drop(nonces); // releases the lock on the store
+        Ok(Self {
+            sk,
+            pk,
+            scheme,
+            nonces: NonceManager::open(path)?,
+        })
+    }
+
+    /// Generates a pre-signature `Sigma'` using Bob's secret key, after checking that Alice
+    /// knows the discrete log of `T`. Asked again for the same `tx` and `T`, he returns the
+    /// pre-signature he already handed out.
     ///
     /// # Arguments
     ///
     /// * `tx` - A string representing the transaction or message to be signed.
     /// * `T` - A public tweak point involved in adaptor signing.
+    /// * `pi_T` - Alice's proof of knowledge of `t`.
     ///
     /// # Returns
     ///
-    /// * A `Sigma_prime` representing the pre-signature.
-    pub fn generate_presig(&self, tx: &str, T: &ProjectivePoint) -> Sigma_prime {
-        let r_prime = Scalar::random(&mut OsRng);
-        let sigma_prime = self.scheme.pre_sign(&self.sk, tx, T, &r_prime);
-        sigma_prime
+    /// * A `SchemePreSignature` representing the pre-signature, a `Rejected` error with the
+    ///   `VerifyError` of `pi_T` if it does not verify, or a `NonceManagerError` if pre-signing
+    ///   fails or the nonce store cannot be written.
+    pub fn generate_presig(
+        &mut self,
+        tx: &[u8],
+        T: &AdaptorStatement,
+        pi_T: &PoK,
+    ) -> Result<SchemePreSignature, Box<dyn std::error::Error>> {
+        self.scheme.verify_statement(T, pi_T).map_err(|reason| {
+            Rejected::new("Bob rejected Alice's proof of knowledge of t", reason)
+        })?;
+        let mut aux_rand = [0u8; 32];
+        OsRng.fill_bytes(&mut aux_rand);
+        Ok(self
+            .nonces
+            .pre_sign(&self.scheme, &self.sk, tx, T, Some(&aux_rand))?)
     }
 
     /// Verifies a given pre-signature against the expected public key and message.
@@ -56,15 +109,15 @@ impl Bob {
     ///
     /// # Returns
     ///
-    /// * `true` if the pre-signature is valid; `false` otherwise.
+    /// * `Ok(())` if the pre-signature is valid; otherwise the `VerifyError` explaining why it was rejected.
     pub fn verify_presig(
         &self,
-        sigma_prime: &Sigma_prime,
-        pk: &ProjectivePoint,
-        tx: &str,
-        T: &ProjectivePoint,
-    ) -> bool {
-        self.scheme.verify_pre_sign(pk, tx, T, sigma_prime)
+        sigma_prime: &SchemePreSignature,
+        pk: &PublicKey,
+        tx: &[u8],
+        T: &AdaptorStatement,
+    ) -> Result<(), VerifyError> {
+        self.scheme.verify_pre_sign_detailed(pk, tx, T, sigma_prime)
     }
 
     /// Verifies two full signatures over the same message.
@@ -78,17 +131,16 @@ impl Bob {
     ///
     /// # Returns
     ///
-    /// * `true` if both signatures are valid; `false` otherwise.
+    /// * `Ok(())` if both signatures are valid; otherwise the `VerifyError` of the first invalid one.
     pub fn verify_sign(
         &self,
-        a_pk: &ProjectivePoint,
-        tx: &str,
-        sigma_a: &Sigma,
-        sigma_b: &Sigma,
-    ) -> bool {
-        let is_a_correct = self.scheme.verify_sign(sigma_a, a_pk, tx);
-        let is_b_correct = self.scheme.verify_sign(sigma_b, &self.pk, tx);
-        is_a_correct && is_b_correct
+        a_pk: &PublicKey,
+        tx: &[u8],
+        sigma_a: &SchemeSignature,
+        sigma_b: &SchemeSignature,
+    ) -> Result<(), VerifyError> {
+        self.scheme.verify_sign_detailed(sigma_a, a_pk, tx)?;
+        self.scheme.verify_sign_detailed(sigma_b, &self.pk, tx)
     }
 
     /// Extracts the secret witness value `t` from a full signature and its corresponding pre-signature.
@@ -97,13 +149,18 @@ impl Bob {
     ///
     /// * `sigma` - The full signature.
     /// * `sigma_prime` - The pre-signature.
+    /// * `T` - The tweak point the witness must correspond to.
     ///
     /// # Returns
     ///
-    /// * The extracted `Scalar` witness value `t`.
-    pub fn extract_secret(&self, sigma: &Sigma, sigma_prime: &Sigma_prime) -> Scalar {
-        let t = self.scheme.extract_witness(sigma, sigma_prime);
-        t
+    /// * The extracted `AdaptorWitness` `t`, or an `Error` if it does not match `T`.
+    pub fn extract_secret(
+        &self,
+        sigma: &SchemeSignature,
+        sigma_prime: &SchemePreSignature,
+        T: &AdaptorStatement,
+    ) -> Result<AdaptorWitness, Error> {
+        self.scheme.extract_witness(sigma, sigma_prime, T)
     }
 
     /// Generates Bob’s full signature and adapts Alice’s pre-signature using a shared witness.
@@ -117,18 +174,20 @@ impl Bob {
     /// # Returns
     ///
     /// * A tuple containing:
-    ///     - Alice’s adapted full signature (`Sigma`)
-    ///     - Bob’s newly generated full signature (`Sigma`)
+    ///     - Alice’s adapted full signature (`SchemeSignature`)
+    ///     - Bob’s newly generated full signature (`SchemeSignature`)
+    /// * An `Error` if signing or adapting fails.
     pub fn generate_sig_and_adapt(
         &self,
-        tx: &str,
-        sigma_prime_a: &Sigma_prime,
-        t: &Scalar,
-    ) -> (Sigma, Sigma) {
-        let r_b = Scalar::random(&mut OsRng);
-        let sigma_a = self.scheme.adapt_signature(sigma_prime_a, t);
-        let sigma_b = self.scheme.sign(&self.sk, tx, &r_b);
+        tx: &[u8],
+        sigma_prime_a: &SchemePreSignature,
+        t: &AdaptorWitness,
+    ) -> Result<(SchemeSignature, SchemeSignature), Error> {
+        let mut aux_rand = [0u8; 32];
+        OsRng.fill_bytes(&mut aux_rand);
+        let sigma_a = self.scheme.adapt_signature(sigma_prime_a, t)?;
+        let sigma_b = self.scheme.sign(&self.sk, tx, Some(&aux_rand))?;
 
-        (sigma_a, sigma_b)
+        Ok((sigma_a, sigma_b))
     }
 }
@@ -3,53 +3,124 @@
 
 mod alice;
 mod bob;
+#[path = "../common/mod.rs"]
+mod common;
 use alice::Alice;
-use as_for_fde::{Scheme, Schnorr, ECDSA};
+use as_for_fde::{AS_scheme, Error, NonceManager, NonceReuse, Observation, Scheme, Sign_scheme};
 use bob::Bob;
+use common::{parse_scheme, same_pre_signature, NonceDir, Rejected};
+use k256::{elliptic_curve::ff::Field, Scalar};
+use rand_core::OsRng;
 use std::env;
 
+/// Shows why pre-signing needs fresh nonces: a party that pre-signs two transactions with the
+/// same nonce, e.g. after restarting with a fixed one, hands out its secret key. The nonce
+/// reuse scan of the library recovers it from the two pre-signatures.
+fn demonstrate_nonce_reuse(scheme: &Scheme) -> Result<(), Error> {
+    let (p, P) = scheme.keygen(&mut OsRng);
+    let (_, T, _) = scheme.gen_statement(&mut OsRng, false);
+    let k = Scalar::random(&mut OsRng);
+    let (m1, m2): (&[u8], &[u8]) = (b"Transaction id 1 :)", b"Transaction id 2 :)");
+
+    // the schemes have their own pre-signature types, so each arm scans its own corpus
+    macro_rules! scan {
+        ($scheme:expr) => {{
+            let first = $scheme.pre_sign_with_nonce(&p, m1, &T, &k)?;
+            let second = $scheme.pre_sign_with_nonce(&p, m2, &T, &k)?;
+            $scheme.find_nonce_reuse(&[
+                Observation::PreSignature {
+                    sigma_prime: &first,
+                    P: &P,
+                    m: m1,
+                    T: &T,
+                },
+                Observation::PreSignature {
+                    sigma_prime: &second,
+                    P: &P,
+                    m: m2,
+                    T: &T,
+                },
+            ])
+        }};
+    }
+    let findings: Vec<NonceReuse> = match scheme {
+        Scheme::Schnorr(schnorr) => scan!(schnorr),
+        Scheme::BIP340(bip340) => scan!(bip340),
+        Scheme::ECDSA(ecdsa) => scan!(ecdsa),
+        Scheme::AumayrECDSA(aumayr) => scan!(aumayr),
+    };
+    match findings.first().and_then(|finding| finding.secret_key.as_ref()) {
+        Some(recovered) if *recovered == p => println!(
+            "Two pre-signatures with a reused nonce were flagged, and the secret key was recovered from them."
+        ),
+        _ => println!("The pre-signatures with a reused nonce were not flagged."),
+    }
+    Ok(())
+}
+
 /// Schematic implementation of the steps of a two party atomic swap protocol.
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     // === Step 0: Set to chosen scheme ===
     let args: Vec<String> = env::args().collect();
     let input = args.get(1).map(String::as_str).unwrap_or("schnorr");
 
-    let scheme: Scheme = match input {
-        "schnorr" => Scheme::Schnorr(Schnorr {}),
-        "ecdsa" => Scheme::ECDSA(ECDSA {}),
-        _ => {
-            eprintln!("Please input a valid scheme: [\"schnorr\", \"ecdsa\"]");
-            std::process::exit(1);
-        }
-    };
+    let scheme = parse_scheme(input)?;
     println!("The protocol will run using : {}", input);
 
     // === Step 1: Setup ===
-    let alice = Alice::new(scheme.clone());
-    let bob = Bob::new(scheme);
+    // the pre-signatures each party hands out are kept in the directory given as second
+    // argument or in AS_FOR_FDE_NONCE_DIR, else in one for this run only, so a restarted
+    // party re-sends the same pre-signature instead of one with a fresh nonce
+    let dir = NonceDir::new(args.get(2).map(String::as_str))?;
+    let (alice_nonces, bob_nonces) = (dir.join("alice_nonces"), dir.join("bob_nonces"));
+    let mut alice = Alice::new(scheme.clone(), NonceManager::open(&alice_nonces)?);
+    let mut bob = Bob::new(scheme.clone(), NonceManager::open(&bob_nonces)?);
 
     // === Step 2: Alice creates tx_2, and generates a pre-signature on it ===
-    let tx2 = "Transaction id 2 :)";
-    let (sigma_prime_a2, T) = alice.generate_presig(&tx2);
+    let tx2: &[u8] = b"Transaction id 2 :)";
+    let (sigma_prime_a2, T) = alice.generate_presig(tx2)?;
 
-    println!("Alice generated tx2 and sent her pre-signature, on tx2, as well as T to Bob.");
+    println!(
+        "Alice generated tx2 and sent her pre-signature, on tx2, as well as T and a proof of knowledge of t to Bob."
+    );
 
     // === Step 3: Bob verifies Alice's presignature  ===
-    assert!(bob.verify_presig(&sigma_prime_a2, &alice.pk, &tx2, &T));
+    bob.verify_presig(&sigma_prime_a2, &alice.pk, tx2, &T)
+        .map_err(|reason| Rejected::new("Bob rejected Alice's pre-signature", reason))?;
     //Bob creates tx1 and a pre-signature on it
-    let tx1 = "Transaction id 1 :)";
-    let sigma_prime_b1 = bob.generate_presig(&tx1, &T);
-    println!("Bob generated tx1 and sent his pre-signature on it to Alice.");
+    let tx1: &[u8] = b"Transaction id 1 :)";
+    let sigma_prime_b1 = bob.generate_presig(tx1, &T, &alice.pi_T)?;
+    println!("Bob checked Alice's proof, generated tx1 and sent his pre-signature on it to Alice.");
+
+    // Bob crashes before he learns that his pre-signature arrived, and pre-signs tx1 again
+    // after restarting: his store gives back the pre-signature he already sent
+    bob = bob.restart(&bob_nonces)?;
+    let resent = bob.generate_presig(tx1, &T, &alice.pi_T)?;
+    if !same_pre_signature(&scheme, &resent, &sigma_prime_b1) {
+        return Err("Bob sent a second pre-signature on tx1 after restarting".into());
+    }
+    println!(
+        "Bob restarted and re-sent the same pre-signature on tx1, read back from his nonce store."
+    );
 
     // === Step 4: Alice verifies s'_b1 and generates s_a1, s_b1 ===
-    assert!(alice.verify_presig(&sigma_prime_b1, &bob.pk, &tx1));
-    let (sigma_a1, sigma_b1) = alice.generate_sig_and_adapt(&tx1, &sigma_prime_b1);
+    alice
+        .verify_presig(&sigma_prime_b1, &bob.pk, tx1)
+        .map_err(|reason| Rejected::new("Alice rejected Bob's pre-signature", reason))?;
+    let (sigma_a1, sigma_b1) = alice.generate_sig_and_adapt(tx1, &sigma_prime_b1)?;
     println!("Alice verified pre-sig and broadcasted s_a1, s_b1.");
 
     // === Step 5: Bob verifies broadcasted signatures, extracts secret and generates s_a2, s_b2 ===
-    assert!(bob.verify_sign(&alice.pk, &tx1, &sigma_a1, &sigma_b1));
+    bob.verify_sign(&alice.pk, tx1, &sigma_a1, &sigma_b1)
+        .map_err(|reason| {
+            Rejected::new("Bob rejected the signatures broadcasted by Alice", reason)
+        })?;
     println!("Bob verified signatures broadcasted by Alice");
-    let t = bob.extract_secret(&sigma_b1, &sigma_prime_b1);
-    let (_sigma_a2, _sigma_b2) = bob.generate_sig_and_adapt(&tx2, &sigma_prime_a2, &t);
+    let t = bob.extract_secret(&sigma_b1, &sigma_prime_b1, &T)?;
+    let (_sigma_a2, _sigma_b2) = bob.generate_sig_and_adapt(tx2, &sigma_prime_a2, &t)?;
     println!("Client extracted t and broadcasted s_a2, s_b2");
+
+    // === Step 6: Why fresh nonces matter ===
+    demonstrate_nonce_reuse(&scheme)?;
+    Ok(())
 }
@@ -0,0 +1,157 @@
+//! Helpers shared by the demo protocols.
+
+use as_for_fde::{
+    AS_scheme, AumayrECDSA, Scheme, SchemePreSignature, Schnorr, VerifyError, BIP340, ECDSA,
+};
+use rand_core::{OsRng, RngCore};
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, DirBuilder};
+#[cfg(unix)]
+use std::os::unix::fs::DirBuilderExt;
+use std::path::PathBuf;
+
+/// Environment variable naming the directory of the nonce stores, if not given on the
+/// command line.
+pub const NONCE_DIR_VAR: &str = "AS_FOR_FDE_NONCE_DIR";
+
+/// Returns the scheme named `input`.
+///
+/// # Arguments
+/// * `input` - One of `schnorr`, `bip340`, `ecdsa` or `aumayr`
+///
+/// # Returns
+/// * `Result<Scheme, UnknownScheme>` - The scheme, or `UnknownScheme` for any other name
+pub fn parse_scheme(input: &str) -> Result<Scheme, UnknownScheme> {
+    match input {
+        "schnorr" => Ok(Scheme::Schnorr(Schnorr::new())),
+        "bip340" => Ok(Scheme::BIP340(BIP340)),
+        "ecdsa" => Ok(Scheme::ECDSA(ECDSA::new())),
+        "aumayr" => Ok(Scheme::AumayrECDSA(AumayrECDSA::new())),
+        _ => Err(UnknownScheme(input.to_string())),
+    }
+}
+
+/// Returns whether `a` and `b` are the same pre-signature of `scheme`.
+pub fn same_pre_signature(scheme: &Scheme, a: &SchemePreSignature, b: &SchemePreSignature) -> bool {
+    match (
+        scheme.encode_pre_signature(a),
+        scheme.encode_pre_signature(b),
+    ) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false, // one of them belongs to another scheme
+    }
+}
+
+/// `NonceDir` is the directory of the nonce stores of a run. Unless one is given, it is a
+/// fresh directory in the temporary directory, removed when the `NonceDir` is dropped.
+pub struct NonceDir {
+    path: PathBuf,
+    temporary: bool,
+}
+
+impl NonceDir {
+    /// Returns the directory of the nonce stores, creating it accessible by its owner only if
+    /// it is missing. A given directory is kept after the run, so a party that is run again
+    /// finds the pre-signatures it handed out before.
+    ///
+    /// # Arguments
+    /// * `arg` - The directory given on the command line, if any
+    ///
+    /// # Returns
+    /// * `std::io::Result<NonceDir>` - `arg`, else the directory in `AS_FOR_FDE_NONCE_DIR`,
+    ///   else a new directory in the temporary directory that only lasts for this run
+    pub fn new(arg: Option<&str>) -> std::io::Result<Self> {
+        let mut builder = DirBuilder::new();
+        #[cfg(unix)]
+        DirBuilderExt::mode(&mut builder, 0o700);
+        let given = arg
+            .map(OsString::from)
+            .or_else(|| env::var_os(NONCE_DIR_VAR));
+        let Some(dir) = given else {
+            let name = format!("as_for_fde_nonces_{:016x}", OsRng.next_u64());
+            let path = env::temp_dir().join(name);
+            builder.create(&path)?; // fails rather than reuse a directory that exists
+            return Ok(Self {
+                path,
+                temporary: true,
+            });
+        };
+        let path = PathBuf::from(dir);
+        builder.recursive(true).create(&path)?;
+        Ok(Self {
+            path,
+            temporary: false,
+        })
+    }
+
+    /// Returns the path of the store `name` in the directory.
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for NonceDir {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// `UnknownScheme` is the error for a scheme name the demos do not know.
+pub struct UnknownScheme(pub String);
+
+impl fmt::Display for UnknownScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown scheme \"{}\", expected one of: schnorr, bip340, ecdsa, aumayr",
+            self.0
+        )
+    }
+}
+
+// `main` prints the error it returns with `Debug`
+impl fmt::Debug for UnknownScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for UnknownScheme {}
+
+/// `Rejected` is the error of a party that aborts the protocol: the check it did at `step`
+/// failed for `reason`.
+pub struct Rejected {
+    /// The check that failed, e.g. "Bob rejected Alice's pre-signature"
+    pub step: &'static str,
+    /// Why the checked value was rejected
+    pub reason: VerifyError,
+}
+
+impl Rejected {
+    /// Records that the check at `step` failed for `reason`.
+    pub fn new(step: &'static str, reason: VerifyError) -> Self {
+        Self { step, reason }
+    }
+}
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.step, self.reason)
+    }
+}
+
+impl fmt::Debug for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for Rejected {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
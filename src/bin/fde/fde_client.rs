@@ -1,45 +1,100 @@
+use crate::common::Rejected;
 use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Key, Nonce,
 };
-use as_for_fde::{AS_scheme, Sigma, Sigma_prime, Scheme, Sign_scheme};
-use hex;
-use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
-use rand_core::OsRng;
+use as_for_fde::{
+    AS_scheme, AdaptorStatement, AdaptorWitness, Error, NonceManager, NonceManagerError, PoK,
+    PublicKey, Scheme, SchemePreSignature, SchemeSignature, SecretKey, Sign_scheme, VerifyError,
+};
+use rand_core::{OsRng, RngCore};
+use std::path::Path;
+use zeroize::Zeroizing;
 /// `Client` represents a buying party in fair data exchange protocol.
 /// They hold a secret key `sk`, and use a selected signature `Scheme`.
 pub struct Client {
     /// Client's secret key
-    sk: Scalar,
+    sk: SecretKey,
     /// Client's public key, derived from `sk`
-    pub pk: ProjectivePoint,
+    pub pk: PublicKey,
     /// The cryptographic signing scheme used (e.g., Schnorr or ECDSA)
     scheme: Scheme,
+    /// Store of the pre-signatures the client has handed out
+    nonces: NonceManager,
 }
 
 impl Client {
     /// Creates a new `Client` instance with random `sk`
-    /// computes `pk`, and stores the chosen signature scheme.
-    pub fn new(scheme: Scheme) -> Self {
-        let sk = Scalar::random(&mut OsRng);
-        let pk = ProjectivePoint::GENERATOR * sk;
-        Self { sk, pk, scheme }
+    /// computes `pk`, and stores the chosen signature scheme and the store `nonces` of the
+    /// pre-signatures it hands out.
+    pub fn new(scheme: Scheme, nonces: NonceManager) -> Self {
+        let (sk, pk) = scheme.keygen(&mut OsRng);
+        Self {
+            sk,
+            pk,
+            scheme,
+            nonces,
+        }
     }
 
-    /// Generates a pre-signature (`Sigma_prime`) for a given ciphertext `ct`.
-    /// The ciphertext is hex-encoded before signing.
+    /// Simulates a restart of the client after a crash: the store of the pre-signatures it
+    /// handed out is closed and opened again from `path`. The key is kept, as a real party
+    /// would load it from its own storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the store.
+    ///
+    /// # Returns
+    ///
+    /// * The restarted `Client`, or a `NonceManagerError` if the store cannot be opened.
+    pub fn restart(self, path: &Path) -> Result<Self, NonceManagerError> {
+        let Self {
+            sk,
+            pk,
+            scheme,
+            nonces,
+        } = self;
+        drop(nonces); // releases the lock on the store
+        Ok(Self {
+            sk,
+            pk,
+            scheme,
+            nonces: NonceManager::open(path)?,
+        })
+    }
+
+    /// Generates a pre-signature (`SchemePreSignature`) for a given ciphertext `ct`.
+    /// The raw ciphertext bytes are signed. Asked again for the same `ct` and `server_pk`, the
+    /// client returns the pre-signature it already handed out.
     ///
     /// # Arguments
     /// * `ct` - Ciphertext to be signed.
     /// * `server_pk` - The server's public key, used in the pre-signing process.
+    /// * `server_pk_proof` - The server's proof of knowledge of the secret key of `server_pk`.
     /// # Returns
-    /// The pre-signature and the tweak point `T`.
-    pub fn generate_presig(&self, ct: &[u8], server_pk: &ProjectivePoint) -> Sigma_prime {
-        let r_prime = Scalar::random(&mut OsRng);
-        let sigma_prime = self
-            .scheme
-            .pre_sign(&self.sk, &hex::encode(ct), server_pk, &r_prime);
-        sigma_prime
+    /// The pre-signature, a `Rejected` error with the `VerifyError` of `server_pk_proof` if it
+    /// does not verify, or a `NonceManagerError` if pre-signing fails or the nonce store cannot
+    /// be written.
+    pub fn generate_presig(
+        &mut self,
+        ct: &[u8],
+        server_pk: &AdaptorStatement,
+        server_pk_proof: &PoK,
+    ) -> Result<SchemePreSignature, Box<dyn std::error::Error>> {
+        self.scheme
+            .verify_statement(server_pk, server_pk_proof)
+            .map_err(|reason| {
+                Rejected::new(
+                    "Client rejected the server's proof of knowledge of its secret key",
+                    reason,
+                )
+            })?;
+        let mut aux_rand = [0u8; 32];
+        OsRng.fill_bytes(&mut aux_rand);
+        Ok(self
+            .nonces
+            .pre_sign(&self.scheme, &self.sk, ct, server_pk, Some(&aux_rand))?)
     }
 
     /// Verifies the correctness of both the server's and client's signatures on the same ciphertext.
@@ -51,19 +106,16 @@ impl Client {
     /// * `sigma_c` - Signature from the client.
     ///
     /// # Returns
-    /// * `true` if both signatures are valid; `false` otherwise.
+    /// * `Ok(())` if both signatures are valid; otherwise the `VerifyError` of the first invalid one.
     pub fn verify_sign(
         &self,
-        server_pk: &ProjectivePoint,
+        server_pk: &PublicKey,
         ct: &[u8],
-        sigma_s: &Sigma,
-        sigma_c: &Sigma,
-    ) -> bool {
-        let is_s_correct = self
-            .scheme
-            .verify_sign(sigma_s, server_pk, &hex::encode(ct));
-        let is_c_correct = self.scheme.verify_sign(sigma_c, &self.pk, &hex::encode(ct));
-        is_c_correct && is_s_correct
+        sigma_s: &SchemeSignature,
+        sigma_c: &SchemeSignature,
+    ) -> Result<(), VerifyError> {
+        self.scheme.verify_sign_detailed(sigma_s, server_pk, ct)?;
+        self.scheme.verify_sign_detailed(sigma_c, &self.pk, ct)
     }
 
     /// Extracts the secret witness value `t` from a full signature and its corresponding pre-signature.
@@ -72,27 +124,32 @@ impl Client {
     ///
     /// * `sigma` - The full signature.
     /// * `sigma_prime` - The pre-signature.
+    /// * `server_pk` - The server's public key, which the witness must correspond to.
     ///
     /// # Returns
     ///
-    /// * The extracted `Scalar` witness value `t`.
-    pub fn extract_secret(&self, sigma: &Sigma, sigma_prime: &Sigma_prime) -> Scalar {
-        let t = self.scheme.extract_witness(sigma, sigma_prime);
-        t
+    /// * The extracted `AdaptorWitness` `t`, or an `Error` if it does not match `server_pk`.
+    pub fn extract_secret(
+        &self,
+        sigma: &SchemeSignature,
+        sigma_prime: &SchemePreSignature,
+        server_pk: &AdaptorStatement,
+    ) -> Result<AdaptorWitness, Error> {
+        self.scheme.extract_witness(sigma, sigma_prime, server_pk)
     }
 
     /// Decrypts ciphertext `ct` using a derived key `sk` (scalar) and a given nonce.
     ///
     /// # Arguments
     /// * `ct` - The ciphertext to decrypt.
-    /// * `sk` - A scalar used as the decryption key (derived from a witness or shared secret).
+    /// * `sk` - The witness used as the decryption key.
     /// * `nonce_bytes` - A 96-bit nonce required by AES-GCM.
     ///
     /// # Panics
     /// Will panic if decryption fails or the result is not valid UTF-8.
-    pub fn decrypt_data(&self, ct: &[u8], sk: &Scalar, nonce_bytes: &[u8]) -> String {
-        let key_bytes = sk.to_bytes();
-        let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
+    pub fn decrypt_data(&self, ct: &[u8], sk: &AdaptorWitness, nonce_bytes: &[u8]) -> String {
+        let key_bytes = Zeroizing::new(sk.to_bytes());
+        let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..]);
         let cipher = Aes256Gcm::new(key);
         let nonce = Nonce::from_slice(nonce_bytes);
         let plaintext: Vec<u8> = cipher.decrypt(nonce, ct).expect("decryption failed");
@@ -2,26 +2,30 @@ use aes_gcm::{
     aead::{Aead, AeadCore, KeyInit},
     Aes256Gcm, Key,
 };
-use hex;
-use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
+use rand_core::{OsRng, RngCore};
+use zeroize::Zeroizing;
 
-use rand_core::OsRng;
-
-use as_for_fde::{AS_scheme, Sigma, Sigma_prime, Scheme, Sign_scheme};
+use as_for_fde::{
+    AS_scheme, AdaptorStatement, AdaptorWitness, Error, PoK, PublicKey, Scheme, SchemePreSignature,
+    SchemeSignature, SecretKey, Sign_scheme, VerifyError,
+};
 /// `Server` represents a data provider in fair data exchange (FDE) protocol.  
 /// It holds two secret keys:
 /// - One for encrypting data (`sk`)
 /// - One for signing (`sk_s`)
-/// And uses a selected cryptographic signature `Scheme`.
+///
+/// It uses a selected cryptographic signature `Scheme`.
 pub struct Server {
     /// Secret encryption key (used for AES encryption and adaptor signing)
-    sk: Scalar,
-    /// Public key corresponding to `sk`
-    pub pk: ProjectivePoint,
+    sk: AdaptorWitness,
+    /// Public key corresponding to `sk`, the statement the client pre-signs under
+    pub pk: AdaptorStatement,
+    /// Proof of knowledge of `sk`, checked by the client before it pre-signs under `pk`
+    pub pk_proof: PoK,
     /// Secret signing key (used for generating actual signatures)
-    sk_s: Scalar,
+    sk_s: SecretKey,
     /// Public key corresponding to `sk_s`
-    pub pk_s: ProjectivePoint,
+    pub pk_s: PublicKey,
     /// The cryptographic signature scheme in use (e.g., Schnorr or ECDSA)
     scheme: Scheme,
 }
@@ -38,13 +42,13 @@ impl Server {
     ///
     /// * A new `Server` instance.
     pub fn new(scheme: Scheme) -> Self {
-        let sk = Scalar::random(&mut OsRng);
-        let pk = ProjectivePoint::GENERATOR * sk;
-        let sk_s = Scalar::random(&mut OsRng);
-        let pk_s = ProjectivePoint::GENERATOR * sk_s;
+        let (sk, pk, pk_proof) = scheme.gen_statement(&mut OsRng, true);
+        let pk_proof = pk_proof.expect("gen_statement returns a proof when asked for one");
+        let (sk_s, pk_s) = scheme.keygen(&mut OsRng);
         Self {
             sk,
             pk,
+            pk_proof,
             sk_s,
             pk_s,
             scheme,
@@ -63,9 +67,9 @@ impl Server {
     ///   - The encrypted ciphertext as a byte vector.
     ///   - The randomly generated 12-byte nonce used during encryption.
     pub fn encrypt_data(&self, plaintext: &str) -> (Vec<u8>, [u8; 12]) {
-        let key_bytes = self.sk.to_bytes();
-        let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
-        let cipher = Aes256Gcm::new(&key);
+        let key_bytes = Zeroizing::new(self.sk.to_bytes());
+        let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..]);
+        let cipher = Aes256Gcm::new(key);
 
         let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
         let ciphertext = cipher
@@ -88,15 +92,15 @@ impl Server {
     ///
     /// # Returns
     ///
-    /// * `true` if the pre-signature is valid; `false` otherwise.
+    /// * `Ok(())` if the pre-signature is valid; otherwise the `VerifyError` explaining why it was rejected.
     pub fn verify_presig(
         &self,
-        sigma_prime: &Sigma_prime,
-        pk_c: &ProjectivePoint,
+        sigma_prime: &SchemePreSignature,
+        pk_c: &PublicKey,
         ct: &[u8],
-    ) -> bool {
+    ) -> Result<(), VerifyError> {
         self.scheme
-            .verify_pre_sign(pk_c, &hex::encode(ct), &self.pk, sigma_prime)
+            .verify_pre_sign_detailed(pk_c, ct, &self.pk, sigma_prime)
     }
 
     /// Generates a full signature using the signing key `sk_s`, and adapts a client’s pre-signature  
@@ -110,13 +114,19 @@ impl Server {
     /// # Returns
     ///
     /// * A tuple containing:
-    ///   - The server’s full signature (`Sigma`).
-    ///   - The adapted signature derived from the client’s pre-signature (`Sigma`).
-    pub fn generate_sig_and_adapt(&self, ct: &[u8], sigma_prime: &Sigma_prime) -> (Sigma, Sigma) {
-        let r_s = Scalar::random(&mut OsRng);
-        let sigma_s = self.scheme.sign(&self.sk_s, &hex::encode(ct), &r_s);
-        let sigma_c = self.scheme.adapt_signature(sigma_prime, &self.sk);
+    ///   - The server’s full signature (`SchemeSignature`).
+    ///   - The adapted signature derived from the client’s pre-signature (`SchemeSignature`).
+    /// * An `Error` if signing or adapting fails.
+    pub fn generate_sig_and_adapt(
+        &self,
+        ct: &[u8],
+        sigma_prime: &SchemePreSignature,
+    ) -> Result<(SchemeSignature, SchemeSignature), Error> {
+        let mut aux_rand = [0u8; 32];
+        OsRng.fill_bytes(&mut aux_rand);
+        let sigma_s = self.scheme.sign(&self.sk_s, ct, Some(&aux_rand))?;
+        let sigma_c = self.scheme.adapt_signature(sigma_prime, &self.sk)?;
 
-        (sigma_s, sigma_c)
+        Ok((sigma_s, sigma_c))
     }
 }
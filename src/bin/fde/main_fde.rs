@@ -1,51 +1,75 @@
+#[path = "../common/mod.rs"]
+mod common;
 mod fde_client;
 mod fde_server;
-use as_for_fde::{Scheme, Schnorr, ECDSA};
+use as_for_fde::NonceManager;
+use common::{parse_scheme, same_pre_signature, NonceDir, Rejected};
 use fde_client::Client;
 use fde_server::Server;
 use std::env;
 
 /// Schematic implementation of the steps of a two party fair data exchange protocol.
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     // === Step 0: Set to chosen scheme ===
     let args: Vec<String> = env::args().collect();
     let input = args.get(1).map(String::as_str).unwrap_or("schnorr");
 
-    let scheme: Scheme = match input {
-        "schnorr" => Scheme::Schnorr(Schnorr {}),
-        "ecdsa" => Scheme::ECDSA(ECDSA {}),
-        _ => {
-            eprintln!("Please input a valid scheme: [\"schnorr\", \"ecdsa\"]");
-            std::process::exit(1);
-        }
-    };
+    let scheme = parse_scheme(input)?;
     println!("The protocol will run using : {}", input);
 
     // === Step 1: Setup ===
     let server = Server::new(scheme.clone());
-    let client = Client::new(scheme);
+    // the client's pre-signatures are kept in the directory given as second argument or in
+    // AS_FOR_FDE_NONCE_DIR, else in one for this run only, so a restarted client re-sends the
+    // same pre-signature instead of one with a fresh nonce
+    let dir = NonceDir::new(args.get(2).map(String::as_str))?;
+    let client_nonces = dir.join("client_nonces");
+    let mut client = Client::new(scheme.clone(), NonceManager::open(&client_nonces)?);
 
     // === Step 2: Server encrypts data ===
     let data = "Very secret data :)";
     let (ct, nonce) = server.encrypt_data(data);
 
-    println!("Server encrypted data and sent ct + nonce + pk to client.");
+    println!(
+        "Server encrypted data and sent ct + nonce + pk + proof of knowledge of sk to client."
+    );
 
     // === Step 3: Client creates pre-signature ===
-    let sigma_prime_c = client.generate_presig(&ct, &server.pk);
-    println!("Client generated (s'_c, R'_c) and sent to server.");
+    let sigma_prime_c = client.generate_presig(&ct, &server.pk, &server.pk_proof)?;
+    println!("Client checked the server's proof, generated (s'_c, R'_c) and sent to server.");
+
+    // the client crashes before it learns that its pre-signature arrived, and pre-signs ct
+    // again after restarting: its store gives back the pre-signature it already sent
+    client = client.restart(&client_nonces)?;
+    let resent = client.generate_presig(&ct, &server.pk, &server.pk_proof)?;
+    if !same_pre_signature(&scheme, &resent, &sigma_prime_c) {
+        return Err("Client sent a second pre-signature on ct after restarting".into());
+    }
+    println!(
+        "Client restarted and re-sent the same pre-signature, read back from its nonce store."
+    );
 
     // === Step 4: Server verifies s'_c and generates s_s, s_c ===
-    assert!(server.verify_presig(&sigma_prime_c, &client.pk, &ct));
-    let (sigma_s, sigma_c) = server.generate_sig_and_adapt(&ct, &sigma_prime_c);
+    server
+        .verify_presig(&sigma_prime_c, &client.pk, &ct)
+        .map_err(|reason| Rejected::new("Server rejected the client's pre-signature", reason))?;
+    let (sigma_s, sigma_c) = server.generate_sig_and_adapt(&ct, &sigma_prime_c)?;
     println!("Server verified pre-sig and broadcasted s_s, s_c.");
 
     // === Step 5: Client extracts secret and decrypts ===
-    assert!(client.verify_sign(&server.pk_s, &ct, &sigma_s, &sigma_c));
+    client
+        .verify_sign(&server.pk_s, &ct, &sigma_s, &sigma_c)
+        .map_err(|reason| {
+            Rejected::new(
+                "Client rejected the signatures broadcasted by the server",
+                reason,
+            )
+        })?;
     println!("Client verified signatures broadcasted by server");
-    let sk_recovered = client.extract_secret(&sigma_c, &sigma_prime_c);
+    let sk_recovered = client.extract_secret(&sigma_c, &sigma_prime_c, &server.pk)?;
     let decrypted = client.decrypt_data(&ct, &sk_recovered, &nonce);
 
     println!("Client extracted sk and decrypted the data:");
     println!("Decrypted message: {}", decrypted);
+    Ok(())
 }
@@ -0,0 +1,494 @@
+use k256::{
+    elliptic_curve::{
+        ops::{MulByGenerator, Reduce},
+        point::{AffineCoordinates, DecompressPoint},
+        subtle::Choice,
+        PrimeField,
+    },
+    AffinePoint, FieldBytes, ProjectivePoint, Scalar, U256,
+};
+use sha2::Digest;
+use zeroize::Zeroizing;
+
+use crate::msm::{msm_vartime, PointTable};
+use crate::sealed::{KeyPair, PreSignWithKey};
+use crate::utils::tagged_hash;
+use crate::{
+    AS_scheme, AdaptorStatement, AdaptorWitness, DiscreteLog, Error, PublicKey, SecretKey, Sigma,
+    Sigma_prime, Sign_scheme, VerifyError,
+};
+
+/// `BIP340` implements the Schnorr signature scheme as specified in BIP-340:
+/// x-only 32-byte public keys, even-Y nonces, tagged hashes and 64-byte signatures.
+/// Its signatures are valid Taproot key-path signatures, and its adaptor variant produces
+/// adapted signatures that pass strict BIP-340 verification.
+#[derive(Clone)]
+pub struct BIP340;
+
+impl BIP340 {
+    /// Returns true if the affine Y coordinate of `W` is even.
+    fn has_even_y(W: &ProjectivePoint) -> bool {
+        !bool::from(W.to_affine().y_is_odd())
+    }
+
+    /// Returns the point with the same x-coordinate as `W` and an even Y coordinate.
+    fn with_even_y(W: &ProjectivePoint) -> ProjectivePoint {
+        if Self::has_even_y(W) {
+            *W
+        } else {
+            -W
+        }
+    }
+
+    /// Serializes a point to its 32-byte x-only encoding.
+    ///
+    /// # Arguments
+    /// * `W` - Point to encode
+    ///
+    /// # Returns
+    /// * `[u8; 32]` - Big-endian x-coordinate of `W`
+    pub fn xonly(W: &ProjectivePoint) -> [u8; 32] {
+        W.to_affine().x().into()
+    }
+
+    /// Recovers the unique point with x-coordinate `x` and an even Y coordinate.
+    ///
+    /// # Arguments
+    /// * `x` - 32-byte big-endian x-coordinate
+    ///
+    /// # Returns
+    /// * `Option<ProjectivePoint>` - The lifted point, or `None` if `x` is not on the curve
+    pub fn lift_x(x: &[u8; 32]) -> Option<ProjectivePoint> {
+        AffinePoint::decompress(&FieldBytes::from(*x), Choice::from(0))
+            .into_option()
+            .map(ProjectivePoint::from)
+    }
+
+    /// Computes the BIP-340 challenge `e = H_{BIP0340/challenge}(x(R) || x(P) || m)`.
+    ///
+    /// # Arguments
+    /// * `R` - Nonce point
+    /// * `P` - Public key
+    /// * `message` - Message bytes
+    ///
+    /// # Returns
+    /// * `Scalar` - Challenge reduced modulo the group order
+    pub fn hash_challenge_bytes(
+        &self,
+        R: &ProjectivePoint,
+        P: &ProjectivePoint,
+        message: &[u8],
+    ) -> Scalar {
+        let mut hasher = tagged_hash("BIP0340/challenge");
+        hasher.update(Self::xonly(R)); // add x(R)
+        hasher.update(Self::xonly(P)); // add x(P)
+        hasher.update(message); // add message
+        let hash: [u8; 32] = hasher.finalize().into();
+        <Scalar as Reduce<U256>>::reduce_bytes(&hash.into())
+    }
+
+    /// Signs `m` with nonce `k`, negating the secret key and nonce where needed so that
+    /// both `P` and `R` have even Y coordinates.
+    ///
+    /// # Arguments
+    /// * `p` - Secret signing key
+    /// * `m` - Message bytes
+    /// * `k` - Nonce
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - Signature `(s, R)` with `R` having an even Y coordinate, or
+    ///   `Error::InvalidScalar` if `p` or `k` is zero
+    pub fn sign_bytes(&self, p: &SecretKey, m: &[u8], k: &Scalar) -> Result<Sigma, Error> {
+        let p = p.as_scalar();
+        if *p == Scalar::ZERO || *k == Scalar::ZERO {
+            return Err(Error::InvalidScalar);
+        }
+        let P = ProjectivePoint::mul_by_generator(p);
+        let d = Zeroizing::new(if Self::has_even_y(&P) { *p } else { -p });
+        let R = ProjectivePoint::mul_by_generator(k);
+        let k = Zeroizing::new(if Self::has_even_y(&R) { *k } else { -k });
+        let R = Self::with_even_y(&R);
+        let e = self.hash_challenge_bytes(&R, &P, m);
+        let s = *k + e * *d;
+        Ok(Sigma { s, R })
+    }
+
+    /// Signs `m` following the BIP-340 default signing algorithm, deriving the nonce
+    /// from the secret key, the message and the auxiliary randomness `aux_rand`.
+    ///
+    /// # Arguments
+    /// * `p` - Secret signing key
+    /// * `m` - Message bytes
+    /// * `aux_rand` - 32 bytes of auxiliary randomness
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - BIP-340 signature `(s, R)`, or `Error::InvalidScalar` if `p`
+    ///   or the derived nonce is zero
+    pub fn sign_raw(&self, p: &SecretKey, m: &[u8], aux_rand: &[u8; 32]) -> Result<Sigma, Error> {
+        let P = ProjectivePoint::mul_by_generator(p.as_scalar());
+        let d = Zeroizing::new(if Self::has_even_y(&P) {
+            *p.as_scalar()
+        } else {
+            -p.as_scalar()
+        });
+
+        let aux_hash: [u8; 32] = tagged_hash("BIP0340/aux")
+            .chain_update(aux_rand)
+            .finalize()
+            .into();
+        let mut t = Zeroizing::new([0u8; 32]);
+        for (i, byte) in d.to_bytes().iter().enumerate() {
+            t[i] = byte ^ aux_hash[i];
+        }
+
+        let rand: Zeroizing<[u8; 32]> = Zeroizing::new(
+            tagged_hash("BIP0340/nonce")
+                .chain_update(&t[..])
+                .chain_update(Self::xonly(&P))
+                .chain_update(m)
+                .finalize()
+                .into(),
+        );
+        let k = Zeroizing::new(<Scalar as Reduce<U256>>::reduce_bytes(&(*rand).into()));
+        self.sign_bytes(p, m, &k)
+    }
+
+    /// Verifies a serialized BIP-340 signature against an x-only public key.
+    ///
+    /// # Arguments
+    /// * `pk` - 32-byte x-only public key
+    /// * `m` - Message bytes
+    /// * `sig` - 64-byte signature `x(R) || s`
+    ///
+    /// # Returns
+    /// * `bool` - True if the signature is valid
+    pub fn verify_raw(&self, pk: &[u8; 32], m: &[u8], sig: &[u8; 64]) -> bool {
+        let (P, sigma) = match (Self::lift_x(pk), self.signature_from_bytes(sig)) {
+            (Some(P), Ok(sigma)) => (P, sigma),
+            _ => return false,
+        };
+        let e = self.hash_challenge_bytes(&sigma.R, &P, m);
+        msm_vartime(&[(ProjectivePoint::GENERATOR, sigma.s), (P, -e)]) == sigma.R
+    }
+
+    /// Serializes a signature into its 64-byte BIP-340 encoding `x(R) || s`.
+    ///
+    /// # Arguments
+    /// * `sigma` - Signature to encode
+    ///
+    /// # Returns
+    /// * `[u8; 64]` - Encoded signature
+    pub fn signature_to_bytes(&self, sigma: &Sigma) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&Self::xonly(&sigma.R));
+        bytes[32..].copy_from_slice(&sigma.s.to_bytes());
+        bytes
+    }
+
+    /// Parses a 64-byte BIP-340 signature, rejecting x-coordinates that are not on the
+    /// curve and scalars that are not reduced modulo the group order.
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded signature `x(R) || s`
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - The decoded signature, or `Error::InvalidPoint` /
+    ///   `Error::InvalidScalar` if the encoding is invalid
+    pub fn signature_from_bytes(&self, bytes: &[u8; 64]) -> Result<Sigma, Error> {
+        let mut r = [0u8; 32];
+        r.copy_from_slice(&bytes[..32]);
+        let R = Self::lift_x(&r).ok_or(Error::InvalidPoint)?;
+        let s = Scalar::from_repr(*FieldBytes::from_slice(&bytes[32..]))
+            .into_option()
+            .ok_or(Error::InvalidScalar)?;
+        Ok(Sigma { s, R })
+    }
+}
+
+impl Sign_scheme for BIP340 {
+    type Signature = Sigma;
+
+    fn nonce_domain(&self) -> &'static [&'static str] {
+        &["as_for_fde/BIP340"]
+    }
+
+    /// Signs a message `m` using secret key `p` and nonce `k`, following BIP-340.
+    ///
+    /// # Arguments
+    /// * `p` - Secret signing key
+    /// * `m` - Message to sign
+    /// * `k` - Random nonce
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - BIP-340 signature `(s, R)` with even-Y `R`
+    fn sign_with_nonce(&self, p: &SecretKey, m: &[u8], k: &Scalar) -> Result<Sigma, Error> {
+        self.sign_bytes(p, m, k)
+    }
+
+    /// Signs a message `m` using the BIP-340 default nonce derivation. Without `aux_rand`,
+    /// 32 zero bytes are used as auxiliary randomness, which makes signing deterministic.
+    ///
+    /// # Arguments
+    /// * `p` - Secret signing key
+    /// * `m` - Message to sign
+    /// * `aux_rand` - Optional auxiliary randomness
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - BIP-340 signature `(s, R)` with even-Y `R`
+    fn sign(&self, p: &SecretKey, m: &[u8], aux_rand: Option<&[u8; 32]>) -> Result<Sigma, Error> {
+        self.sign_raw(p, m, aux_rand.unwrap_or(&[0u8; 32]))
+    }
+
+    /// Verifies a BIP-340 signature. Only the x-coordinate of `P` is used.
+    ///
+    /// # Arguments
+    /// * `sigma` - Signature `(s, R)` to verify
+    /// * `P` - Public key of the signer
+    /// * `m` - Message that was signed
+    ///
+    /// # Returns
+    /// * `Result<(), VerifyError>` - `Ok(())` if the signature is valid, otherwise the reason
+    ///   it is rejected; a nonce `R` with an odd Y coordinate is non-canonical
+    fn verify_sign_detailed(
+        &self,
+        sigma: &Sigma,
+        P: &PublicKey,
+        m: &[u8],
+    ) -> Result<(), VerifyError> {
+        let P = P.as_point();
+        if *P == ProjectivePoint::IDENTITY || sigma.R == ProjectivePoint::IDENTITY {
+            return Err(VerifyError::IdentityPoint);
+        }
+        if !Self::has_even_y(&sigma.R) {
+            return Err(VerifyError::NonCanonicalEncoding);
+        }
+        let P = Self::with_even_y(P);
+        let e = self.hash_challenge_bytes(&sigma.R, &P, m);
+        if msm_vartime(&[(ProjectivePoint::GENERATOR, sigma.s), (P, -e)]) == sigma.R {
+            Ok(())
+        } else {
+            Err(VerifyError::EquationMismatch)
+        }
+    }
+
+    /// Serializes a signature into its 64-byte BIP-340 encoding `x(R) || s`.
+    ///
+    /// # Arguments
+    /// * `sigma` - Signature to encode
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>, Error>` - Encoded signature, see [`BIP340::signature_to_bytes`];
+    ///   never fails
+    fn encode_signature(&self, sigma: &Sigma) -> Result<Vec<u8>, Error> {
+        Ok(self.signature_to_bytes(sigma).to_vec())
+    }
+
+    /// Parses a 64-byte BIP-340 signature.
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded signature `x(R) || s`
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - The decoded signature, or `Error::InvalidLength` if `bytes`
+    ///   is not 64 bytes long, see [`BIP340::signature_from_bytes`]
+    fn decode_signature(&self, bytes: &[u8]) -> Result<Sigma, Error> {
+        let bytes: &[u8; 64] = bytes.try_into().map_err(|_| Error::InvalidLength)?;
+        self.signature_from_bytes(bytes)
+    }
+}
+
+impl AS_scheme for BIP340 {
+    type Relation = DiscreteLog;
+    type PreSignature = Sigma_prime;
+
+    /// Computes the BIP-340 challenge using the tagged hash of `(x(R) || x(P) || m)`.
+    ///
+    /// # Arguments
+    /// * `R` - Nonce point
+    /// * `P` - Public key
+    /// * `message` - Message to sign
+    ///
+    /// # Returns
+    /// * `Result<Scalar, Error>` - Challenge derived from hash; empty messages are allowed
+    fn hash_challenge(
+        &self,
+        R: &ProjectivePoint,
+        P: &PublicKey,
+        message: &[u8],
+    ) -> Result<Scalar, Error> {
+        Ok(self.hash_challenge_bytes(R, P.as_point(), message))
+    }
+
+    /// Creates a BIP-340 adaptor pre-signature `(s', R')` with `R' = r'G + T`.
+    ///
+    /// The final nonce is the even-Y point with the same x-coordinate as `R'`. When `R'`
+    /// has an odd Y coordinate the nonce `r'` is negated, and the witness will be subtracted
+    /// rather than added during adaptation.
+    ///
+    /// # Arguments
+    /// * `p` - Secret key
+    /// * `m` - Message to sign
+    /// * `T` - Tweak point (derived from secret witness `t`)
+    /// * `r_prime` - Random nonce
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime, Error>` - Adaptor pre-signature, where `R'` keeps its original
+    ///   parity, or an error if `p` or `r_prime` is zero or `R'` is the identity
+    fn pre_sign_with_nonce(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &AdaptorStatement,
+        r_prime: &Scalar,
+    ) -> Result<Sigma_prime, Error> {
+        self.pre_sign_with_key(&KeyPair::new(p), m, T, r_prime, None)
+    }
+
+    /// Verifies a BIP-340 adaptor pre-signature.
+    ///
+    /// Checks $s'G = R' - T + eP$ if `R'` has an even Y coordinate and
+    /// $s'G = T - R' + eP$ otherwise, with `P` taken as its x-only (even-Y) form.
+    ///
+    /// # Arguments
+    /// * `P` - Signer's public key
+    /// * `m` - Message
+    /// * `T` - Tweak point used in pre-signature
+    /// * `sigma_prime` - Adaptor pre-signature `(s', R')`
+    ///
+    /// # Returns
+    /// * `Result<(), VerifyError>` - `Ok(())` if the pre-signature is valid, otherwise the
+    ///   reason it is rejected
+    fn verify_pre_sign_detailed(
+        &self,
+        P: &PublicKey,
+        m: &[u8],
+        T: &AdaptorStatement,
+        sigma_prime: &Sigma_prime,
+    ) -> Result<(), VerifyError> {
+        let (P, T) = (P.as_point(), T.as_point());
+        if *P == ProjectivePoint::IDENTITY || sigma_prime.R_prime == ProjectivePoint::IDENTITY {
+            return Err(VerifyError::IdentityPoint);
+        }
+        let P = Self::with_even_y(P);
+        let R = Self::with_even_y(&sigma_prime.R_prime);
+        let e = self.hash_challenge_bytes(&R, &P, m);
+        let lhs = msm_vartime(&[(ProjectivePoint::GENERATOR, sigma_prime.s_prime), (P, -e)]);
+        let rhs = if Self::has_even_y(&sigma_prime.R_prime) {
+            sigma_prime.R_prime - T
+        } else {
+            *T - sigma_prime.R_prime
+        };
+        if lhs == rhs {
+            Ok(())
+        } else {
+            Err(VerifyError::EquationMismatch)
+        }
+    }
+
+    /// Adapts a pre-signature into a valid BIP-340 signature using secret witness `t`.
+    ///
+    /// # Arguments
+    /// * `sigma_prime` - Pre-signature `(s', R')`
+    /// * `t` - Secret tweak scalar
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - Adapted signature `(s, R)` with $s = s' \pm t$ and `R` the
+    ///   even-Y form of `R'`
+    fn adapt_signature(
+        &self,
+        sigma_prime: &Sigma_prime,
+        t: &AdaptorWitness,
+    ) -> Result<Sigma, Error> {
+        let s = if Self::has_even_y(&sigma_prime.R_prime) {
+            sigma_prime.s_prime + t.as_scalar()
+        } else {
+            sigma_prime.s_prime - t.as_scalar()
+        };
+        Ok(Sigma {
+            s,
+            R: Self::with_even_y(&sigma_prime.R_prime),
+        })
+    }
+
+    /// Extracts the witness `t` used to adapt the pre-signature into the full signature.
+    ///
+    /// # Arguments
+    /// * `sigma` - Final signature `(s, R)`
+    /// * `sigma_prime` - Pre-signature `(s', R')`
+    /// * `T` - Tweak point the witness must match
+    ///
+    /// # Returns
+    /// * `Result<AdaptorWitness, Error>` - Extracted secret tweak $t = \pm(s - s')$, depending on the
+    ///   parity of `R'`, or `Error::WitnessMismatch` if $tG \neq T$
+    fn extract_witness(
+        &self,
+        sigma: &Sigma,
+        sigma_prime: &Sigma_prime,
+        T: &AdaptorStatement,
+    ) -> Result<AdaptorWitness, Error> {
+        let t = AdaptorWitness::from(if Self::has_even_y(&sigma_prime.R_prime) {
+            sigma.s - sigma_prime.s_prime
+        } else {
+            sigma_prime.s_prime - sigma.s
+        });
+        if t.statement() == *T {
+            Ok(t)
+        } else {
+            Err(Error::WitnessMismatch)
+        }
+    }
+
+    /// Serializes a pre-signature as `R' || s'` (65 bytes), keeping the parity of `R'`.
+    ///
+    /// # Arguments
+    /// * `sigma_prime` - Pre-signature `(s', R')`
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>, Error>` - Canonical encoding of the pre-signature; never fails
+    fn encode_pre_signature(&self, sigma_prime: &Sigma_prime) -> Result<Vec<u8>, Error> {
+        Ok(sigma_prime.to_bytes().to_vec())
+    }
+
+    /// Parses a pre-signature encoded as `R' || s'`.
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded pre-signature
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime, Error>` - The decoded pre-signature, or an error if the length is
+    ///   wrong, `R'` is off-curve or the identity, or `s'` is not below the group order
+    fn decode_pre_signature(&self, bytes: &[u8]) -> Result<Sigma_prime, Error> {
+        Sigma_prime::from_bytes(bytes)
+    }
+}
+
+impl PreSignWithKey<AdaptorStatement, Sigma_prime> for BIP340 {
+    /// Pre-signs `m` with nonce `r_prime` and the precomputed public key of `key`. `T` is
+    /// only added to `R'`, so `table` is not used.
+    fn pre_sign_with_key(
+        &self,
+        key: &KeyPair,
+        m: &[u8],
+        T: &AdaptorStatement,
+        r_prime: &Scalar,
+        _table: Option<&PointTable>,
+    ) -> Result<Sigma_prime, Error> {
+        let (p, P, T) = (key.p.as_scalar(), key.P.as_point(), T.as_point());
+        if *p == Scalar::ZERO || *r_prime == Scalar::ZERO {
+            return Err(Error::InvalidScalar);
+        }
+        let d = Zeroizing::new(if Self::has_even_y(P) { *p } else { -p });
+        let R_prime = ProjectivePoint::mul_by_generator(r_prime) + T;
+        if R_prime == ProjectivePoint::IDENTITY {
+            return Err(Error::InvalidPoint);
+        }
+        let r = Zeroizing::new(if Self::has_even_y(&R_prime) {
+            *r_prime
+        } else {
+            -r_prime
+        });
+        let e = self.hash_challenge_bytes(&Self::with_even_y(&R_prime), P, m);
+        let s_prime = *r + e * *d;
+        Ok(Sigma_prime { s_prime, R_prime })
+    }
+}
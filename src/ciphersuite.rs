@@ -0,0 +1,119 @@
+use k256::{
+    elliptic_curve::{ops::Reduce, sec1::ToEncodedPoint},
+    ProjectivePoint, Scalar, U256,
+};
+use sha2::{Digest, Sha256, Sha512};
+
+/// `Ciphersuite` fixes the hash function and the domain-separation tags a scheme hashes its
+/// challenges with. [`Schnorr`](crate::Schnorr) and [`ECDSA`](crate::ECDSA)
+/// take it as a type parameter, defaulting to [`Sha256Suite`].
+///
+/// Digests shorter than 32 bytes are read as big-endian integers; longer ones are truncated
+/// to their leftmost 32 bytes, as ECDSA does with wide hashes.
+pub trait Ciphersuite {
+    /// Hash function of the suite.
+    type Hash: Digest;
+    /// Name of the suite, which schemes add to the domain of the nonces they derive: the
+    /// same nonce under the challenges of two suites leaks the secret key.
+    const ID: &'static str;
+    /// Tag of the Schnorr challenge hash, or `None` for an untagged hash.
+    const CHALLENGE_TAG: Option<&'static str>;
+    /// Tag of the ECDSA message hash, or `None` for an untagged hash.
+    const MESSAGE_TAG: Option<&'static str>;
+
+    /// Creates a hasher, prefixed with `H(tag) || H(tag)` when `tag` is given (BIP-340
+    /// style).
+    fn hasher(tag: Option<&str>) -> Self::Hash {
+        let mut hasher = Self::Hash::new();
+        if let Some(tag) = tag {
+            let tag_hash = Self::Hash::digest(tag.as_bytes());
+            hasher.update(&tag_hash); // add H(tag)
+            hasher.update(&tag_hash); // add H(tag)
+        }
+        hasher
+    }
+
+    /// Finalizes `hasher` into a 32-byte digest.
+    fn finalize(hasher: Self::Hash) -> [u8; 32] {
+        let output = hasher.finalize();
+        let mut digest = [0u8; 32];
+        if output.len() >= 32 {
+            digest.copy_from_slice(&output[..32]);
+        } else {
+            digest[32 - output.len()..].copy_from_slice(&output);
+        }
+        digest
+    }
+
+    /// Computes the Schnorr challenge $e = H(R || P || m)$ over uncompressed SEC1 encodings.
+    ///
+    /// # Arguments
+    /// * `R` - Nonce point
+    /// * `P` - Public key
+    /// * `message` - Message to sign
+    ///
+    /// # Returns
+    /// * `Scalar` - The digest reduced modulo the group order
+    fn challenge(R: &ProjectivePoint, P: &ProjectivePoint, message: &[u8]) -> Scalar {
+        let mut hasher = Self::hasher(Self::CHALLENGE_TAG); // init hasher
+        hasher.update(R.to_affine().to_encoded_point(false).as_bytes()); // add R
+        hasher.update(P.to_affine().to_encoded_point(false).as_bytes()); // add P
+        hasher.update(message); // add message
+        <Scalar as Reduce<U256>>::reduce_bytes(&Self::finalize(hasher).into())
+    }
+
+    /// Computes the ECDSA message digest $H(m)$.
+    fn message_digest(message: &[u8]) -> [u8; 32] {
+        let mut hasher = Self::hasher(Self::MESSAGE_TAG); // init hasher
+        hasher.update(message); // add message
+        Self::finalize(hasher)
+    }
+}
+
+/// `Sha256Suite` is the default suite: untagged SHA-256, as in standard ECDSA.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha256Suite;
+
+impl Ciphersuite for Sha256Suite {
+    type Hash = Sha256;
+    const ID: &'static str = "SHA-256";
+    const CHALLENGE_TAG: Option<&'static str> = None;
+    const MESSAGE_TAG: Option<&'static str> = None;
+}
+
+/// `Sha512Suite` is SHA-512 truncated to 256 bits, with explicit domain tags.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha512Suite;
+
+impl Ciphersuite for Sha512Suite {
+    type Hash = Sha512;
+    const ID: &'static str = "SHA-512";
+    const CHALLENGE_TAG: Option<&'static str> = Some("as_for_fde/SHA-512/challenge");
+    const MESSAGE_TAG: Option<&'static str> = Some("as_for_fde/SHA-512/message");
+}
+
+/// `Keccak256Suite` is untagged Keccak-256, the hash Ethereum signs transactions with.
+#[cfg(feature = "keccak")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Keccak256Suite;
+
+#[cfg(feature = "keccak")]
+impl Ciphersuite for Keccak256Suite {
+    type Hash = sha3::Keccak256;
+    const ID: &'static str = "Keccak-256";
+    const CHALLENGE_TAG: Option<&'static str> = None;
+    const MESSAGE_TAG: Option<&'static str> = None;
+}
+
+/// `Blake2sSuite` is BLAKE2s-256 with explicit domain tags.
+#[cfg(feature = "blake2")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake2sSuite;
+
+#[cfg(feature = "blake2")]
+impl Ciphersuite for Blake2sSuite {
+    type Hash = blake2::Blake2s256;
+    const ID: &'static str = "BLAKE2s";
+    const CHALLENGE_TAG: Option<&'static str> = Some("as_for_fde/BLAKE2s/challenge");
+    const MESSAGE_TAG: Option<&'static str> = Some("as_for_fde/BLAKE2s/message");
+}
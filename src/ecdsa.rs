@@ -1,27 +1,552 @@
 use k256::{
-    elliptic_curve::{ff::Field, ops::Reduce, sec1::ToEncodedPoint},
+    ecdsa::Signature,
+    elliptic_curve::{
+        ops::{MulByGenerator, Reduce},
+        scalar::IsHigh,
+    },
     ProjectivePoint, Scalar, U256,
 };
-use sha2::{Digest, Sha256};
+use std::marker::PhantomData;
+use zeroize::Zeroizing;
 
-use rand_core::OsRng;
+use crate::ciphersuite::{Ciphersuite, Sha256Suite};
+use crate::msm::{msm_vartime, mul_with_table, PointTable};
+use crate::sealed::{KeyPair, PreSignWithKey};
+use crate::sigma::{prove_with_commitment, Dleq, DleqStatement, DleqWithTable, Proof};
+use crate::utils::{
+    decode_point, decode_scalar, derive_nonce, encode_point, encode_scalar, get_x, invert_scalar,
+    POINT_LEN, SCALAR_LEN,
+};
+use crate::{
+    pre_sign_nonce, AS_scheme, DiscreteLog, Error, HardRelation, Pi, PiCommitment, PublicKey,
+    SecretKey, Sigma, SigmaProtocol, Sign_scheme, Transcript, VerifyError, ZKP,
+};
+
+/// `Sigma_prime_ECDSA` is an [`ECDSA`] adaptor pre-signature `(s', R', Z, pi)`,
+/// where `Z = pT` and `pi` proves $\log_T(Z) = \log_G(P)$. The proof is mandatory, and is
+/// sent in commitment form so that many pre-signatures can be checked at once with
+/// [`ECDSA::verify_pre_sign_batch`].
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sigma_prime_ECDSA {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scalar"))]
+    pub s_prime: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))]
+    pub R_prime: ProjectivePoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))]
+    pub Z: ProjectivePoint,
+    pub pi: PiCommitment,
+}
+
+impl Sigma_prime_ECDSA {
+    /// Length of the canonical encoding `R' || s' || Z || pi`.
+    pub const ENCODED_LEN: usize = ECDSA::PRE_SIGNATURE_LEN;
+
+    /// Serializes the pre-signature as `R' || s' || Z || pi`.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        ECDSA::encode_dleq_pre_signature(&self.s_prime, &self.R_prime, &self.Z, &self.pi)
+    }
+
+    /// Parses a pre-signature encoded by [`Sigma_prime_ECDSA::to_bytes`], rejecting wrong
+    /// lengths, off-curve or identity points and scalars that are not below the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (s_prime, R_prime, Z, pi) = ECDSA::decode_dleq_pre_signature(bytes)?;
+        Ok(Self {
+            s_prime,
+            R_prime,
+            Z,
+            pi,
+        })
+    }
+}
+
+/// Fiat–Shamir label of the DLEQ proofs attached to pre-signatures.
+pub(crate) const DLEQ_LABEL: &str = "as_for_fde/ECDSA/dleq";
 
-use crate::utils::{get_x, invert_scalar};
-use crate::{AS_scheme, Sigma, Sigma_prime, Pi, Sign_scheme, ZKP};
+/// Session context binding the DLEQ proof of a pre-signature to that pre-signature: the
+/// message hash `e` and the nonce point `R'`.
+pub(crate) fn pre_signature_context(
+    e: &Scalar,
+    R_prime: &ProjectivePoint,
+) -> [u8; SCALAR_LEN + POINT_LEN] {
+    let mut context = [0u8; SCALAR_LEN + POINT_LEN];
+    context[..SCALAR_LEN].copy_from_slice(&encode_scalar(e)); // add e
+    context[SCALAR_LEN..].copy_from_slice(&encode_point(R_prime)); // add R'
+    context
+}
+
+/// DLEQ statement $\log_G(P) = \log_T(Z)$ proved by [`ZKP::gen_proof`].
+pub(crate) fn dleq_statement(
+    P: &ProjectivePoint,
+    Z: &ProjectivePoint,
+    T: &ProjectivePoint,
+) -> DleqStatement {
+    DleqStatement {
+        G: ProjectivePoint::GENERATOR,
+        X: *P,
+        H: *T,
+        Y: *Z,
+    }
+}
+
+/// Proves the DLEQ statement of a pre-signature with witness `x` under the Fiat–Shamir
+/// `label` of the scheme, using `table` for the multiplications by `H` when given.
+///
+/// The statement is computed from `x` by the pre-signer, so the witness check of
+/// [`SigmaProtocol::prove`] is skipped.
+pub(crate) fn prove_pre_signature_dleq(
+    label: &'static str,
+    x: &Scalar,
+    statement: &DleqStatement,
+    table: Option<&PointTable>,
+    context: &[u8],
+) -> PiCommitment {
+    let transcript = Transcript::new(label, Some(context));
+    let ((J, J_prime), Proof { z, .. }) = match table {
+        Some(table) => {
+            prove_with_commitment(&DleqWithTable(table), &transcript, statement, x, None)
+        }
+        None => prove_with_commitment(&Dleq, &transcript, statement, x, None),
+    };
+    PiCommitment { J, J_prime, i: z }
+}
+
+/// Recomputes the challenge `e` of the DLEQ proof `pi` of `statement` made under the
+/// Fiat–Shamir `label` and the session `context`.
+pub(crate) fn pre_signature_dleq_challenge(
+    label: &'static str,
+    statement: &DleqStatement,
+    pi: &PiCommitment,
+    context: Option<&[u8]>,
+) -> Scalar {
+    let transcript = Transcript::new(label, context);
+    Dleq.challenge(&transcript, statement, &(pi.J, pi.J_prime))
+}
+
+/// Verifies the DLEQ proof `pi` of `statement` made under the Fiat–Shamir `label` and the
+/// session `context`, checking $iG = J + eX$ and $iH = J' + eY$.
+pub(crate) fn verify_pre_signature_dleq(
+    label: &'static str,
+    statement: &DleqStatement,
+    pi: &PiCommitment,
+    context: Option<&[u8]>,
+) -> Result<(), VerifyError> {
+    let e = pre_signature_dleq_challenge(label, statement, pi, context);
+    match Dleq.recompute_commitment(statement, &e, &pi.i) {
+        Some((J, J_prime)) if J == pi.J && J_prime == pi.J_prime => Ok(()),
+        _ => Err(VerifyError::InvalidProof),
+    }
+}
 
 /// `ECDSA` implements the Elliptic Curve Digital Signature Algorithm and its adaptor variant,
 /// including its zero-knowledge proof.
+///
+/// Pre-signatures are locked to the statements of the hard relation `Rel`, and messages are
+/// hashed with the ciphersuite `Suite`. [`ECDSA::new`] returns the scheme over [`DiscreteLog`]
+/// with [`Sha256Suite`], and `ECDSA::<Rel, Suite>::default()` the scheme over another relation
+/// or ciphersuite.
 #[derive(Clone)]
-pub struct ECDSA;
-impl ZKP for ECDSA {
-    /// Computes a Fiat–Shamir challenge `e` for a zero-knowledge proof,
-    /// using public data and hashing it to a scalar.
+pub struct ECDSA<Rel = DiscreteLog, Suite = Sha256Suite> {
+    pub(crate) _relation: PhantomData<Rel>,
+    pub(crate) _suite: PhantomData<Suite>,
+}
+
+impl<Rel, Suite> Default for ECDSA<Rel, Suite> {
+    fn default() -> Self {
+        Self {
+            _relation: PhantomData,
+            _suite: PhantomData,
+        }
+    }
+}
+
+impl ECDSA {
+    /// Returns the scheme over the secp256k1 discrete-log relation with SHA-256 message
+    /// hashing.
+    pub const fn new() -> Self {
+        Self {
+            _relation: PhantomData,
+            _suite: PhantomData,
+        }
+    }
+
+    /// Length of the canonical pre-signature encoding `R' || s' || W || pi`, where `W` is
+    /// `Z` for [`ECDSA`] and `K` for [`AumayrECDSA`](crate::AumayrECDSA).
+    pub const PRE_SIGNATURE_LEN: usize = 2 * POINT_LEN + SCALAR_LEN + PiCommitment::ENCODED_LEN;
+
+    /// Serializes `R' || s' || W || pi`, the pre-signature layout shared by both ECDSA
+    /// constructions.
+    pub(crate) fn encode_dleq_pre_signature(
+        s_prime: &Scalar,
+        R_prime: &ProjectivePoint,
+        W: &ProjectivePoint,
+        pi: &PiCommitment,
+    ) -> [u8; Self::PRE_SIGNATURE_LEN] {
+        let mut bytes = [0u8; Self::PRE_SIGNATURE_LEN];
+        let (R_prime_bytes, rest) = bytes.split_at_mut(POINT_LEN);
+        let (s_prime_bytes, rest) = rest.split_at_mut(SCALAR_LEN);
+        let (W_bytes, pi_bytes) = rest.split_at_mut(POINT_LEN);
+        R_prime_bytes.copy_from_slice(&encode_point(R_prime)); // add R'
+        s_prime_bytes.copy_from_slice(&encode_scalar(s_prime)); // add s'
+        W_bytes.copy_from_slice(&encode_point(W)); // add Z or K
+        pi_bytes.copy_from_slice(&pi.to_bytes()); // add pi
+        bytes
+    }
+
+    /// Parses `R' || s' || W || pi` into `(s', R', W, pi)`.
+    pub(crate) fn decode_dleq_pre_signature(
+        bytes: &[u8],
+    ) -> Result<(Scalar, ProjectivePoint, ProjectivePoint, PiCommitment), Error> {
+        if bytes.len() != Self::PRE_SIGNATURE_LEN {
+            return Err(Error::InvalidLength);
+        }
+        let (R_prime, rest) = bytes.split_at(POINT_LEN);
+        let (s_prime, rest) = rest.split_at(SCALAR_LEN);
+        let (W, pi) = rest.split_at(POINT_LEN);
+        Ok((
+            decode_scalar(s_prime)?,
+            decode_point(R_prime)?,
+            decode_point(W)?,
+            PiCommitment::from_bytes(pi)?,
+        ))
+    }
+
+    /// Reduces a 32-byte message digest modulo the group order, as ECDSA does with $H(m)$.
+    pub(crate) fn reduce_digest(digest: &[u8; 32]) -> Scalar {
+        <Scalar as Reduce<U256>>::reduce_bytes(&(*digest).into())
+    }
+}
+
+impl<Rel, Suite: Ciphersuite> ECDSA<Rel, Suite> {
+    /// Normalises a signature to its low-S form by replacing `(s, R)` with `(-s, -R)`
+    /// when `s` is greater than half the group order. Both forms share the same `x(R)`.
+    ///
+    /// # Arguments
+    /// * `sigma` - Signature `(s, R)` to normalise
+    ///
+    /// # Returns
+    /// * `Sigma` - Equivalent signature with a low `s`
+    pub fn normalize_s(&self, sigma: &Sigma) -> Sigma {
+        if bool::from(sigma.s.is_high()) {
+            Sigma {
+                s: -sigma.s,
+                R: -sigma.R,
+            }
+        } else {
+            Sigma {
+                s: sigma.s,
+                R: sigma.R,
+            }
+        }
+    }
+
+    /// Converts a signature into a standard low-S `(r, s)` ECDSA signature, as accepted by
+    /// `k256::ecdsa::VerifyingKey` and by Bitcoin and Ethereum nodes.
+    ///
+    /// # Arguments
+    /// * `sigma` - Signature `(s, R)` produced by `sign` or `adapt_signature`
+    ///
+    /// # Returns
+    /// * `Result<Signature, Error>` - The standard signature with `r` the x-coordinate of `R`
+    ///   modulo the group order, `Error::InvalidPoint` if `R` is the identity, or
+    ///   `Error::InvalidScalar` if `r` or `s` is zero
+    pub fn to_signature(&self, sigma: &Sigma) -> Result<Signature, Error> {
+        let sigma = self.normalize_s(sigma);
+        let r = get_x(&sigma.R)?;
+        Signature::from_scalars(r, sigma.s).map_err(|_| Error::InvalidScalar)
+    }
+
+    /// Verifies a standard `(r, s)` ECDSA signature over the digest of `m` under the
+    /// ciphersuite, see [`Ciphersuite::message_digest`], which is the SHA-256 hash of `m` with
+    /// the default [`Sha256Suite`].
+    /// Both low-S and high-S signatures are accepted.
+    ///
+    /// # Arguments
+    /// * `signature` - Standard ECDSA signature
+    /// * `P` - Signer's public key
+    /// * `m` - Message that was signed
+    ///
+    /// # Returns
+    /// * `bool` - True if valid, false otherwise
+    pub fn verify_standard(&self, signature: &Signature, P: &PublicKey, m: &[u8]) -> bool {
+        let r: Scalar = *signature.r();
+        let (s_inv, e) = match (invert_scalar(&signature.s()), self.hash_message(m)) {
+            (Ok(s_inv), Ok(e)) => (s_inv, e),
+            _ => return false,
+        };
+        let R = msm_vartime(&[
+            (ProjectivePoint::GENERATOR, e * s_inv),
+            (*P.as_point(), r * s_inv),
+        ]);
+        get_x(&R) == Ok(r)
+    }
+
+    /// Hashes a message into the scalar $H(m)$ signed by ECDSA.
+    ///
+    /// # Arguments
+    /// * `message` - The message to hash
+    ///
+    /// # Returns
+    /// * `Result<Scalar, Error>` - The ciphersuite's message digest reduced modulo the group
+    ///   order, or `Error::EmptyMessage`
+    pub(crate) fn hash_message(&self, message: &[u8]) -> Result<Scalar, Error> {
+        if message.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+        Ok(ECDSA::reduce_digest(&Suite::message_digest(message)))
+    }
+
+    /// Signs the message hash `e` with nonce `k`: $s = k^{-1}(e + x(R) p)$.
+    pub(crate) fn sign_with_challenge(
+        &self,
+        p: &SecretKey,
+        e: &Scalar,
+        k: &Scalar,
+    ) -> Result<Sigma, Error> {
+        let k_inv = Zeroizing::new(invert_scalar(k)?);
+        let R = ProjectivePoint::mul_by_generator(k);
+        let r_x = get_x(&R)?;
+        let s = *k_inv * (e + *p.as_scalar() * r_x);
+        Ok(Sigma { s, R })
+    }
+
+    /// Verifies a signature over the message hash `e` by checking
+    /// $x(s^{-1}(eG + x(R)P)) = x(R)$.
+    pub(crate) fn verify_with_challenge(
+        &self,
+        sigma: &Sigma,
+        P: &PublicKey,
+        e: &Scalar,
+    ) -> Result<(), VerifyError> {
+        if *P.as_point() == ProjectivePoint::IDENTITY || sigma.R == ProjectivePoint::IDENTITY {
+            return Err(VerifyError::IdentityPoint);
+        }
+        let s_inv = invert_scalar(&sigma.s)?;
+        let r_x = get_x(&sigma.R)?;
+        let rhs_point = msm_vartime(&[
+            (ProjectivePoint::GENERATOR, *e * s_inv),
+            (*P.as_point(), r_x * s_inv),
+        ]);
+        if get_x(&rhs_point) == Ok(r_x) {
+            Ok(())
+        } else {
+            Err(VerifyError::EquationMismatch)
+        }
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> ECDSA<Rel, Suite> {
+    /// Adapts the pre-signature component `s'` with witness `t`: $s = s' t^{-1}$.
+    pub(crate) fn adapt_s_prime(
+        &self,
+        s_prime: &Scalar,
+        R_prime: &ProjectivePoint,
+        t: &Rel::Witness,
+    ) -> Result<Sigma, Error> {
+        let t_inv = Zeroizing::new(invert_scalar(Rel::adaptor_scalar(t))?);
+        Ok(Sigma {
+            s: s_prime * &*t_inv,
+            R: *R_prime,
+        })
+    }
+
+    /// Extracts `t` from `s` and the pre-signature component `s'`, trying both $s' s^{-1}$
+    /// and $-s' s^{-1}$ since the published `s` may have been negated.
+    pub(crate) fn extract_from_s_prime(
+        &self,
+        sigma: &Sigma,
+        s_prime: &Scalar,
+        T: &Rel::Statement,
+    ) -> Result<Rel::Witness, Error> {
+        let s_inv: Scalar = invert_scalar(&sigma.s)?;
+        let t = Zeroizing::new(s_prime * &s_inv);
+        [*t, -*t]
+            .into_iter()
+            .map(Rel::witness_from_scalar)
+            .find(|candidate| Rel::verify_witness(T, candidate))
+            .ok_or(Error::WitnessMismatch)
+    }
+
+    /// Pre-signs the message hash `e` with nonce `k` under statement `T`, with the public key
+    /// `P` of `p`, multiplying `T` with `table` when given.
+    fn pre_sign_with_challenge(
+        &self,
+        p: &SecretKey,
+        P: &PublicKey,
+        e: &Scalar,
+        T: &Rel::Statement,
+        k: &Scalar,
+        table: Option<&PointTable>,
+    ) -> Result<Sigma_prime_ECDSA, Error> {
+        let (p, P, T) = (p.as_scalar(), P.as_point(), &Rel::adaptor_point(T));
+        // s' = k⁻1(H(m)+r'_xtP)
+        // R' = k·T
+        let k_inv = Zeroizing::new(invert_scalar(k)?);
+        let R_prime = mul_with_table(T, table, k);
+        let R_prime_x = get_x(&R_prime)?;
+
+        let s_prime = *k_inv * (e + R_prime_x * p);
+        let Z = mul_with_table(T, table, p);
+        let context = pre_signature_context(e, &R_prime);
+        Ok(Sigma_prime_ECDSA {
+            s_prime,
+            R_prime,
+            Z,
+            pi: prove_pre_signature_dleq(DLEQ_LABEL, p, &dleq_statement(P, &Z, T), table, &context),
+        })
+    }
+
+    /// Pre-signs `m` like [`AS_scheme::pre_sign`], multiplying `T` with its precomputed
+    /// `table`. Building a table costs about three scalar multiplications and saves about
+    /// two per pre-signature, so it pays off from about three pre-signatures against the
+    /// same `T`. The pre-signature is the same as the one of [`AS_scheme::pre_sign`].
+    ///
+    /// # Arguments
+    /// * `p` - Secret key
+    /// * `m` - Message
+    /// * `T` - Tweak point
+    /// * `table` - Table of the adaptor point of `T`, see [`PointTable::new`]
+    /// * `aux_rand` - Optional randomness hedging the nonce
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime_ECDSA, Error>` - Adaptor pre-signature, or an error if `m` is
+    ///   empty, `R'` is unusable or `table` is not the table of `T` (`Error::InvalidPoint`)
+    pub fn pre_sign_with_table(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &Rel::Statement,
+        table: &PointTable,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Sigma_prime_ECDSA, Error> {
+        let T_point = Rel::adaptor_point(T);
+        if *table.point() != T_point {
+            return Err(Error::InvalidPoint);
+        }
+        let e = self.hash_message(m)?;
+        let k = pre_sign_nonce(self, p, m, &T_point, aux_rand);
+        self.pre_sign_with_challenge(p, &p.public_key(), &e, T, &k, Some(table))
+    }
+
+    /// Verifies a pre-signature over the message hash `e` and its DLEQ proof. `R'` is checked
+    /// in full as $s'R' = eT + rZ$, not only through its x-coordinate, so that the check is the
+    /// linear equation of [`ECDSA::verify_pre_sign_batch`].
+    fn verify_pre_sign_with_challenge(
+        &self,
+        P: &PublicKey,
+        e: &Scalar,
+        T: &Rel::Statement,
+        sigma_prime: &Sigma_prime_ECDSA,
+    ) -> Result<(), VerifyError> {
+        let (P, T) = (P.as_point(), &Rel::adaptor_point(T));
+        if *P == ProjectivePoint::IDENTITY
+            || sigma_prime.R_prime == ProjectivePoint::IDENTITY
+            || sigma_prime.Z == ProjectivePoint::IDENTITY
+        {
+            return Err(VerifyError::IdentityPoint);
+        }
+        let s_prime_inv = invert_scalar(&sigma_prime.s_prime)?;
+        let r_prime_x = get_x(&sigma_prime.R_prime)?;
+        let rhs_point = msm_vartime(&[
+            (*T, *e * s_prime_inv),
+            (sigma_prime.Z, r_prime_x * s_prime_inv),
+        ]);
+
+        if rhs_point != sigma_prime.R_prime {
+            return Err(VerifyError::EquationMismatch);
+        }
+        let context = pre_signature_context(e, &sigma_prime.R_prime);
+        verify_pre_signature_dleq(
+            DLEQ_LABEL,
+            &dleq_statement(P, &sigma_prime.Z, T),
+            &sigma_prime.pi,
+            Some(&context),
+        )
+    }
+}
+
+impl<Rel, Suite> ECDSA<Rel, Suite> {
+    /// Generates the DLEQ proof of [`ZKP::gen_proof`] in commitment form `(J, J', i)`, for
+    /// verifiers that check many proofs with [`ECDSA::verify_proof_batch`]. Its challenge and
+    /// response are those of the proof `(e, i)` of [`ZKP::gen_proof`].
+    ///
+    /// # Arguments
+    /// * `p` - Secret scalar
+    /// * `Z` - Public commitment (T * p)
+    /// * `P` - Public key (G * p)
+    /// * `T` - Tweak point
+    /// * `context` - Optional session context the proof is bound to
+    ///
+    /// # Returns
+    /// * `Result<PiCommitment, Error>` - The proof, or `Error::WitnessMismatch` if `p` is not
+    ///   the discrete log of `P` and `Z`
+    pub fn gen_proof_committed(
+        &self,
+        p: &Scalar,
+        Z: &ProjectivePoint,
+        P: &ProjectivePoint,
+        T: &ProjectivePoint,
+        context: Option<&[u8]>,
+    ) -> Result<PiCommitment, Error> {
+        let statement = dleq_statement(P, Z, T);
+        if !Dleq.holds(&statement, p) {
+            return Err(Error::WitnessMismatch);
+        }
+        let transcript = Transcript::new(DLEQ_LABEL, context);
+        let ((J, J_prime), Proof { z, .. }) =
+            prove_with_commitment(&Dleq, &transcript, &statement, p, None);
+        Ok(PiCommitment { J, J_prime, i: z })
+    }
+
+    /// Verifies a DLEQ proof in commitment form by recomputing its challenge `e` and checking
+    /// $iG = J + eP$ and $iT = J' + eZ$.
+    ///
+    /// # Arguments
+    /// * `P` - Public key
+    /// * `Z` - Tweaked public key
+    /// * `T` - Tweak point
+    /// * `pi` - Proof in commitment form
+    /// * `context` - Session context the proof was made with
+    ///
+    /// # Returns
+    /// * `Result<(), VerifyError>` - `Ok(())` if the proof is valid, otherwise
+    ///   `VerifyError::InvalidProof`
+    pub fn verify_proof_committed_detailed(
+        &self,
+        P: &ProjectivePoint,
+        Z: &ProjectivePoint,
+        T: &ProjectivePoint,
+        pi: &PiCommitment,
+        context: Option<&[u8]>,
+    ) -> Result<(), VerifyError> {
+        verify_pre_signature_dleq(DLEQ_LABEL, &dleq_statement(P, Z, T), pi, context)
+    }
+
+    /// Verifies a DLEQ proof in commitment form, see
+    /// [`ECDSA::verify_proof_committed_detailed`].
+    pub fn verify_proof_committed(
+        &self,
+        P: &ProjectivePoint,
+        Z: &ProjectivePoint,
+        T: &ProjectivePoint,
+        pi: &PiCommitment,
+        context: Option<&[u8]>,
+    ) -> bool {
+        self.verify_proof_committed_detailed(P, Z, T, pi, context)
+            .is_ok()
+    }
+}
+
+impl<Rel, Suite> ZKP for ECDSA<Rel, Suite> {
+    /// Computes the Fiat–Shamir challenge `e` of the DLEQ proof, i.e. the [`Dleq`] challenge
+    /// for the statement `(G, P, T, Z)` and the commitment `(J, J')`.
     ///
     /// # Arguments
     /// * `P` - Public key corresponding to secret `p`
     /// * `Z` - Tweaked public key (T * p)
     /// * `T` - Tweak point
     /// * `J`, `J_prime` - Commitment points used in the proof
+    /// * `context` - Optional session context
     ///
     /// # Returns
     /// * `Scalar` - The derived challenge value
@@ -32,21 +557,10 @@ impl ZKP for ECDSA {
         T: &ProjectivePoint,
         J: &ProjectivePoint,
         J_prime: &ProjectivePoint,
+        context: Option<&[u8]>,
     ) -> Scalar {
-        let mut hasher = Sha256::new(); //init hasher
-        hasher.update(
-            ProjectivePoint::GENERATOR
-                .to_affine()
-                .to_encoded_point(false)
-                .as_bytes(),
-        ); // add G
-        hasher.update(T.to_affine().to_encoded_point(false).as_bytes()); // add T
-        hasher.update(P.to_affine().to_encoded_point(false).as_bytes()); // add P
-        hasher.update(Z.to_affine().to_encoded_point(false).as_bytes()); // add Z
-        hasher.update(J.to_affine().to_encoded_point(false).as_bytes()); // add J
-        hasher.update(J_prime.to_affine().to_encoded_point(false).as_bytes()); // add J'
-        let hash: [u8; 32] = hasher.finalize().into();
-        <Scalar as Reduce<U256>>::reduce_bytes(&hash.into())
+        let transcript = Transcript::new(DLEQ_LABEL, context);
+        Dleq.challenge(&transcript, &dleq_statement(P, Z, T), &(*J, *J_prime))
     }
 
     /// Generates a zero-knowledge proof `Pi` that the prover has set `Z ` such that $\log_T(Z) = \log_G(P)$.
@@ -56,22 +570,22 @@ impl ZKP for ECDSA {
     /// * `Z` - Public commitment (T * p)
     /// * `P` - Public key (G * p)
     /// * `T` - Tweak point
+    /// * `context` - Optional session context the proof is bound to
     ///
     /// # Returns
-    /// * `Pi` - The generated zero-knowledge proof
+    /// * `Result<Pi, Error>` - The generated zero-knowledge proof, or `Error::WitnessMismatch`
+    ///   if `p` is not the discrete log of `P` and `Z`
     fn gen_proof(
         &self,
         p: &Scalar,
         Z: &ProjectivePoint,
         P: &ProjectivePoint,
         T: &ProjectivePoint,
-    ) -> Pi {
-        let j = Scalar::random(&mut OsRng);
-        let J = ProjectivePoint::GENERATOR * j;
-        let J_prime = *T * j;
-        let e = self.compute_challenge(P, Z, T, &J, &J_prime);
-        let i = j + e * p;
-        Pi { e: e, i: i }
+        context: Option<&[u8]>,
+    ) -> Result<Pi, Error> {
+        let transcript = Transcript::new(DLEQ_LABEL, context);
+        let Proof { e, z } = Dleq.prove(&transcript, &dleq_statement(P, Z, T), p, None)?;
+        Ok(Pi { e, i: z })
     }
 
     /// Verifies a zero-knowledge proof that a prover has set `Z ` such that $\log_T(Z) = \log_G(P)$.
@@ -81,23 +595,32 @@ impl ZKP for ECDSA {
     /// * `Z` - Tweaked public key
     /// * `T` - Tweak point
     /// * `pi` - Proof object
+    /// * `context` - Session context the proof was made with
     ///
     /// # Returns
-    /// * `bool` - True if proof is valid, false otherwise
-    fn verify_proof(
+    /// * `Result<(), VerifyError>` - `Ok(())` if the proof is valid, otherwise
+    ///   `VerifyError::InvalidProof`
+    fn verify_proof_detailed(
         &self,
         P: &ProjectivePoint,
         Z: &ProjectivePoint,
         T: &ProjectivePoint,
         pi: &Pi,
-    ) -> bool {
-        let J = ProjectivePoint::GENERATOR * pi.i - (*P * pi.e);
-        let J_prime = *T * pi.i - (*Z * pi.e);
-        let e_bis = self.compute_challenge(P, Z, T, &J, &J_prime);
-        e_bis == pi.e
+        context: Option<&[u8]>,
+    ) -> Result<(), VerifyError> {
+        let transcript = Transcript::new(DLEQ_LABEL, context);
+        let proof = Proof { e: pi.e, z: pi.i };
+        Dleq.verify_detailed(&transcript, &dleq_statement(P, Z, T), &proof)
     }
 }
-impl Sign_scheme for ECDSA {
+
+impl<Rel, Suite: Ciphersuite> Sign_scheme for ECDSA<Rel, Suite> {
+    type Signature = Sigma;
+
+    fn nonce_domain(&self) -> &'static [&'static str] {
+        const { &["as_for_fde/ECDSA", Suite::ID] }
+    }
+
     /// Generates a standard ECDSA-style signature.
     ///
     /// # Arguments
@@ -106,18 +629,11 @@ impl Sign_scheme for ECDSA {
     /// * `k` - Random nonce scalar
     ///
     /// # Returns
-    /// * `Sigma` - Signature containing `(s, R)`
-    fn sign(&self, p: &Scalar, m: &str, k: &Scalar) -> Sigma {
-        if m.is_empty() {
-            panic!("Message cannot be empty.");
-        }
-        let R = ProjectivePoint::GENERATOR * k;
-        let P = ProjectivePoint::GENERATOR * p;
-        let r_x = get_x(&R);
-        let e = self.hash_challenge(&R, &P, m);
-        let k_inv = invert_scalar(k);
-        let s = k_inv * (e + *p * r_x);
-        Sigma { s: s, R: R }
+    /// * `Result<Sigma, Error>` - Signature containing `(s, R)`, or an error if `m` is empty
+    ///   or `k` is zero
+    fn sign_with_nonce(&self, p: &SecretKey, m: &[u8], k: &Scalar) -> Result<Sigma, Error> {
+        let e = self.hash_message(m)?;
+        self.sign_with_challenge(p, &e, k)
     }
 
     /// Verifies a standard ECDSA signature.
@@ -128,33 +644,105 @@ impl Sign_scheme for ECDSA {
     /// * `m` - Message that was signed
     ///
     /// # Returns
-    /// * `bool` - True if valid, false otherwise
-    fn verify_sign(&self, sigma: &Sigma, P: &ProjectivePoint, m: &str) -> bool {
-        let r_x = get_x(&sigma.R);
-        let e: Scalar = self.hash_challenge(&sigma.R, P, m);
-        let s_inv = invert_scalar(&sigma.s);
-        let rhs_point: ProjectivePoint = (ProjectivePoint::GENERATOR * e + *P * r_x) * s_inv;
-        let rhs = get_x(&rhs_point);
-        r_x == rhs
+    /// * `Result<(), VerifyError>` - `Ok(())` if the signature is valid, otherwise the reason
+    ///   it is rejected
+    fn verify_sign_detailed(
+        &self,
+        sigma: &Sigma,
+        P: &PublicKey,
+        m: &[u8],
+    ) -> Result<(), VerifyError> {
+        let e = self.hash_message(m)?;
+        self.verify_with_challenge(sigma, P, &e)
+    }
+
+    /// Signs a 32-byte digest, used directly as $H(m)$.
+    ///
+    /// # Arguments
+    /// * `p` - Secret key scalar
+    /// * `digest` - Message digest, e.g. a transaction sighash
+    /// * `aux_rand` - Optional randomness hedging the nonce
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - Signature containing `(s, R)`
+    fn sign_prehash(
+        &self,
+        p: &SecretKey,
+        digest: &[u8; 32],
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Sigma, Error> {
+        let k = Zeroizing::new(derive_nonce(
+            p.as_scalar(),
+            digest,
+            self.nonce_domain(),
+            "sign_prehash",
+            None,
+            aux_rand,
+        ));
+        self.sign_with_challenge(p, &ECDSA::reduce_digest(digest), &k)
+    }
+
+    /// Verifies a signature over a 32-byte digest, used directly as $H(m)$.
+    ///
+    /// # Arguments
+    /// * `sigma` - Signature to verify
+    /// * `P` - Signer's public key
+    /// * `digest` - Message digest that was signed
+    ///
+    /// # Returns
+    /// * `Result<(), VerifyError>` - `Ok(())` if the signature is valid, otherwise the reason
+    ///   it is rejected
+    fn verify_sign_prehash_detailed(
+        &self,
+        sigma: &Sigma,
+        P: &PublicKey,
+        digest: &[u8; 32],
+    ) -> Result<(), VerifyError> {
+        self.verify_with_challenge(sigma, P, &ECDSA::reduce_digest(digest))
+    }
+
+    /// Serializes a signature as `R || s` (65 bytes), see [`Sigma::to_bytes`].
+    ///
+    /// # Arguments
+    /// * `sigma` - Signature
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>, Error>` - Canonical encoding of the signature; never fails
+    fn encode_signature(&self, sigma: &Sigma) -> Result<Vec<u8>, Error> {
+        Ok(sigma.to_bytes().to_vec())
+    }
+
+    /// Parses a signature encoded as `R || s`, see [`Sigma::from_bytes`].
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded signature
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - The decoded signature, or an error if the length is wrong,
+    ///   `R` is off-curve or the identity, or `s` is not below the group order
+    fn decode_signature(&self, bytes: &[u8]) -> Result<Sigma, Error> {
+        Sigma::from_bytes(bytes)
     }
 }
 
-impl AS_scheme for ECDSA {
+impl<Rel: HardRelation, Suite: Ciphersuite> AS_scheme for ECDSA<Rel, Suite> {
+    type Relation = Rel;
+    type PreSignature = Sigma_prime_ECDSA;
+
     /// Hashes a message into a challenge scalar.
     ///
     /// # Arguments
     /// * `message` - The message to hash
     ///
     /// # Returns
-    /// * `Scalar` - Hash challenge scalar
-    fn hash_challenge(&self, _R: &ProjectivePoint, _P: &ProjectivePoint, message: &str) -> Scalar {
-        if message.is_empty() {
-            panic!("Message cannot be empty.");
-        }
-        let mut hasher = Sha256::new(); //init hasher
-        hasher.update(message.as_bytes()); // add message
-        let hash: [u8; 32] = hasher.finalize().into();
-        <Scalar as Reduce<U256>>::reduce_bytes(&hash.into())
+    /// * `Result<Scalar, Error>` - Hash challenge scalar, or `Error::EmptyMessage`
+    fn hash_challenge(
+        &self,
+        _R: &ProjectivePoint,
+        _P: &PublicKey,
+        message: &[u8],
+    ) -> Result<Scalar, Error> {
+        self.hash_message(message)
     }
 
     /// Produces an adaptor pre-signature `Sigma'` with a ZK proof of correctness.
@@ -166,30 +754,21 @@ impl AS_scheme for ECDSA {
     /// * `k` - Random nonce
     ///
     /// # Returns
-    /// * `Sigma_prime` - Adaptor pre-signature
-    fn pre_sign(&self, p: &Scalar, m: &str, T: &ProjectivePoint, k: &Scalar) -> Sigma_prime {
-        // s' = k⁻1(H(m)+r'_xtP)
-        // R' = k·T
-        if m.is_empty() {
-            panic!("Message cannot be empty.");
-        }
-
-        let R_prime: ProjectivePoint = T * k;
-        let R_prime_x = get_x(&R_prime);
-
-        let P: ProjectivePoint = ProjectivePoint::GENERATOR * p;
+    /// * `Result<Sigma_prime_ECDSA, Error>` - Adaptor pre-signature, or an error if `m` is empty,
+    ///   `k` is zero or `R'` is unusable
+    fn pre_sign_with_nonce(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &Rel::Statement,
+        k: &Scalar,
+    ) -> Result<Sigma_prime_ECDSA, Error> {
+        self.pre_sign_with_key(&KeyPair::new(p), m, T, k, None)
+    }
 
-        let e = self.hash_challenge(&R_prime, &P, m);
-        let k_inv = invert_scalar(&k);
-        let s_prime = k_inv * (e + R_prime_x * p);
-        let Z = T * p;
-        Sigma_prime {
-            s_prime,
-            R_prime,
-            Z,
-            pi: self.gen_proof(p, &Z, &P, T),
-            ..Default::default()
-        }
+    /// Precomputes the table of `T`, which pre-signing multiplies by the nonce and the key.
+    fn statement_table(&self, T: &Rel::Statement) -> Option<PointTable> {
+        Some(PointTable::new(&Rel::adaptor_point(T)))
     }
 
     /// Verifies the validity of an adaptor pre-signature.
@@ -201,22 +780,67 @@ impl AS_scheme for ECDSA {
     /// * `sigma_prime` - Pre-signature
     ///
     /// # Returns
-    /// * `bool` - True if pre-signature is valid
-    fn verify_pre_sign(
+    /// * `Result<(), VerifyError>` - `Ok(())` if the pre-signature is valid, otherwise the
+    ///   reason it is rejected
+    fn verify_pre_sign_detailed(
         &self,
-        P: &ProjectivePoint,
-        m: &str,
-        T: &ProjectivePoint,
-        sigma_prime: &Sigma_prime,
-    ) -> bool {
-        let r_prime_x = get_x(&sigma_prime.R_prime);
+        P: &PublicKey,
+        m: &[u8],
+        T: &Rel::Statement,
+        sigma_prime: &Sigma_prime_ECDSA,
+    ) -> Result<(), VerifyError> {
+        let e = self.hash_message(m)?;
+        self.verify_pre_sign_with_challenge(P, &e, T, sigma_prime)
+    }
 
-        let s_prime_inv = invert_scalar(&sigma_prime.s_prime);
-        let e: Scalar = self.hash_challenge(&sigma_prime.R_prime, &P, m);
-        let rhs_point: ProjectivePoint = (*T * e + sigma_prime.Z * r_prime_x) * s_prime_inv;
-        let rhs = get_x(&rhs_point);
+    /// Produces an adaptor pre-signature over a 32-byte digest, used directly as $H(m)$.
+    ///
+    /// # Arguments
+    /// * `p` - Secret key
+    /// * `digest` - Message digest, e.g. a transaction sighash
+    /// * `T` - Tweak point
+    /// * `aux_rand` - Optional randomness hedging the nonce
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime_ECDSA, Error>` - Adaptor pre-signature
+    fn pre_sign_prehash(
+        &self,
+        p: &SecretKey,
+        digest: &[u8; 32],
+        T: &Rel::Statement,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Sigma_prime_ECDSA, Error> {
+        let k = Zeroizing::new(derive_nonce(
+            p.as_scalar(),
+            digest,
+            self.nonce_domain(),
+            "pre_sign_prehash",
+            Some(&Rel::adaptor_point(T)),
+            aux_rand,
+        ));
+        let e = ECDSA::reduce_digest(digest);
+        self.pre_sign_with_challenge(p, &p.public_key(), &e, T, &k, None)
+    }
 
-        r_prime_x == rhs && self.verify_proof(P, &sigma_prime.Z, T, &sigma_prime.pi)
+    /// Verifies an adaptor pre-signature over a 32-byte digest, used directly as $H(m)$.
+    ///
+    /// # Arguments
+    /// * `P` - Public key
+    /// * `digest` - Message digest
+    /// * `T` - Tweak point
+    /// * `sigma_prime` - Pre-signature
+    ///
+    /// # Returns
+    /// * `Result<(), VerifyError>` - `Ok(())` if the pre-signature is valid, otherwise the
+    ///   reason it is rejected
+    fn verify_pre_sign_prehash_detailed(
+        &self,
+        P: &PublicKey,
+        digest: &[u8; 32],
+        T: &Rel::Statement,
+        sigma_prime: &Sigma_prime_ECDSA,
+    ) -> Result<(), VerifyError> {
+        self.verify_pre_sign_with_challenge(P, &ECDSA::reduce_digest(digest), T, sigma_prime)
     }
 
     /// Adapts a pre-signature `Sigma'` into a valid full signature using secret `t`.
@@ -226,26 +850,77 @@ impl AS_scheme for ECDSA {
     /// * `t` - Tweak scalar used to adapt the signature
     ///
     /// # Returns
-    /// * `Sigma` - Final adapted signature (s,R) such that $s = s' t^{-1}$
-    fn adapt_signature(&self, sigma_prime: &Sigma_prime, t: &Scalar) -> Sigma {
-        let t_inv = invert_scalar(t);
-        let s = sigma_prime.s_prime * t_inv;
-        Sigma {
-            s,
-            R: sigma_prime.R_prime,
-        }
+    /// * `Result<Sigma, Error>` - Final adapted signature (s,R) such that $s = s' t^{-1}$, or
+    ///   `Error::NonInvertible` if `t` is zero
+    fn adapt_signature(
+        &self,
+        sigma_prime: &Sigma_prime_ECDSA,
+        t: &Rel::Witness,
+    ) -> Result<Sigma, Error> {
+        self.adapt_s_prime(&sigma_prime.s_prime, &sigma_prime.R_prime, t)
     }
 
     /// Extracts the secret tweak `t` from a known signature and its pre-signature form.
     ///
+    /// The published `s` may have been negated by low-S normalisation or by a third party,
+    /// so both $s' s^{-1}$ and $-s' s^{-1}$ are tried and the one matching `T` is returned.
+    ///
     /// # Arguments
     /// * `sigma` - Final signature
     /// * `sigma_prime` - Pre-signature
+    /// * `T` - Tweak point the witness must match
+    ///
+    /// # Returns
+    /// * `Result<Rel::Witness, Error>` - Extracted secret tweak `t` such that $tG = T$, or
+    ///   `Error::WitnessMismatch` if neither candidate matches
+    fn extract_witness(
+        &self,
+        sigma: &Sigma,
+        sigma_prime: &Sigma_prime_ECDSA,
+        T: &Rel::Statement,
+    ) -> Result<Rel::Witness, Error> {
+        self.extract_from_s_prime(sigma, &sigma_prime.s_prime, T)
+    }
+
+    /// Serializes a pre-signature as `R' || s' || Z || pi` (196 bytes).
+    ///
+    /// # Arguments
+    /// * `sigma_prime` - Pre-signature
     ///
     /// # Returns
-    /// * `Scalar` - Extracted secret tweak `t` such that $t = s' s^{-1}$
-    fn extract_witness(&self, sigma: &Sigma, sigma_prime: &Sigma_prime) -> Scalar {
-        let s_inv: Scalar = invert_scalar(&sigma.s);
-        sigma_prime.s_prime * s_inv
+    /// * `Result<Vec<u8>, Error>` - Canonical encoding of the pre-signature; never fails
+    fn encode_pre_signature(&self, sigma_prime: &Sigma_prime_ECDSA) -> Result<Vec<u8>, Error> {
+        Ok(sigma_prime.to_bytes().to_vec())
+    }
+
+    /// Parses a pre-signature encoded as `R' || s' || Z || pi`.
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded pre-signature
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime_ECDSA, Error>` - The decoded pre-signature, or an error if the length is
+    ///   wrong, a point is off-curve or the identity, or a scalar is not below the group order
+    fn decode_pre_signature(&self, bytes: &[u8]) -> Result<Sigma_prime_ECDSA, Error> {
+        Sigma_prime_ECDSA::from_bytes(bytes)
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> PreSignWithKey<Rel::Statement, Sigma_prime_ECDSA>
+    for ECDSA<Rel, Suite>
+{
+    /// Pre-signs `m` with nonce `k`, the precomputed public key of `key` and, when
+    /// given, the `table` of `T`.
+    fn pre_sign_with_key(
+        &self,
+        key: &KeyPair,
+        m: &[u8],
+        T: &Rel::Statement,
+        k: &Scalar,
+        table: Option<&PointTable>,
+    ) -> Result<Sigma_prime_ECDSA, Error> {
+        let (p, P) = (key.p, &key.P);
+        let e = self.hash_message(m)?;
+        self.pre_sign_with_challenge(p, P, &e, T, k, table)
     }
 }
@@ -0,0 +1,440 @@
+use k256::{elliptic_curve::ops::MulByGenerator, ProjectivePoint, Scalar};
+use std::marker::PhantomData;
+use zeroize::Zeroizing;
+
+use crate::ecdsa::{
+    dleq_statement, pre_signature_context, prove_pre_signature_dleq, verify_pre_signature_dleq,
+};
+use crate::msm::{msm_vartime, mul_with_table, PointTable};
+use crate::sealed::{KeyPair, PreSignWithKey};
+use crate::utils::{derive_nonce, get_x, invert_scalar};
+use crate::{
+    pre_sign_nonce, AS_scheme, Ciphersuite, DiscreteLog, Error, HardRelation, PiCommitment,
+    PublicKey, SecretKey, Sha256Suite, Sigma, Sign_scheme, VerifyError, ECDSA,
+};
+
+/// Fiat–Shamir label of the DLEQ proofs $\log_G(K) = \log_T(R')$ attached to pre-signatures,
+/// distinct from the one of the [`ECDSA`] proofs $\log_G(P) = \log_T(Z)$.
+pub(crate) const DLEQ_LABEL: &str = "as_for_fde/AumayrECDSA/dleq";
+
+/// `Sigma_prime_Aumayr` is an [`AumayrECDSA`] adaptor pre-signature `(s', R', K, pi)`, where
+/// `K = kG`, `R' = kT` and `pi` proves $\log_G(K) = \log_T(R')$. The proof is mandatory, and is
+/// sent in commitment form so that many pre-signatures can be checked at once with
+/// [`AumayrECDSA::verify_pre_sign_batch`].
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sigma_prime_Aumayr {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scalar"))]
+    pub s_prime: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))]
+    pub R_prime: ProjectivePoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))]
+    pub K: ProjectivePoint,
+    pub pi: PiCommitment,
+}
+
+impl Sigma_prime_Aumayr {
+    /// Length of the canonical encoding `R' || s' || K || pi`.
+    pub const ENCODED_LEN: usize = ECDSA::PRE_SIGNATURE_LEN;
+
+    /// Serializes the pre-signature as `R' || s' || K || pi`.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        ECDSA::encode_dleq_pre_signature(&self.s_prime, &self.R_prime, &self.K, &self.pi)
+    }
+
+    /// Parses a pre-signature encoded by [`Sigma_prime_Aumayr::to_bytes`], rejecting wrong
+    /// lengths, off-curve or identity points and scalars that are not below the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (s_prime, R_prime, K, pi) = ECDSA::decode_dleq_pre_signature(bytes)?;
+        Ok(Self {
+            s_prime,
+            R_prime,
+            K,
+            pi,
+        })
+    }
+}
+
+/// `AumayrECDSA` implements the ECDSA adaptor signature scheme of Aumayr et al.
+/// ("Generalized Bitcoin-Compatible Channels"), in which the pre-signature carries
+/// `K = kG`, `R' = kT` and a DLEQ proof that $\log_G(K) = \log_T(R')$.
+///
+/// Pre-signatures are locked to the statements of the hard relation `Rel`. Full signatures
+/// are standard ECDSA signatures, identical to those of [`ECDSA`] with the same
+/// ciphersuite `Suite`, which also hashes the messages. [`AumayrECDSA::new`] returns the
+/// scheme over [`DiscreteLog`] with [`Sha256Suite`], and `AumayrECDSA::<Rel, Suite>::default()`
+/// the scheme over another relation or ciphersuite.
+#[derive(Clone)]
+pub struct AumayrECDSA<Rel = DiscreteLog, Suite = Sha256Suite> {
+    _relation: PhantomData<Rel>,
+    _suite: PhantomData<Suite>,
+}
+
+impl AumayrECDSA {
+    /// Returns the scheme over the secp256k1 discrete-log relation with SHA-256 message
+    /// hashing.
+    pub const fn new() -> Self {
+        Self {
+            _relation: PhantomData,
+            _suite: PhantomData,
+        }
+    }
+}
+
+impl<Rel, Suite> Default for AumayrECDSA<Rel, Suite> {
+    fn default() -> Self {
+        Self {
+            _relation: PhantomData,
+            _suite: PhantomData,
+        }
+    }
+}
+
+impl<Rel, Suite> AumayrECDSA<Rel, Suite> {
+    /// The ECDSA scheme with the same relation and ciphersuite, which signs, hashes messages,
+    /// adapts and extracts for this one.
+    pub(crate) const ECDSA: ECDSA<Rel, Suite> = ECDSA {
+        _relation: PhantomData,
+        _suite: PhantomData,
+    };
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> AumayrECDSA<Rel, Suite> {
+    /// Pre-signs the message hash `e` with nonce `k` under statement `T`, multiplying `T` with
+    /// `table` when given.
+    fn pre_sign_with_challenge(
+        &self,
+        p: &SecretKey,
+        e: &Scalar,
+        T: &Rel::Statement,
+        k: &Scalar,
+        table: Option<&PointTable>,
+    ) -> Result<Sigma_prime_Aumayr, Error> {
+        let (p, T) = (p.as_scalar(), &Rel::adaptor_point(T));
+        let k_inv = Zeroizing::new(invert_scalar(k)?);
+        let K = ProjectivePoint::mul_by_generator(k);
+        let R_prime = mul_with_table(T, table, k);
+        let r = get_x(&R_prime)?;
+
+        let s_prime = *k_inv * (e + r * p);
+        let context = pre_signature_context(e, &R_prime);
+        Ok(Sigma_prime_Aumayr {
+            s_prime,
+            R_prime,
+            K,
+            pi: prove_pre_signature_dleq(
+                DLEQ_LABEL,
+                k,
+                &dleq_statement(&K, &R_prime, T),
+                table,
+                &context,
+            ),
+        })
+    }
+
+    /// Pre-signs `m` like [`AS_scheme::pre_sign`], multiplying `T` with its precomputed
+    /// `table`, see [`ECDSA::pre_sign_with_table`]. The pre-signature is the same as the one
+    /// of [`AS_scheme::pre_sign`].
+    ///
+    /// # Arguments
+    /// * `p` - Secret key
+    /// * `m` - Message
+    /// * `T` - Tweak point
+    /// * `table` - Table of the adaptor point of `T`, see [`PointTable::new`]
+    /// * `aux_rand` - Optional randomness hedging the nonce
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime_Aumayr, Error>` - Adaptor pre-signature, or an error if `m` is
+    ///   empty, `R'` is unusable or `table` is not the table of `T` (`Error::InvalidPoint`)
+    pub fn pre_sign_with_table(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &Rel::Statement,
+        table: &PointTable,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Sigma_prime_Aumayr, Error> {
+        let T_point = Rel::adaptor_point(T);
+        if *table.point() != T_point {
+            return Err(Error::InvalidPoint);
+        }
+        let e = Self::ECDSA.hash_message(m)?;
+        let k = pre_sign_nonce(self, p, m, &T_point, aux_rand);
+        self.pre_sign_with_challenge(p, &e, T, &k, Some(table))
+    }
+
+    /// Verifies a pre-signature over the message hash `e` and its DLEQ proof.
+    fn verify_pre_sign_with_challenge(
+        &self,
+        P: &PublicKey,
+        e: &Scalar,
+        T: &Rel::Statement,
+        sigma_prime: &Sigma_prime_Aumayr,
+    ) -> Result<(), VerifyError> {
+        let (P, T) = (P.as_point(), &Rel::adaptor_point(T));
+        if *P == ProjectivePoint::IDENTITY
+            || sigma_prime.K == ProjectivePoint::IDENTITY
+            || sigma_prime.R_prime == ProjectivePoint::IDENTITY
+        {
+            return Err(VerifyError::IdentityPoint);
+        }
+        let s_prime_inv = invert_scalar(&sigma_prime.s_prime)?;
+        let r = get_x(&sigma_prime.R_prime)?;
+        let K = msm_vartime(&[
+            (ProjectivePoint::GENERATOR, *e * s_prime_inv),
+            (*P, r * s_prime_inv),
+        ]);
+
+        if K != sigma_prime.K {
+            return Err(VerifyError::EquationMismatch);
+        }
+        let context = pre_signature_context(e, &sigma_prime.R_prime);
+        verify_pre_signature_dleq(
+            DLEQ_LABEL,
+            &dleq_statement(&sigma_prime.K, &sigma_prime.R_prime, T),
+            &sigma_prime.pi,
+            Some(&context),
+        )
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> Sign_scheme for AumayrECDSA<Rel, Suite> {
+    type Signature = Sigma;
+
+    fn nonce_domain(&self) -> &'static [&'static str] {
+        const { &["as_for_fde/AumayrECDSA", Suite::ID] }
+    }
+
+    /// Generates a standard ECDSA signature, see [`ECDSA::sign_with_nonce`](Sign_scheme::sign_with_nonce).
+    fn sign_with_nonce(&self, p: &SecretKey, m: &[u8], k: &Scalar) -> Result<Sigma, Error> {
+        Self::ECDSA.sign_with_nonce(p, m, k)
+    }
+
+    /// Verifies a standard ECDSA signature, see
+    /// [`ECDSA::verify_sign_detailed`](Sign_scheme::verify_sign_detailed).
+    fn verify_sign_detailed(
+        &self,
+        sigma: &Sigma,
+        P: &PublicKey,
+        m: &[u8],
+    ) -> Result<(), VerifyError> {
+        Self::ECDSA.verify_sign_detailed(sigma, P, m)
+    }
+
+    /// Signs a 32-byte digest like [`ECDSA::sign_prehash`](Sign_scheme::sign_prehash), with a
+    /// nonce derived under the nonce domain of this scheme, as [`Sign_scheme::sign`] does.
+    fn sign_prehash(
+        &self,
+        p: &SecretKey,
+        digest: &[u8; 32],
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Sigma, Error> {
+        let k = Zeroizing::new(derive_nonce(
+            p.as_scalar(),
+            digest,
+            self.nonce_domain(),
+            "sign_prehash",
+            None,
+            aux_rand,
+        ));
+        Self::ECDSA.sign_with_challenge(p, &ECDSA::reduce_digest(digest), &k)
+    }
+
+    /// Verifies a signature over a 32-byte digest, see
+    /// [`ECDSA::verify_sign_prehash_detailed`](Sign_scheme::verify_sign_prehash_detailed).
+    fn verify_sign_prehash_detailed(
+        &self,
+        sigma: &Sigma,
+        P: &PublicKey,
+        digest: &[u8; 32],
+    ) -> Result<(), VerifyError> {
+        Self::ECDSA.verify_sign_prehash_detailed(sigma, P, digest)
+    }
+
+    /// Serializes a signature as `R || s`, see [`ECDSA::encode_signature`](Sign_scheme::encode_signature).
+    fn encode_signature(&self, sigma: &Sigma) -> Result<Vec<u8>, Error> {
+        Self::ECDSA.encode_signature(sigma)
+    }
+
+    /// Parses a signature encoded as `R || s`, see
+    /// [`ECDSA::decode_signature`](Sign_scheme::decode_signature).
+    fn decode_signature(&self, bytes: &[u8]) -> Result<Sigma, Error> {
+        Self::ECDSA.decode_signature(bytes)
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> AS_scheme for AumayrECDSA<Rel, Suite> {
+    type Relation = Rel;
+    type PreSignature = Sigma_prime_Aumayr;
+
+    /// Hashes a message into a challenge scalar, see [`ECDSA::hash_challenge`](AS_scheme::hash_challenge).
+    fn hash_challenge(
+        &self,
+        R: &ProjectivePoint,
+        P: &PublicKey,
+        message: &[u8],
+    ) -> Result<Scalar, Error> {
+        Self::ECDSA.hash_challenge(R, P, message)
+    }
+
+    /// Produces an adaptor pre-signature `(s', R', K, pi)` where `K = kG`, `R' = kT`,
+    /// $s' = k^{-1}(H(m) + x(R') p)$ and `pi` proves $\log_G(K) = \log_T(R')$.
+    ///
+    /// # Arguments
+    /// * `p` - Secret key
+    /// * `m` - Message
+    /// * `T` - Tweak point
+    /// * `k` - Random nonce
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime_Aumayr, Error>` - Adaptor pre-signature, or an error if `m` is empty,
+    ///   `k` is zero or `R'` is unusable
+    fn pre_sign_with_nonce(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &Rel::Statement,
+        k: &Scalar,
+    ) -> Result<Sigma_prime_Aumayr, Error> {
+        let e = Self::ECDSA.hash_message(m)?;
+        self.pre_sign_with_challenge(p, &e, T, k, None)
+    }
+
+    /// Precomputes the table of `T`, which pre-signing multiplies by the nonce.
+    fn statement_table(&self, T: &Rel::Statement) -> Option<PointTable> {
+        Some(PointTable::new(&Rel::adaptor_point(T)))
+    }
+
+    /// Verifies an adaptor pre-signature by checking $K = s'^{-1}(H(m)G + x(R')P)$
+    /// and the DLEQ proof that $\log_G(K) = \log_T(R')$.
+    ///
+    /// # Arguments
+    /// * `P` - Public key
+    /// * `m` - Message
+    /// * `T` - Tweak point
+    /// * `sigma_prime` - Pre-signature
+    ///
+    /// # Returns
+    /// * `Result<(), VerifyError>` - `Ok(())` if the pre-signature is valid, otherwise the
+    ///   reason it is rejected
+    fn verify_pre_sign_detailed(
+        &self,
+        P: &PublicKey,
+        m: &[u8],
+        T: &Rel::Statement,
+        sigma_prime: &Sigma_prime_Aumayr,
+    ) -> Result<(), VerifyError> {
+        let e = Self::ECDSA.hash_message(m)?;
+        self.verify_pre_sign_with_challenge(P, &e, T, sigma_prime)
+    }
+
+    /// Produces an adaptor pre-signature over a 32-byte digest, used directly as $H(m)$.
+    ///
+    /// # Arguments
+    /// * `p` - Secret key
+    /// * `digest` - Message digest, e.g. a transaction sighash
+    /// * `T` - Tweak point
+    /// * `aux_rand` - Optional randomness hedging the nonce
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime_Aumayr, Error>` - Adaptor pre-signature
+    fn pre_sign_prehash(
+        &self,
+        p: &SecretKey,
+        digest: &[u8; 32],
+        T: &Rel::Statement,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Sigma_prime_Aumayr, Error> {
+        let k = Zeroizing::new(derive_nonce(
+            p.as_scalar(),
+            digest,
+            self.nonce_domain(),
+            "pre_sign_prehash",
+            Some(&Rel::adaptor_point(T)),
+            aux_rand,
+        ));
+        self.pre_sign_with_challenge(p, &ECDSA::reduce_digest(digest), T, &k, None)
+    }
+
+    /// Verifies an adaptor pre-signature over a 32-byte digest, used directly as $H(m)$.
+    ///
+    /// # Arguments
+    /// * `P` - Public key
+    /// * `digest` - Message digest
+    /// * `T` - Tweak point
+    /// * `sigma_prime` - Pre-signature
+    ///
+    /// # Returns
+    /// * `Result<(), VerifyError>` - `Ok(())` if the pre-signature is valid, otherwise the
+    ///   reason it is rejected
+    fn verify_pre_sign_prehash_detailed(
+        &self,
+        P: &PublicKey,
+        digest: &[u8; 32],
+        T: &Rel::Statement,
+        sigma_prime: &Sigma_prime_Aumayr,
+    ) -> Result<(), VerifyError> {
+        self.verify_pre_sign_with_challenge(P, &ECDSA::reduce_digest(digest), T, sigma_prime)
+    }
+
+    /// Adapts a pre-signature into a valid ECDSA signature, see
+    /// [`ECDSA::adapt_signature`](AS_scheme::adapt_signature).
+    fn adapt_signature(
+        &self,
+        sigma_prime: &Sigma_prime_Aumayr,
+        t: &Rel::Witness,
+    ) -> Result<Sigma, Error> {
+        Self::ECDSA.adapt_s_prime(&sigma_prime.s_prime, &sigma_prime.R_prime, t)
+    }
+
+    /// Extracts the secret tweak `t`, see [`ECDSA::extract_witness`](AS_scheme::extract_witness).
+    fn extract_witness(
+        &self,
+        sigma: &Sigma,
+        sigma_prime: &Sigma_prime_Aumayr,
+        T: &Rel::Statement,
+    ) -> Result<Rel::Witness, Error> {
+        Self::ECDSA.extract_from_s_prime(sigma, &sigma_prime.s_prime, T)
+    }
+
+    /// Serializes a pre-signature as `R' || s' || K || pi` (196 bytes).
+    ///
+    /// # Arguments
+    /// * `sigma_prime` - Pre-signature
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>, Error>` - Canonical encoding of the pre-signature; never fails
+    fn encode_pre_signature(&self, sigma_prime: &Sigma_prime_Aumayr) -> Result<Vec<u8>, Error> {
+        Ok(sigma_prime.to_bytes().to_vec())
+    }
+
+    /// Parses a pre-signature encoded as `R' || s' || K || pi`.
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded pre-signature
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime_Aumayr, Error>` - The decoded pre-signature, or an error if the length is
+    ///   wrong, a point is off-curve or the identity, or a scalar is not below the group order
+    fn decode_pre_signature(&self, bytes: &[u8]) -> Result<Sigma_prime_Aumayr, Error> {
+        Sigma_prime_Aumayr::from_bytes(bytes)
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> PreSignWithKey<Rel::Statement, Sigma_prime_Aumayr>
+    for AumayrECDSA<Rel, Suite>
+{
+    /// Pre-signs `m` with nonce `k`, multiplying `T` with `table` when given. The public key is
+    /// not part of the pre-signature, so only the secret key of `key` is used.
+    fn pre_sign_with_key(
+        &self,
+        key: &KeyPair,
+        m: &[u8],
+        T: &Rel::Statement,
+        k: &Scalar,
+        table: Option<&PointTable>,
+    ) -> Result<Sigma_prime_Aumayr, Error> {
+        let e = Self::ECDSA.hash_message(m)?;
+        self.pre_sign_with_challenge(key.p, &e, T, k, table)
+    }
+}
@@ -0,0 +1,137 @@
+use std::fmt;
+
+/// `Error` lists the ways an operation of the adaptor signature schemes can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// A point is the identity, off the curve or not the point expected.
+    InvalidPoint,
+    /// A scalar is zero or out of range where a valid non-zero scalar is required.
+    InvalidScalar,
+    /// A scalar that must be inverted is zero.
+    NonInvertible,
+    /// The message to sign is empty.
+    EmptyMessage,
+    /// A zero-knowledge proof does not verify.
+    InvalidProof,
+    /// A witness does not satisfy its statement, e.g. an extracted `t` with $tG \neq T$ or a
+    /// witness passed to a proof of knowledge.
+    WitnessMismatch,
+    /// An encoding is shorter or longer than the fixed length of its type.
+    InvalidLength,
+    /// A signature or pre-signature of one scheme was passed to another [`Scheme`](crate::Scheme).
+    SchemeMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Error::InvalidPoint => "invalid point: identity, off-curve or unexpected",
+            Error::InvalidScalar => "invalid scalar: zero or out of range",
+            Error::NonInvertible => "scalar is not invertible (zero)",
+            Error::EmptyMessage => "message cannot be empty",
+            Error::InvalidProof => "zero-knowledge proof is invalid",
+            Error::WitnessMismatch => "witness does not match the statement",
+            Error::InvalidLength => "encoding has the wrong length",
+            Error::SchemeMismatch => "value belongs to a different scheme",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// `VerifyError` gives the reason a signature, pre-signature or proof was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The signed message is empty.
+    EmptyMessage,
+    /// A public key, nonce or commitment point is the identity.
+    IdentityPoint,
+    /// A scalar that must be non-zero is zero.
+    ZeroScalar,
+    /// A value is not in its canonical form, e.g. a point that does not decode or a BIP-340
+    /// nonce with an odd Y coordinate.
+    NonCanonicalEncoding,
+    /// The verification equation does not hold.
+    EquationMismatch,
+    /// The DLEQ proof attached to the pre-signature does not verify.
+    InvalidProof,
+    /// The signature or pre-signature belongs to a different [`Scheme`](crate::Scheme).
+    SchemeMismatch,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            VerifyError::EmptyMessage => "message is empty",
+            VerifyError::IdentityPoint => "a point is the identity",
+            VerifyError::ZeroScalar => "a scalar is zero",
+            VerifyError::NonCanonicalEncoding => "a value is not canonically encoded",
+            VerifyError::EquationMismatch => "verification equation does not hold",
+            VerifyError::InvalidProof => "DLEQ proof is invalid",
+            VerifyError::SchemeMismatch => "value belongs to a different scheme",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl From<Error> for VerifyError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::InvalidPoint | Error::InvalidLength => VerifyError::NonCanonicalEncoding,
+            Error::InvalidScalar | Error::NonInvertible => VerifyError::ZeroScalar,
+            Error::EmptyMessage => VerifyError::EmptyMessage,
+            Error::InvalidProof => VerifyError::InvalidProof,
+            Error::WitnessMismatch => VerifyError::EquationMismatch,
+            Error::SchemeMismatch => VerifyError::SchemeMismatch,
+        }
+    }
+}
+
+/// `NonceManagerError` gives the reason a [`NonceManager`](crate::NonceManager) could not
+/// issue a pre-signature.
+#[derive(Debug)]
+pub enum NonceManagerError {
+    /// The file of issued pre-signatures could not be read, written or locked.
+    Io(std::io::Error),
+    /// The file of issued pre-signatures is not a store, a record in it fails its checksum,
+    /// or the pre-signature stored for the same scheme, key, message and statement does not
+    /// decode or verify.
+    Corrupt,
+    /// Pre-signing failed.
+    Signing(Error),
+}
+
+impl fmt::Display for NonceManagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NonceManagerError::Io(err) => write!(f, "nonce store: {}", err),
+            NonceManagerError::Corrupt => f.write_str("nonce store is corrupt"),
+            NonceManagerError::Signing(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for NonceManagerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NonceManagerError::Io(err) => Some(err),
+            NonceManagerError::Corrupt => None,
+            NonceManagerError::Signing(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for NonceManagerError {
+    fn from(err: std::io::Error) -> Self {
+        NonceManagerError::Io(err)
+    }
+}
+
+impl From<Error> for NonceManagerError {
+    fn from(err: Error) -> Self {
+        NonceManagerError::Signing(err)
+    }
+}
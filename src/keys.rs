@@ -0,0 +1,211 @@
+use std::fmt;
+
+use k256::{elliptic_curve::ops::MulByGenerator, ProjectivePoint, Scalar};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::utils::{
+    decode_point, decode_scalar, encode_point, encode_scalar, POINT_LEN, SCALAR_LEN,
+};
+use crate::Error;
+
+/// `SecretKey` is a signing key `p`. It is wiped from memory when dropped, and its `Debug`
+/// and `Display` output is redacted.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretKey(Scalar);
+
+impl SecretKey {
+    /// Returns the underlying scalar `p`.
+    pub fn as_scalar(&self) -> &Scalar {
+        &self.0
+    }
+
+    /// Returns the public key $P = pG$.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(ProjectivePoint::mul_by_generator(&self.0))
+    }
+
+    /// Serializes the key as a 32-byte big-endian scalar.
+    pub fn to_bytes(&self) -> [u8; SCALAR_LEN] {
+        encode_scalar(&self.0)
+    }
+
+    /// Parses a key encoded by [`SecretKey::to_bytes`], rejecting wrong lengths and scalars
+    /// that are zero or not below the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let p = decode_scalar(bytes)?;
+        if p == Scalar::ZERO {
+            return Err(Error::InvalidScalar);
+        }
+        Ok(Self(p))
+    }
+}
+
+impl From<Scalar> for SecretKey {
+    fn from(p: Scalar) -> Self {
+        Self(p)
+    }
+}
+
+impl From<SecretKey> for Scalar {
+    fn from(p: SecretKey) -> Self {
+        p.0
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretKey {}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(<redacted>)")
+    }
+}
+
+impl fmt::Display for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// `PublicKey` is a verification key $P = pG$.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PublicKey(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))] ProjectivePoint,
+);
+
+impl PublicKey {
+    /// Returns the underlying point `P`.
+    pub fn as_point(&self) -> &ProjectivePoint {
+        &self.0
+    }
+
+    /// Serializes the key as a compressed SEC1 point.
+    pub fn to_bytes(&self) -> [u8; POINT_LEN] {
+        encode_point(&self.0)
+    }
+
+    /// Parses a key encoded by [`PublicKey::to_bytes`], rejecting wrong lengths and
+    /// off-curve or identity points.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        decode_point(bytes).map(Self)
+    }
+}
+
+impl From<ProjectivePoint> for PublicKey {
+    fn from(P: ProjectivePoint) -> Self {
+        Self(P)
+    }
+}
+
+impl From<PublicKey> for ProjectivePoint {
+    fn from(P: PublicKey) -> Self {
+        P.0
+    }
+}
+
+/// `AdaptorWitness` is the secret `t` that adapts a pre-signature into a signature. Like
+/// [`SecretKey`], it is wiped on drop and redacted when printed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AdaptorWitness(Scalar);
+
+impl AdaptorWitness {
+    /// Returns the underlying scalar `t`.
+    pub fn as_scalar(&self) -> &Scalar {
+        &self.0
+    }
+
+    /// Returns the statement $T = tG$.
+    pub fn statement(&self) -> AdaptorStatement {
+        AdaptorStatement(ProjectivePoint::mul_by_generator(&self.0))
+    }
+
+    /// Serializes the witness as a 32-byte big-endian scalar.
+    pub fn to_bytes(&self) -> [u8; SCALAR_LEN] {
+        encode_scalar(&self.0)
+    }
+
+    /// Parses a witness encoded by [`AdaptorWitness::to_bytes`], rejecting wrong lengths and
+    /// scalars that are zero or not below the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let t = decode_scalar(bytes)?;
+        if t == Scalar::ZERO {
+            return Err(Error::InvalidScalar);
+        }
+        Ok(Self(t))
+    }
+}
+
+impl From<Scalar> for AdaptorWitness {
+    fn from(t: Scalar) -> Self {
+        Self(t)
+    }
+}
+
+impl From<AdaptorWitness> for Scalar {
+    fn from(t: AdaptorWitness) -> Self {
+        t.0
+    }
+}
+
+impl Drop for AdaptorWitness {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for AdaptorWitness {}
+
+impl fmt::Debug for AdaptorWitness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AdaptorWitness(<redacted>)")
+    }
+}
+
+impl fmt::Display for AdaptorWitness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// `AdaptorStatement` is the point $T = tG$ a pre-signature is locked to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptorStatement(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))] ProjectivePoint,
+);
+
+impl AdaptorStatement {
+    /// Returns the underlying point `T`.
+    pub fn as_point(&self) -> &ProjectivePoint {
+        &self.0
+    }
+
+    /// Serializes the statement as a compressed SEC1 point.
+    pub fn to_bytes(&self) -> [u8; POINT_LEN] {
+        encode_point(&self.0)
+    }
+
+    /// Parses a statement encoded by [`AdaptorStatement::to_bytes`], rejecting wrong lengths
+    /// and off-curve or identity points.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        decode_point(bytes).map(Self)
+    }
+}
+
+impl From<ProjectivePoint> for AdaptorStatement {
+    fn from(T: ProjectivePoint) -> Self {
+        Self(T)
+    }
+}
+
+impl From<AdaptorStatement> for ProjectivePoint {
+    fn from(T: AdaptorStatement) -> Self {
+        T.0
+    }
+}
@@ -1,41 +1,143 @@
 #![allow(non_snake_case)]
 #![allow(non_camel_case_types)]
 
+pub mod batch;
+pub mod bip340;
+pub mod ciphersuite;
 pub mod ecdsa;
+pub mod ecdsa_aumayr;
+pub mod error;
+pub mod keys;
+pub mod msm;
+pub mod nonce_manager;
+pub mod nonce_reuse;
+pub mod relation;
 pub mod scheme;
 pub mod schnorr;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod sigma;
+pub mod transcript;
 pub mod utils;
 
-pub use ecdsa::ECDSA;
-pub use scheme::Scheme;
+pub use batch::{BatchFailures, PreSignRequest, PreSignatureItem, ProofItem, SignatureItem};
+pub use bip340::BIP340;
+#[cfg(feature = "blake2")]
+pub use ciphersuite::Blake2sSuite;
+#[cfg(feature = "keccak")]
+pub use ciphersuite::Keccak256Suite;
+pub use ciphersuite::{Ciphersuite, Sha256Suite, Sha512Suite};
+pub use ecdsa::{Sigma_prime_ECDSA, ECDSA};
+pub use ecdsa_aumayr::{AumayrECDSA, Sigma_prime_Aumayr};
+pub use error::{Error, NonceManagerError, VerifyError};
+pub use keys::{AdaptorStatement, AdaptorWitness, PublicKey, SecretKey};
+pub use msm::PointTable;
+pub use nonce_manager::NonceManager;
+pub use nonce_reuse::{NonceReuse, Observation};
+pub use relation::{DiscreteLog, HardRelation};
+pub use scheme::{Scheme, SchemePreSignature, SchemeSignature};
 pub use schnorr::Schnorr;
+pub use sigma::SigmaProtocol;
+pub use transcript::Transcript;
 
-use k256::{ProjectivePoint, Scalar};
+use k256::{NonZeroScalar, ProjectivePoint, Scalar};
+use rand_core::{CryptoRng, RngCore};
+use sigma::{DLog, DLogStatement, Proof};
+use utils::{
+    decode_point, decode_scalar, derive_nonce, encode_point, encode_scalar, POINT_LEN, SCALAR_LEN,
+};
+use zeroize::Zeroizing;
+
+mod sealed {
+    use crate::msm::PointTable;
+    use crate::{Error, PublicKey, SecretKey};
+    use k256::Scalar;
+
+    /// A secret key `p` with its public key `P`, for [`PreSignWithKey`]. Only this crate can
+    /// build one, always with the public key of `p`.
+    pub struct KeyPair<'a> {
+        pub(crate) p: &'a SecretKey,
+        pub(crate) P: PublicKey,
+    }
+
+    impl<'a> KeyPair<'a> {
+        /// Pairs `p` with its public key.
+        pub(crate) fn new(p: &'a SecretKey) -> Self {
+            Self {
+                p,
+                P: p.public_key(),
+            }
+        }
+    }
+
+    /// Pre-signing with a key pair whose public key is already computed and, when given, the
+    /// `table` of the adaptor point of `T` from [`AS_scheme::statement_table`], which
+    /// [`AS_scheme::pre_sign_batch`] computes once for many requests. It is a supertrait of
+    /// [`AS_scheme`] that only the schemes of this crate implement, which seals `AS_scheme`.
+    ///
+    /// [`AS_scheme`]: crate::AS_scheme
+    /// [`AS_scheme::statement_table`]: crate::AS_scheme::statement_table
+    /// [`AS_scheme::pre_sign_batch`]: crate::AS_scheme::pre_sign_batch
+    ///
+    /// Only this crate can build a [`KeyPair`], so the public key cannot be one of another
+    /// secret key, with which two pre-signatures sharing a nonce would leak the secret key.
+    pub trait PreSignWithKey<Statement, PreSignature> {
+        /// Pre-signs `m` under `T` with the caller-supplied nonce `r_prime`, see
+        /// [`AS_scheme::pre_sign_with_nonce`](crate::AS_scheme::pre_sign_with_nonce).
+        fn pre_sign_with_key(
+            &self,
+            key: &KeyPair,
+            m: &[u8],
+            T: &Statement,
+            r_prime: &Scalar,
+            table: Option<&PointTable>,
+        ) -> Result<PreSignature, Error>;
+    }
+}
 
 // Common structs
 
+/// `Sigma_prime` is a Schnorr or BIP-340 adaptor pre-signature `(s', R')`. The ECDSA
+/// schemes carry a proof and use [`Sigma_prime_ECDSA`] and [`Sigma_prime_Aumayr`] instead.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sigma_prime {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scalar"))]
     pub s_prime: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))]
     pub R_prime: ProjectivePoint,
-    pub Z: ProjectivePoint,
-    pub pi: Pi,
 }
 
-impl Default for Sigma_prime {
-    fn default() -> Self {
-        Self {
-            s_prime: Scalar::ZERO,
-            R_prime: ProjectivePoint::IDENTITY,
-            Z: ProjectivePoint::IDENTITY,
-            pi: Pi::default(),
+impl Sigma_prime {
+    /// Length of the canonical encoding `R' || s'`.
+    pub const ENCODED_LEN: usize = POINT_LEN + SCALAR_LEN;
+
+    /// Serializes the pre-signature as `R' || s'`, the layout of [`Sigma::to_bytes`].
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        Sigma {
+            s: self.s_prime,
+            R: self.R_prime,
         }
+        .to_bytes()
+    }
+
+    /// Parses a pre-signature encoded by [`Sigma_prime::to_bytes`], rejecting wrong lengths,
+    /// off-curve or identity points and scalars that are not below the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let Sigma { s, R } = Sigma::from_bytes(bytes)?;
+        Ok(Self {
+            s_prime: s,
+            R_prime: R,
+        })
     }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sigma {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scalar"))]
     pub s: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))]
     pub R: ProjectivePoint,
 }
 
@@ -48,9 +150,37 @@ impl Default for Sigma {
     }
 }
 
+impl Sigma {
+    /// Length of the canonical encoding `R || s`.
+    pub const ENCODED_LEN: usize = POINT_LEN + SCALAR_LEN;
+
+    /// Serializes the signature as `R || s`: a compressed SEC1 point followed by a
+    /// 32-byte big-endian scalar.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0u8; Self::ENCODED_LEN];
+        bytes[..POINT_LEN].copy_from_slice(&encode_point(&self.R));
+        bytes[POINT_LEN..].copy_from_slice(&encode_scalar(&self.s));
+        bytes
+    }
+
+    /// Parses a signature encoded by [`Sigma::to_bytes`], rejecting wrong lengths,
+    /// off-curve or identity points and scalars that are not below the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(Error::InvalidLength);
+        }
+        let R = decode_point(&bytes[..POINT_LEN])?;
+        let s = decode_scalar(&bytes[POINT_LEN..])?;
+        Ok(Self { s, R })
+    }
+}
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pi {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scalar"))]
     pub e: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scalar"))]
     pub i: Scalar,
 }
 
@@ -63,8 +193,178 @@ impl Default for Pi {
     }
 }
 
+impl Pi {
+    /// Length of the canonical encoding `e || i`.
+    pub const ENCODED_LEN: usize = 2 * SCALAR_LEN;
+
+    /// Serializes the proof as `e || i`, two 32-byte big-endian scalars.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0u8; Self::ENCODED_LEN];
+        bytes[..SCALAR_LEN].copy_from_slice(&encode_scalar(&self.e));
+        bytes[SCALAR_LEN..].copy_from_slice(&encode_scalar(&self.i));
+        bytes
+    }
+
+    /// Parses a proof encoded by [`Pi::to_bytes`], rejecting wrong lengths and scalars that
+    /// are not below the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(Error::InvalidLength);
+        }
+        let e = decode_scalar(&bytes[..SCALAR_LEN])?;
+        let i = decode_scalar(&bytes[SCALAR_LEN..])?;
+        Ok(Self { e, i })
+    }
+}
+
+/// `PiCommitment` is the DLEQ proof of [`Pi`] in commitment form `(J, J', i)`: it carries the
+/// commitment instead of the challenge `e`, which the verifier recomputes from it. It is 34
+/// bytes longer than `Pi`, but its equations $iG = J + eP$ and $iT = J' + eZ$ are linear in
+/// the proof, so many proofs can be checked at once with [`ECDSA::verify_proof_batch`]. The
+/// ECDSA pre-signatures carry their proofs in this form, see [`ECDSA::verify_pre_sign_batch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PiCommitment {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))]
+    pub J: ProjectivePoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::point"))]
+    pub J_prime: ProjectivePoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scalar"))]
+    pub i: Scalar,
+}
+
+impl PiCommitment {
+    /// Length of the canonical encoding `J || J' || i`.
+    pub const ENCODED_LEN: usize = 2 * POINT_LEN + SCALAR_LEN;
+
+    /// Serializes the proof as `J || J' || i`.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0u8; Self::ENCODED_LEN];
+        bytes[..POINT_LEN].copy_from_slice(&encode_point(&self.J));
+        bytes[POINT_LEN..2 * POINT_LEN].copy_from_slice(&encode_point(&self.J_prime));
+        bytes[2 * POINT_LEN..].copy_from_slice(&encode_scalar(&self.i));
+        bytes
+    }
+
+    /// Parses a proof encoded by [`PiCommitment::to_bytes`], rejecting wrong lengths,
+    /// off-curve or identity points and scalars that are not below the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(Error::InvalidLength);
+        }
+        Ok(Self {
+            J: decode_point(&bytes[..POINT_LEN])?,
+            J_prime: decode_point(&bytes[POINT_LEN..2 * POINT_LEN])?,
+            i: decode_scalar(&bytes[2 * POINT_LEN..])?,
+        })
+    }
+}
+
+/// `PoK` is a non-interactive Schnorr proof of knowledge of the discrete log `x` of a point
+/// $X = xG$, such as a witness `t` for its statement `T`. It is the [`DLog`] Sigma protocol
+/// made non-interactive with Fiat–Shamir: `e` is derived from a [`Transcript`] of `G`, `X`
+/// and $R = rG$ for a nonce `r`, and $s = r + ex$.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoK {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scalar"))]
+    pub e: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::scalar"))]
+    pub s: Scalar,
+}
+
+impl PoK {
+    /// Length of the canonical encoding `e || s`.
+    pub const ENCODED_LEN: usize = 2 * SCALAR_LEN;
+
+    /// Fiat–Shamir label of the proof.
+    const LABEL: &'static str = "as_for_fde/PoK";
+
+    fn transcript(context: Option<&[u8]>) -> Transcript {
+        Transcript::new(Self::LABEL, context)
+    }
+
+    fn statement(X: &ProjectivePoint) -> DLogStatement {
+        DLogStatement {
+            G: ProjectivePoint::GENERATOR,
+            X: *X,
+        }
+    }
+
+    /// Proves knowledge of `x` such that $X = xG$.
+    ///
+    /// # Arguments
+    /// * `x` - Secret discrete log
+    /// * `X` - Public point `xG`
+    /// * `context` - Optional session context the proof is bound to
+    /// * `aux_rand` - Optional randomness hedging the nonce
+    ///
+    /// # Returns
+    /// * `Result<PoK, Error>` - The proof `(e, s)`, or `Error::WitnessMismatch` if
+    ///   $X \neq xG$
+    pub fn prove(
+        x: &Scalar,
+        X: &ProjectivePoint,
+        context: Option<&[u8]>,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Self, Error> {
+        let transcript = Self::transcript(context);
+        let Proof { e, z } = DLog.prove(&transcript, &Self::statement(X), x, aux_rand)?;
+        Ok(Self { e, s: z })
+    }
+
+    /// Verifies the proof by recomputing $R = sG - eX$ and checking that the transcript gives
+    /// back `e`.
+    ///
+    /// # Arguments
+    /// * `X` - Point whose discrete log the prover claims to know
+    /// * `context` - Session context the proof was made with
+    ///
+    /// # Returns
+    /// * `Result<(), VerifyError>` - `Ok(())` if the proof is valid, otherwise
+    ///   `VerifyError::IdentityPoint` or `VerifyError::InvalidProof`
+    pub fn verify_detailed(
+        &self,
+        X: &ProjectivePoint,
+        context: Option<&[u8]>,
+    ) -> Result<(), VerifyError> {
+        if *X == ProjectivePoint::IDENTITY {
+            return Err(VerifyError::IdentityPoint);
+        }
+        let proof = Proof {
+            e: self.e,
+            z: self.s,
+        };
+        DLog.verify_detailed(&Self::transcript(context), &Self::statement(X), &proof)
+    }
+
+    pub fn verify(&self, X: &ProjectivePoint, context: Option<&[u8]>) -> bool {
+        self.verify_detailed(X, context).is_ok()
+    }
+
+    /// Serializes the proof as `e || s`, two 32-byte big-endian scalars.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        Pi {
+            e: self.e,
+            i: self.s,
+        }
+        .to_bytes()
+    }
+
+    /// Parses a proof encoded by [`PoK::to_bytes`], rejecting wrong lengths and scalars that
+    /// are not below the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let Pi { e, i } = Pi::from_bytes(bytes)?;
+        Ok(Self { e, s: i })
+    }
+}
+
 // Traits
 
+/// DLEQ proofs $\log_G(P) = \log_T(Z)$ attached to pre-signatures, bound to an optional
+/// session context: a proof only verifies with the context it was made with.
+/// [`ECDSA`] implements them with the [`Dleq`](sigma::Dleq) Sigma protocol;
+/// other proofs can be built directly from the protocols of the [`sigma`] module.
 pub trait ZKP {
     fn compute_challenge(
         &self,
@@ -73,6 +373,7 @@ pub trait ZKP {
         T: &ProjectivePoint,
         J: &ProjectivePoint,
         J_prime: &ProjectivePoint,
+        context: Option<&[u8]>,
     ) -> Scalar;
     fn gen_proof(
         &self,
@@ -80,31 +381,346 @@ pub trait ZKP {
         Z: &ProjectivePoint,
         P: &ProjectivePoint,
         T: &ProjectivePoint,
-    ) -> Pi;
+        context: Option<&[u8]>,
+    ) -> Result<Pi, Error>;
+    /// Verifies `pi`, returning the reason it is rejected on failure.
+    fn verify_proof_detailed(
+        &self,
+        P: &ProjectivePoint,
+        Z: &ProjectivePoint,
+        T: &ProjectivePoint,
+        pi: &Pi,
+        context: Option<&[u8]>,
+    ) -> Result<(), VerifyError>;
     fn verify_proof(
         &self,
         P: &ProjectivePoint,
         Z: &ProjectivePoint,
         T: &ProjectivePoint,
         pi: &Pi,
-    ) -> bool;
+        context: Option<&[u8]>,
+    ) -> bool {
+        self.verify_proof_detailed(P, Z, T, pi, context).is_ok()
+    }
 }
 
-pub trait AS_scheme {
-    fn hash_challenge(&self, R: &ProjectivePoint, P: &ProjectivePoint, message: &str) -> Scalar;
-    fn pre_sign(&self, p: &Scalar, m: &str, T: &ProjectivePoint, r_prime: &Scalar) -> Sigma_prime;
+/// Adaptor signature schemes. Each scheme has its own pre-signature type, so a
+/// pre-signature of one scheme cannot be passed to another, and locks pre-signatures to the
+/// statements of a [`HardRelation`].
+pub trait AS_scheme:
+    Sign_scheme
+    + sealed::PreSignWithKey<<Self::Relation as HardRelation>::Statement, Self::PreSignature>
+{
+    /// Relation between the statements `T` pre-signatures are locked to and the witnesses
+    /// `t` that adapt them.
+    type Relation: HardRelation;
+    /// Pre-signature produced by [`AS_scheme::pre_sign`].
+    type PreSignature;
+
+    /// Generates a random witness `t` and its statement `T` with `rng`. When `with_proof` is
+    /// set, also returns a [`PoK`] of the discrete log of the adaptor point `T`, which the
+    /// counterparty can check before pre-signing against `T`.
+    fn gen_statement<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        with_proof: bool,
+    ) -> (
+        <Self::Relation as HardRelation>::Witness,
+        <Self::Relation as HardRelation>::Statement,
+        Option<PoK>,
+    ) {
+        let (t, T) = Self::Relation::gen_statement(rng);
+        let pi = with_proof
+            .then(|| {
+                let mut aux_rand = Zeroizing::new([0u8; 32]);
+                rng.fill_bytes(&mut aux_rand[..]);
+                PoK::prove(
+                    Self::Relation::adaptor_scalar(&t),
+                    &Self::Relation::adaptor_point(&T),
+                    None,
+                    Some(&aux_rand),
+                )
+                .ok() // fails only if the relation's adaptor point is not tG
+            })
+            .flatten();
+        (t, T, pi)
+    }
+
+    /// Checks a [`PoK`] from [`AS_scheme::gen_statement`] against the adaptor point of `T`.
+    /// Call it before pre-signing against a statement received from a counterparty: without
+    /// it, a `T` whose discrete log nobody knows would make the pre-signature unusable.
+    fn verify_statement(
+        &self,
+        T: &<Self::Relation as HardRelation>::Statement,
+        pi: &PoK,
+    ) -> Result<(), VerifyError> {
+        pi.verify_detailed(&Self::Relation::adaptor_point(T), None)
+    }
+
+    fn hash_challenge(
+        &self,
+        R: &ProjectivePoint,
+        P: &PublicKey,
+        message: &[u8],
+    ) -> Result<Scalar, Error>;
+    /// Low-level pre-signing with a caller-supplied nonce `r_prime`.
+    ///
+    /// The nonce must be secret, uniformly random and never reused: two pre-signatures
+    /// sharing a nonce leak the secret key. Prefer [`AS_scheme::pre_sign`].
+    fn pre_sign_with_nonce(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &<Self::Relation as HardRelation>::Statement,
+        r_prime: &Scalar,
+    ) -> Result<Self::PreSignature, Error>;
+    /// Pre-signs `m` under statement `T` with a nonce derived from `p`, `m` and `T`
+    /// (RFC 6979), hedged with `aux_rand` when provided.
+    fn pre_sign(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &<Self::Relation as HardRelation>::Statement,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Self::PreSignature, Error> {
+        let r_prime = pre_sign_nonce(self, p, m, &Self::Relation::adaptor_point(T), aux_rand);
+        self.pre_sign_with_nonce(p, m, T, &r_prime)
+    }
+    /// Precomputes a [`PointTable`] of the adaptor point of `T` if this scheme multiplies
+    /// the statement by secret scalars when pre-signing, as the ECDSA schemes do.
+    /// [`AS_scheme::pre_sign_batch`] uses it for statements shared by several requests.
+    fn statement_table(
+        &self,
+        T: &<Self::Relation as HardRelation>::Statement,
+    ) -> Option<PointTable> {
+        let _ = T;
+        None
+    }
+    /// Pre-signs many messages with one secret key. The public key is computed once, and
+    /// statements shared by several requests are multiplied with a precomputed table. Each
+    /// pre-signature is the one [`AS_scheme::pre_sign`] returns for its request.
+    ///
+    /// # Arguments
+    /// * `p` - Secret key
+    /// * `requests` - Messages with the statements to lock them to
+    /// * `aux_rand` - Optional randomness hedging every nonce
+    ///
+    /// # Returns
+    /// * `Vec<Result<Self::PreSignature, Error>>` - One result per request, in input order
+    fn pre_sign_batch(
+        &self,
+        p: &SecretKey,
+        requests: &[PreSignRequest<<Self::Relation as HardRelation>::Statement>],
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Vec<Result<Self::PreSignature, Error>> {
+        batch::pre_sign_batch(self, p, requests, aux_rand)
+    }
+    /// Pre-signs many messages like [`AS_scheme::pre_sign_batch`], in parallel on `pool`, or
+    /// on the global `rayon` pool when `None`.
+    ///
+    /// # Arguments
+    /// * `p` - Secret key
+    /// * `requests` - Messages with the statements to lock them to
+    /// * `aux_rand` - Optional randomness hedging every nonce
+    /// * `pool` - Thread pool to run on
+    ///
+    /// # Returns
+    /// * `Vec<Result<Self::PreSignature, Error>>` - One result per request, in input order
+    #[cfg(feature = "parallel")]
+    fn pre_sign_batch_parallel(
+        &self,
+        p: &SecretKey,
+        requests: &[PreSignRequest<<Self::Relation as HardRelation>::Statement>],
+        aux_rand: Option<&[u8; 32]>,
+        pool: Option<&rayon::ThreadPool>,
+    ) -> Vec<Result<Self::PreSignature, Error>>
+    where
+        Self: Sync,
+        Self::PreSignature: Send,
+        <Self::Relation as HardRelation>::Statement: Sync,
+    {
+        batch::pre_sign_batch_parallel(self, p, requests, aux_rand, pool)
+    }
+    /// Verifies `sigma_prime`, returning the reason it is rejected on failure.
+    fn verify_pre_sign_detailed(
+        &self,
+        P: &PublicKey,
+        m: &[u8],
+        T: &<Self::Relation as HardRelation>::Statement,
+        sigma_prime: &Self::PreSignature,
+    ) -> Result<(), VerifyError>;
     fn verify_pre_sign(
         &self,
-        P: &ProjectivePoint,
-        m: &str,
-        T: &ProjectivePoint,
-        sigma_prime: &Sigma_prime,
-    ) -> bool;
-    fn adapt_signature(&self, sigma_prime: &Sigma_prime, t: &Scalar) -> Sigma;
-    fn extract_witness(&self, sigma: &Sigma, sigma_prime: &Sigma_prime) -> Scalar;
+        P: &PublicKey,
+        m: &[u8],
+        T: &<Self::Relation as HardRelation>::Statement,
+        sigma_prime: &Self::PreSignature,
+    ) -> bool {
+        self.verify_pre_sign_detailed(P, m, T, sigma_prime).is_ok()
+    }
+    /// Pre-signs a 32-byte message digest, such as a transaction sighash.
+    ///
+    /// Schemes that hash the message into their challenge (Schnorr, BIP-340) sign the digest
+    /// as a 32-byte message; ECDSA schemes use it directly in place of $H(m)$.
+    fn pre_sign_prehash(
+        &self,
+        p: &SecretKey,
+        digest: &[u8; 32],
+        T: &<Self::Relation as HardRelation>::Statement,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Self::PreSignature, Error> {
+        self.pre_sign(p, digest, T, aux_rand)
+    }
+    /// Verifies a pre-signature produced by [`AS_scheme::pre_sign_prehash`], returning the
+    /// reason it is rejected on failure.
+    fn verify_pre_sign_prehash_detailed(
+        &self,
+        P: &PublicKey,
+        digest: &[u8; 32],
+        T: &<Self::Relation as HardRelation>::Statement,
+        sigma_prime: &Self::PreSignature,
+    ) -> Result<(), VerifyError> {
+        self.verify_pre_sign_detailed(P, digest, T, sigma_prime)
+    }
+    fn verify_pre_sign_prehash(
+        &self,
+        P: &PublicKey,
+        digest: &[u8; 32],
+        T: &<Self::Relation as HardRelation>::Statement,
+        sigma_prime: &Self::PreSignature,
+    ) -> bool {
+        self.verify_pre_sign_prehash_detailed(P, digest, T, sigma_prime)
+            .is_ok()
+    }
+    fn adapt_signature(
+        &self,
+        sigma_prime: &Self::PreSignature,
+        t: &<Self::Relation as HardRelation>::Witness,
+    ) -> Result<Self::Signature, Error>;
+    /// Serializes the fields of `sigma_prime` used by this scheme into a fixed-length
+    /// canonical encoding. Fails with `Error::SchemeMismatch` if `sigma_prime` belongs to
+    /// another scheme, which only a [`Scheme`] can be given.
+    fn encode_pre_signature(&self, sigma_prime: &Self::PreSignature) -> Result<Vec<u8>, Error>;
+    /// Parses a pre-signature encoded by [`AS_scheme::encode_pre_signature`], rejecting
+    /// wrong lengths, off-curve or identity points and non-canonical scalars.
+    fn decode_pre_signature(&self, bytes: &[u8]) -> Result<Self::PreSignature, Error>;
+    fn extract_witness(
+        &self,
+        sigma: &Self::Signature,
+        sigma_prime: &Self::PreSignature,
+        T: &<Self::Relation as HardRelation>::Statement,
+    ) -> Result<<Self::Relation as HardRelation>::Witness, Error>;
+}
+
+/// Derives the nonce [`AS_scheme::pre_sign`] uses to pre-sign `m` with `p` under a statement
+/// with adaptor point `T`. Batch pre-signing derives its nonces here too, so that it returns
+/// the same pre-signatures as `pre_sign`.
+pub(crate) fn pre_sign_nonce<S: Sign_scheme + ?Sized>(
+    scheme: &S,
+    p: &SecretKey,
+    m: &[u8],
+    T: &ProjectivePoint,
+    aux_rand: Option<&[u8; 32]>,
+) -> Zeroizing<Scalar> {
+    Zeroizing::new(derive_nonce(
+        p.as_scalar(),
+        m,
+        scheme.nonce_domain(),
+        "pre_sign",
+        Some(T),
+        aux_rand,
+    ))
 }
 
 pub trait Sign_scheme {
-    fn sign(&self, p: &Scalar, m: &str, k: &Scalar) -> Sigma;
-    fn verify_sign(&self, sigma: &Sigma, P: &ProjectivePoint, m: &str) -> bool;
+    /// Signature produced by [`Sign_scheme::sign`].
+    type Signature;
+
+    /// Generates a random key pair `(p, P)` with `rng`.
+    fn keygen<R: RngCore + CryptoRng>(&self, rng: &mut R) -> (SecretKey, PublicKey) {
+        let p = SecretKey::from(*NonZeroScalar::random(rng));
+        let P = p.public_key();
+        (p, P)
+    }
+
+    /// Domain separation tags this scheme passes to RFC 6979 when it derives nonces, unique
+    /// to the scheme so that two schemes never derive the same nonce for the same key and
+    /// message: with different challenges, two signatures sharing a nonce leak the key.
+    fn nonce_domain(&self) -> &'static [&'static str];
+
+    /// Low-level signing with a caller-supplied nonce `k`.
+    ///
+    /// The nonce must be secret, uniformly random and never reused: two signatures
+    /// sharing a nonce leak the secret key. Prefer [`Sign_scheme::sign`].
+    fn sign_with_nonce(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        k: &Scalar,
+    ) -> Result<Self::Signature, Error>;
+    /// Signs `m` with a nonce derived from `p` and `m` (RFC 6979), hedged with
+    /// `aux_rand` when provided.
+    fn sign(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Self::Signature, Error> {
+        let k = Zeroizing::new(derive_nonce(
+            p.as_scalar(),
+            m,
+            self.nonce_domain(),
+            "sign",
+            None,
+            aux_rand,
+        ));
+        self.sign_with_nonce(p, m, &k)
+    }
+    /// Verifies `sigma`, returning the reason it is rejected on failure.
+    fn verify_sign_detailed(
+        &self,
+        sigma: &Self::Signature,
+        P: &PublicKey,
+        m: &[u8],
+    ) -> Result<(), VerifyError>;
+    fn verify_sign(&self, sigma: &Self::Signature, P: &PublicKey, m: &[u8]) -> bool {
+        self.verify_sign_detailed(sigma, P, m).is_ok()
+    }
+    /// Serializes `sigma` into this scheme's fixed-length canonical encoding. Fails with
+    /// `Error::SchemeMismatch` if `sigma` belongs to another scheme, which only a [`Scheme`]
+    /// can be given.
+    fn encode_signature(&self, sigma: &Self::Signature) -> Result<Vec<u8>, Error>;
+    /// Parses a signature encoded by [`Sign_scheme::encode_signature`], rejecting wrong
+    /// lengths, off-curve or identity points and non-canonical scalars.
+    fn decode_signature(&self, bytes: &[u8]) -> Result<Self::Signature, Error>;
+    /// Signs a 32-byte message digest, such as a transaction sighash.
+    ///
+    /// Schemes that hash the message into their challenge (Schnorr, BIP-340) sign the digest
+    /// as a 32-byte message; ECDSA schemes use it directly in place of $H(m)$.
+    fn sign_prehash(
+        &self,
+        p: &SecretKey,
+        digest: &[u8; 32],
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Self::Signature, Error> {
+        self.sign(p, digest, aux_rand)
+    }
+    /// Verifies a signature produced by [`Sign_scheme::sign_prehash`], returning the reason
+    /// it is rejected on failure.
+    fn verify_sign_prehash_detailed(
+        &self,
+        sigma: &Self::Signature,
+        P: &PublicKey,
+        digest: &[u8; 32],
+    ) -> Result<(), VerifyError> {
+        self.verify_sign_detailed(sigma, P, digest)
+    }
+    fn verify_sign_prehash(
+        &self,
+        sigma: &Self::Signature,
+        P: &PublicKey,
+        digest: &[u8; 32],
+    ) -> bool {
+        self.verify_sign_prehash_detailed(sigma, P, digest).is_ok()
+    }
 }
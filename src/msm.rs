@@ -0,0 +1,229 @@
+use k256::{
+    elliptic_curve::{
+        group::Curve,
+        ops::{LinearCombinationExt, MulByGenerator},
+        subtle::{Choice, ConditionallySelectable, ConstantTimeEq},
+    },
+    AffinePoint, ProjectivePoint, Scalar,
+};
+use std::sync::OnceLock;
+
+use crate::utils::encode_scalar;
+
+/// Number of signed radix-16 digits of a scalar.
+const DIGITS: usize = 65;
+/// Number of windows of a [`PointTable`]: one per pair of digits.
+const WINDOWS: usize = DIGITS.div_ceil(2);
+
+/// Recodes `k` into signed radix-16 digits $d_i \in [-8, 8)$ with $k = \sum_i d_i 16^i$,
+/// without branching on `k`.
+fn radix16(k: &Scalar) -> [i8; DIGITS] {
+    let bytes = encode_scalar(k);
+    let mut digits = [0i8; DIGITS];
+    for i in 0..32 {
+        digits[2 * i] = (bytes[31 - i] & 0xf) as i8;
+        digits[2 * i + 1] = (bytes[31 - i] >> 4) as i8;
+    }
+    for i in 0..DIGITS - 1 {
+        let carry = (digits[i] + 8) >> 4;
+        digits[i] -= carry << 4;
+        digits[i + 1] += carry;
+    }
+    digits
+}
+
+/// `PointTable` holds precomputed multiples of a fixed point `B`, so that $kB$ costs 65
+/// point additions and four doublings instead of a full scalar multiplication.
+///
+/// Window `i` holds $jB \cdot 256^i$ for $j = 1, \dots, 8$, as in the generator table of
+/// `k256`. Building a table costs about three scalar multiplications, so it pays off for a
+/// point multiplied several times, such as the generator or a statement `T` that many
+/// pre-signatures are locked to.
+#[derive(Clone)]
+pub struct PointTable {
+    point: ProjectivePoint,
+    windows: Vec<[AffinePoint; 8]>,
+}
+
+impl PointTable {
+    /// Precomputes the table of `B`.
+    pub fn new(B: &ProjectivePoint) -> Self {
+        if *B == ProjectivePoint::IDENTITY {
+            // every multiple is the identity, which `batch_normalize` does not accept
+            let windows = vec![[AffinePoint::IDENTITY; 8]; WINDOWS];
+            return Self { point: *B, windows };
+        }
+        let mut multiples = Vec::with_capacity(WINDOWS * 8);
+        let mut base = *B;
+        for _ in 0..WINDOWS {
+            let mut multiple = base;
+            for _ in 0..8 {
+                multiples.push(multiple);
+                multiple += base; // add jB·256^i
+            }
+            for _ in 0..8 {
+                base = base.double(); // next window
+            }
+        }
+        let mut affine = vec![AffinePoint::IDENTITY; multiples.len()];
+        ProjectivePoint::batch_normalize(&multiples, &mut affine);
+        let windows = affine
+            .chunks_exact(8)
+            .map(|window| window.try_into().unwrap())
+            .collect();
+        Self { point: *B, windows }
+    }
+
+    /// Returns the table of the generator `G`, computed on first use.
+    pub fn generator() -> &'static Self {
+        static TABLE: OnceLock<PointTable> = OnceLock::new();
+        TABLE.get_or_init(|| Self::new(&ProjectivePoint::GENERATOR))
+    }
+
+    /// Returns the point `B` the table was built for.
+    pub fn point(&self) -> &ProjectivePoint {
+        &self.point
+    }
+
+    /// Selects $dB \cdot 256^i$ from window `i` without branching on the digit `d`.
+    fn select(&self, i: usize, d: i8) -> AffinePoint {
+        let mask = d >> 7;
+        let abs = ((d + mask) ^ mask) as u8;
+        let mut t = AffinePoint::IDENTITY;
+        for (j, multiple) in self.windows[i].iter().enumerate() {
+            t.conditional_assign(multiple, abs.ct_eq(&(j as u8 + 1)));
+        }
+        AffinePoint::conditional_select(&t, &-t, Choice::from((mask & 1) as u8))
+    }
+
+    /// Computes $kB$ in constant time, for secret scalars such as keys and nonces.
+    pub fn mul(&self, k: &Scalar) -> ProjectivePoint {
+        let digits = radix16(k);
+        let (mut even, mut odd) = (ProjectivePoint::IDENTITY, ProjectivePoint::IDENTITY);
+        for i in 0..WINDOWS {
+            even += self.select(i, digits[2 * i]); // add d_2i 256^i B
+            if 2 * i + 1 < DIGITS {
+                odd += self.select(i, digits[2 * i + 1]); // add d_2i+1 256^i B
+            }
+        }
+        for _ in 0..4 {
+            odd = odd.double(); // odd digits weigh 16 256^i
+        }
+        even + odd
+    }
+
+    /// Computes $kB$ in variable time. Only for public scalars, e.g. in verification.
+    pub fn mul_vartime(&self, k: &Scalar) -> ProjectivePoint {
+        let digits = radix16(k);
+        let (mut even, mut odd) = (ProjectivePoint::IDENTITY, ProjectivePoint::IDENTITY);
+        for (i, digit) in digits.iter().enumerate() {
+            let acc = if i % 2 == 0 { &mut even } else { &mut odd };
+            let multiple = match digit.unsigned_abs() {
+                0 => continue,
+                abs => self.windows[i / 2][abs as usize - 1],
+            };
+            if *digit < 0 {
+                *acc -= multiple;
+            } else {
+                *acc += multiple;
+            }
+        }
+        for _ in 0..4 {
+            odd = odd.double();
+        }
+        even + odd
+    }
+}
+
+/// Computes $kB$ in constant time, with the precomputed generator table of `k256` when `B` is
+/// the generator.
+pub(crate) fn mul_base(B: &ProjectivePoint, k: &Scalar) -> ProjectivePoint {
+    if *B == ProjectivePoint::GENERATOR {
+        ProjectivePoint::mul_by_generator(k)
+    } else {
+        B * k
+    }
+}
+
+/// Computes $kB$ in constant time, with `table` when given. `table` must be the table of `B`.
+pub(crate) fn mul_with_table(
+    B: &ProjectivePoint,
+    table: Option<&PointTable>,
+    k: &Scalar,
+) -> ProjectivePoint {
+    match table {
+        Some(table) => table.mul(k),
+        None => mul_base(B, k),
+    }
+}
+
+/// Below this many terms [`msm_vartime`] uses Straus' method, from it Pippenger's.
+const PIPPENGER_THRESHOLD: usize = 48;
+
+/// Computes $\sum_i k_i X_i$ in variable time. Only for public points and scalars, e.g. in
+/// verification.
+///
+/// Terms on the generator are summed into one multiplication with the generator table. Small
+/// sums of the other terms share their doublings (Straus' method, via `k256`); large ones sort
+/// the terms into buckets by window digits (Pippenger's method), which costs about
+/// $2n / \log_2 n$ additions per window instead of one per term.
+pub fn msm_vartime(terms: &[(ProjectivePoint, Scalar)]) -> ProjectivePoint {
+    let mut g = Scalar::ZERO;
+    let mut others = Vec::with_capacity(terms.len());
+    for (X, k) in terms {
+        if *X == ProjectivePoint::GENERATOR {
+            g += k;
+        } else {
+            others.push((*X, *k));
+        }
+    }
+    let sum = match others.len() {
+        0 => ProjectivePoint::IDENTITY,
+        n if n < PIPPENGER_THRESHOLD => ProjectivePoint::lincomb_ext(others.as_slice()),
+        _ => pippenger(&others),
+    };
+    sum + PointTable::generator().mul_vartime(&g)
+}
+
+/// Pippenger's bucket method over unsigned `c`-bit windows.
+fn pippenger(terms: &[(ProjectivePoint, Scalar)]) -> ProjectivePoint {
+    let c = window_bits(terms.len());
+    let scalars: Vec<[u8; 32]> = terms.iter().map(|(_, k)| encode_scalar(k)).collect();
+    let mut buckets = vec![ProjectivePoint::IDENTITY; (1 << c) - 1];
+    let mut acc = ProjectivePoint::IDENTITY;
+    for window in (0..256usize.div_ceil(c)).rev() {
+        for _ in 0..c {
+            acc = acc.double();
+        }
+        buckets.fill(ProjectivePoint::IDENTITY);
+        for ((X, _), k) in terms.iter().zip(&scalars) {
+            let digit = window_digit(k, window * c, c);
+            if digit != 0 {
+                buckets[digit - 1] += X; // sort X into bucket d
+            }
+        }
+        // sum_d d * bucket_d as a running sum
+        let (mut running, mut sum) = (ProjectivePoint::IDENTITY, ProjectivePoint::IDENTITY);
+        for bucket in buckets.iter().rev() {
+            running += bucket;
+            sum += running;
+        }
+        acc += sum;
+    }
+    acc
+}
+
+/// Window width of Pippenger's method for `n` terms.
+fn window_bits(n: usize) -> usize {
+    (n.ilog2() as usize).saturating_sub(2).clamp(4, 16)
+}
+
+/// Reads the `c` bits of the big-endian scalar `k` starting at bit `start`.
+fn window_digit(k: &[u8; 32], start: usize, c: usize) -> usize {
+    let mut digit = 0;
+    for bit in start..(start + c).min(256) {
+        let byte = k[31 - bit / 8];
+        digit |= (((byte >> (bit % 8)) & 1) as usize) << (bit - start);
+    }
+    digit
+}
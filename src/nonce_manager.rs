@@ -0,0 +1,235 @@
+use sha2::Digest;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::utils::{encode_point, tagged_hash};
+use crate::{AS_scheme, HardRelation, NonceManagerError, SecretKey};
+
+/// First bytes of a store: a magic string and the format version.
+const HEADER: &[u8; 9] = b"asfde_nm\x02";
+/// Length of the hash identifying a (scheme, key, message, statement) tuple.
+const ID_LEN: usize = 32;
+/// Length of the big-endian length prefix of a stored pre-signature.
+const LEN_PREFIX: usize = 2;
+/// Length of a record checksum.
+const CHECK_LEN: usize = 8;
+/// Length of a record head: the id, the length prefix and the checksum of both.
+const HEAD_LEN: usize = ID_LEN + LEN_PREFIX + CHECK_LEN;
+
+/// `NonceManager` issues at most one pre-signature per (scheme, key, message, statement) tuple
+/// and remembers it in a file, so that a participant that crashes and retries after it sent a
+/// pre-signature sends the same one again rather than a second one with a fresh nonce.
+///
+/// The file starts with a magic string and a version, followed by one record per issued
+/// pre-signature: a hash of the nonce domain of the scheme, the public key, the statement and
+/// the message, the length of the encoding of the pre-signature and a checksum of both, then
+/// the encoding and a checksum of the hash, the length and the encoding. A record is written
+/// and synced before its pre-signature is returned, so a crash can only lose a pre-signature
+/// that was never handed out, and a record whose write fails is cut off again. The manager
+/// holds an exclusive lock on the file until it is dropped.
+pub struct NonceManager {
+    file: File,
+    issued: HashMap<[u8; ID_LEN], Vec<u8>>,
+    /// Set when a failed write could not be cut off, after which no record is appended.
+    poisoned: bool,
+}
+
+impl NonceManager {
+    /// Opens and locks the store at `path`, creating it if needed, and loads the
+    /// pre-signatures issued so far. On Unix a symbolic link at `path` is not followed, and a
+    /// new store is readable by its owner only. Only a final record cut short by a crash is discarded:
+    /// one that ends inside its head, or whose head checksum holds and whose declared length
+    /// runs past the end of the file. Any other damage is reported rather than repaired.
+    ///
+    /// # Arguments
+    /// * `path` - File of issued pre-signatures
+    ///
+    /// # Returns
+    /// * `Result<NonceManager, NonceManagerError>` - The store, `NonceManagerError::Corrupt` if
+    ///   the file is not a store or a record fails its checksum, or the I/O error that
+    ///   prevented reading or locking it, of kind `WouldBlock` if another manager holds it
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, NonceManagerError> {
+        let path = path.as_ref();
+        let mut options = OpenOptions::new();
+        options.read(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.custom_flags(libc::O_NOFOLLOW).mode(0o600);
+        }
+        let mut file = match options.clone().create_new(true).open(path) {
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => options.open(path)?,
+            file => file?,
+        };
+        try_lock(&file)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        if bytes.len() < HEADER.len() {
+            if !HEADER.starts_with(&bytes) {
+                return Err(NonceManagerError::Corrupt);
+            }
+            // a new store, or one whose creation was cut short
+            file.set_len(0)?;
+            file.write_all(HEADER)?;
+            file.sync_all()?;
+            return Ok(Self {
+                file,
+                issued: HashMap::new(),
+                poisoned: false,
+            });
+        }
+        if bytes[..HEADER.len()] != HEADER[..] {
+            return Err(NonceManagerError::Corrupt);
+        }
+
+        let mut issued = HashMap::new();
+        let mut pos = HEADER.len();
+        while pos < bytes.len() {
+            let rest = &bytes[pos..];
+            if rest.len() < HEAD_LEN {
+                break; // shorter than any record, so never a complete one
+            }
+            let (head, body) = rest.split_at(HEAD_LEN);
+            let (id_len, head_check) = head.split_at(ID_LEN + LEN_PREFIX);
+            if checksum(&[id_len]) != head_check {
+                return Err(NonceManagerError::Corrupt);
+            }
+            let len = u16::from_be_bytes([id_len[ID_LEN], id_len[ID_LEN + 1]]) as usize;
+            let Some(body) = body.get(..len + CHECK_LEN) else {
+                break; // the checked length runs past the end of the file
+            };
+            let (encoding, check) = body.split_at(len);
+            if checksum(&[id_len, encoding]) != check {
+                return Err(NonceManagerError::Corrupt);
+            }
+            let id = id_len[..ID_LEN].try_into().unwrap();
+            if issued.insert(id, encoding.to_vec()).is_some() {
+                return Err(NonceManagerError::Corrupt);
+            }
+            pos += HEAD_LEN + len + CHECK_LEN;
+        }
+        if pos < bytes.len() {
+            file.set_len(pos as u64)?; // drop the torn record
+            file.sync_all()?;
+        }
+        Ok(Self {
+            file,
+            issued,
+            poisoned: false,
+        })
+    }
+
+    /// Pre-signs `m` under statement `T` with [`AS_scheme::pre_sign`], unless a pre-signature
+    /// was already issued for the same scheme, key, message and statement, in which case that
+    /// one is returned and `aux_rand` is ignored.
+    ///
+    /// # Arguments
+    /// * `scheme` - Adaptor signature scheme
+    /// * `p` - Secret key
+    /// * `m` - Message
+    /// * `T` - Statement
+    /// * `aux_rand` - Optional randomness hedging the nonce of a new pre-signature
+    ///
+    /// # Returns
+    /// * `Result<S::PreSignature, NonceManagerError>` - The pre-signature of the tuple, or an
+    ///   error if pre-signing fails, the record cannot be written, or the stored pre-signature
+    ///   does not decode or verify under `scheme` (`NonceManagerError::Corrupt`). Once a
+    ///   failed write cannot be cut off, every new pre-signature fails with an I/O error.
+    pub fn pre_sign<S: AS_scheme + ?Sized>(
+        &mut self,
+        scheme: &S,
+        p: &SecretKey,
+        m: &[u8],
+        T: &<S::Relation as HardRelation>::Statement,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<S::PreSignature, NonceManagerError> {
+        let P = p.public_key();
+        let mut hasher = tagged_hash("as_for_fde/nonce_manager");
+        for tag in scheme.nonce_domain() {
+            hasher.update([tag.len() as u8]); // add tag length
+            hasher.update(tag.as_bytes()); // add tag
+        }
+        let id: [u8; ID_LEN] = hasher
+            .chain_update(encode_point(P.as_point()))
+            .chain_update(encode_point(&S::Relation::adaptor_point(T)))
+            .chain_update(m)
+            .finalize()
+            .into();
+
+        if let Some(encoding) = self.issued.get(&id) {
+            let sigma_prime = scheme
+                .decode_pre_signature(encoding)
+                .map_err(|_| NonceManagerError::Corrupt)?;
+            if !scheme.verify_pre_sign(&P, m, T, &sigma_prime) {
+                return Err(NonceManagerError::Corrupt);
+            }
+            return Ok(sigma_prime);
+        }
+
+        if self.poisoned {
+            return Err(io::Error::other("nonce store holds a partly written record").into());
+        }
+        let sigma_prime = scheme.pre_sign(p, m, T, aux_rand)?;
+        let encoding = scheme.encode_pre_signature(&sigma_prime)?;
+        let mut record = Vec::with_capacity(HEAD_LEN + encoding.len() + CHECK_LEN);
+        record.extend_from_slice(&id);
+        record.extend_from_slice(&(encoding.len() as u16).to_be_bytes());
+        let head_check = checksum(&[&record]);
+        record.extend_from_slice(&head_check);
+        record.extend_from_slice(&encoding);
+        let check = checksum(&[&record[..ID_LEN + LEN_PREFIX], &encoding]);
+        record.extend_from_slice(&check);
+        let end = self.file.seek(SeekFrom::End(0))?;
+        if let Err(err) = self.append(&record) {
+            // cut off what was written, so that the next record follows the last complete one
+            let rollback = self.file.set_len(end).and_then(|()| self.file.sync_all());
+            if rollback
+                .and_then(|()| self.file.seek(SeekFrom::Start(end)))
+                .is_err()
+            {
+                self.poisoned = true;
+            }
+            return Err(err.into());
+        }
+        self.issued.insert(id, encoding);
+        Ok(sigma_prime)
+    }
+
+    /// Writes `record` at the end of the store and syncs it, so that it is on disk before its
+    /// pre-signature is handed out.
+    fn append(&mut self, record: &[u8]) -> io::Result<()> {
+        self.file.write_all(record)?;
+        self.file.sync_all()
+    }
+}
+
+/// Takes an exclusive lock on `file` without waiting, failing with `WouldBlock` if another
+/// open file holds one.
+#[cfg(unix)]
+fn try_lock(file: &File) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+    // SAFETY: the descriptor stays open while `file` is borrowed
+    match unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// Takes an exclusive lock on `file` without waiting, failing with `WouldBlock` if another
+/// open file holds one.
+#[cfg(not(unix))]
+fn try_lock(file: &File) -> io::Result<()> {
+    file.try_lock().map_err(io::Error::from)
+}
+
+/// Checksum of the concatenation of `parts`, guarding records against damage on disk.
+fn checksum(parts: &[&[u8]]) -> [u8; CHECK_LEN] {
+    let mut hasher = tagged_hash("as_for_fde/nonce_manager/record");
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize()[..CHECK_LEN].try_into().unwrap()
+}
@@ -0,0 +1,320 @@
+use k256::{elliptic_curve::ops::MulByGenerator, ProjectivePoint, Scalar};
+use std::collections::HashMap;
+
+use crate::ciphersuite::Ciphersuite;
+use crate::utils::{encode_point, encode_scalar, get_x, invert_scalar, POINT_LEN};
+use crate::{
+    AS_scheme, AdaptorStatement, AumayrECDSA, HardRelation, PublicKey, Schnorr, SecretKey, Sigma,
+    Sigma_prime, Sigma_prime_Aumayr, Sigma_prime_ECDSA, BIP340, ECDSA,
+};
+
+/// An entry of a corpus scanned for nonce reuse: a signature or a pre-signature, with the
+/// public key and message it was made for and, for a pre-signature, its statement.
+#[derive(Debug)]
+pub enum Observation<'a, Statement, PreSignature> {
+    Signature {
+        sigma: &'a Sigma,
+        P: &'a PublicKey,
+        m: &'a [u8],
+    },
+    PreSignature {
+        sigma_prime: &'a PreSignature,
+        P: &'a PublicKey,
+        m: &'a [u8],
+        T: &'a Statement,
+    },
+}
+
+// only references, so copyable whatever the statement and pre-signature types
+impl<Statement, PreSignature> Clone for Observation<'_, Statement, PreSignature> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Statement, PreSignature> Copy for Observation<'_, Statement, PreSignature> {}
+
+/// Entries of a corpus made with the same nonce under the same public key.
+#[derive(Debug, Clone)]
+pub struct NonceReuse {
+    /// Indices of the entries in the corpus, in increasing order.
+    pub indices: Vec<usize>,
+    /// Public key of the entries.
+    pub P: PublicKey,
+    /// Secret key solved from two of the entries and checked against `P`, or `None` if their
+    /// equations do not determine it.
+    pub secret_key: Option<SecretKey>,
+}
+
+/// An entry reduced to the nonce it commits to and the scalars of its signing equation.
+struct Entry {
+    index: usize,
+    P: PublicKey,
+    /// Encoding of the nonce commitment, equal for entries made with the same nonce.
+    nonce: Vec<u8>,
+    s: Scalar,
+    e: Scalar,
+    /// `r` of the ECDSA signing equation $sk = e + rp$, zero for Schnorr.
+    r: Scalar,
+}
+
+/// Groups `entries` by public key and nonce, and reports every group with at least two
+/// different signing equations, solving the key with `recover` from two of them. Entries
+/// with the same challenge and `r` are repeats of one signature, which leak nothing.
+fn find_reuse(
+    entries: Vec<Entry>,
+    recover: impl Fn(&Entry, &Entry) -> Option<Scalar>,
+) -> Vec<NonceReuse> {
+    let mut groups: HashMap<([u8; POINT_LEN], Vec<u8>), Vec<Entry>> = HashMap::new();
+    for entry in entries {
+        let key = (encode_point(entry.P.as_point()), entry.nonce.clone());
+        groups.entry(key).or_default().push(entry);
+    }
+
+    let mut findings: Vec<NonceReuse> = groups
+        .into_values()
+        .filter_map(|group| {
+            let first = &group[0];
+            let second = group
+                .iter()
+                .find(|entry| (entry.e, entry.r) != (first.e, first.r))?;
+            let secret_key = recover(first, second)
+                .filter(|p| ProjectivePoint::mul_by_generator(p) == *first.P.as_point())
+                .map(SecretKey::from);
+            Some(NonceReuse {
+                indices: group.iter().map(|entry| entry.index).collect(),
+                P: first.P,
+                secret_key,
+            })
+        })
+        .collect();
+    findings.sort_unstable_by_key(|finding| finding.indices[0]);
+    findings
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> Schnorr<Rel, Suite> {
+    /// Scans `corpus` for signatures and pre-signatures that share a nonce under the same
+    /// public key, and recovers the secret key from them.
+    ///
+    /// A signature commits to its nonce $r$ with $R = rG$ and a pre-signature with
+    /// $R' = rG + T$, so both are compared by $rG$. Two entries with nonce $r$ and challenges
+    /// $e_1 \neq e_2$ satisfy $s_i = r + e_i p$, hence $p = (s_1 - s_2) / (e_1 - e_2)$.
+    ///
+    /// # Arguments
+    /// * `corpus` - Signatures and pre-signatures with their keys, messages and statements
+    ///
+    /// # Returns
+    /// * `Vec<NonceReuse>` - The groups of entries sharing a nonce, ordered by their first
+    ///   index, each with the recovered secret key
+    pub fn find_nonce_reuse(
+        &self,
+        corpus: &[Observation<Rel::Statement, Sigma_prime>],
+    ) -> Vec<NonceReuse> {
+        let entries = corpus
+            .iter()
+            .enumerate()
+            .filter_map(|(index, observation)| {
+                let (R, s, P, m, rG) = match *observation {
+                    Observation::Signature { sigma, P, m } => (sigma.R, sigma.s, P, m, sigma.R),
+                    Observation::PreSignature {
+                        sigma_prime,
+                        P,
+                        m,
+                        T,
+                    } => {
+                        let R_prime = sigma_prime.R_prime;
+                        let rG = R_prime - Rel::adaptor_point(T); // R' - T
+                        (R_prime, sigma_prime.s_prime, P, m, rG)
+                    }
+                };
+                let e = self.hash_challenge(&R, P, m).ok()?;
+                Some(Entry {
+                    index,
+                    P: *P,
+                    nonce: encode_point(&rG).to_vec(),
+                    s,
+                    e,
+                    r: Scalar::ZERO,
+                })
+            });
+        find_reuse(entries.collect(), |first, second| {
+            Some((first.s - second.s) * invert_scalar(&(first.e - second.e)).ok()?)
+        })
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> ECDSA<Rel, Suite> {
+    /// Scans `corpus` for signatures, or pre-signatures under the same statement, that share
+    /// a nonce under the same public key, and recovers the secret key from them.
+    ///
+    /// A signature commits to its nonce $k$ with $r = x(kG)$ and a pre-signature with
+    /// $r = x(kT)$, so pre-signatures are only compared with those under the same `T`. Both
+    /// satisfy $sk = e + rp$, and $k$ and $-k$ give the same $r$, so two entries with
+    /// challenges $e_1 \neq e_2$ give $k = (e_1 - e_2) / (s_1 \mp s_2)$ and
+    /// $p = (s_1 k - e_1) / r$, checked against the public key for both signs.
+    ///
+    /// # Arguments
+    /// * `corpus` - Signatures and pre-signatures with their keys, messages and statements
+    ///
+    /// # Returns
+    /// * `Vec<NonceReuse>` - The groups of entries sharing a nonce, ordered by their first
+    ///   index, each with the recovered secret key
+    pub fn find_nonce_reuse(
+        &self,
+        corpus: &[Observation<Rel::Statement, Sigma_prime_ECDSA>],
+    ) -> Vec<NonceReuse> {
+        let entries = corpus
+            .iter()
+            .enumerate()
+            .filter_map(|(index, observation)| {
+                // signatures and pre-signatures commit to the nonce with different bases, and
+                // pre-signatures with the base `T` they were made under
+                let (mut nonce, R, s, P, m) = match *observation {
+                    Observation::Signature { sigma, P, m } => (vec![0], sigma.R, sigma.s, P, m),
+                    Observation::PreSignature {
+                        sigma_prime,
+                        P,
+                        m,
+                        T,
+                    } => {
+                        let mut nonce = vec![1];
+                        nonce.extend_from_slice(&encode_point(&Rel::adaptor_point(T))); // add T
+                        (nonce, sigma_prime.R_prime, sigma_prime.s_prime, P, m)
+                    }
+                };
+                let r = get_x(&R).ok()?;
+                nonce.extend_from_slice(&encode_scalar(&r)); // add r
+                Some(Entry {
+                    index,
+                    P: *P,
+                    nonce,
+                    s,
+                    e: self.hash_message(m).ok()?,
+                    r,
+                })
+            });
+        find_reuse(entries.collect(), |first, second| {
+            [second.s, -second.s].into_iter().find_map(|s| {
+                let k = (first.e - second.e) * invert_scalar(&(first.s - s)).ok()?;
+                let p = (first.s * k - first.e) * invert_scalar(&first.r).ok()?;
+                (ProjectivePoint::mul_by_generator(&p) == *first.P.as_point()).then_some(p)
+            })
+        })
+    }
+}
+
+impl BIP340 {
+    /// Scans `corpus` for signatures and pre-signatures that share a nonce under the same
+    /// public key, and recovers the secret key from them.
+    ///
+    /// A signature commits to its nonce with $R$ and a pre-signature with $\pm(R' - T)$, both
+    /// compared by their x-coordinate as BIP-340 negates nonces to even Y coordinates. Two
+    /// entries then satisfy $s_i = \pm r + e_i d$, where $d = \pm p$ is the key with an even
+    /// public key, so $d = (s_1 \mp s_2) / (e_1 \mp e_2)$, checked against the public key for
+    /// all signs.
+    ///
+    /// # Arguments
+    /// * `corpus` - Signatures and pre-signatures with their keys, messages and statements
+    ///
+    /// # Returns
+    /// * `Vec<NonceReuse>` - The groups of entries sharing a nonce, ordered by their first
+    ///   index, each with the recovered secret key
+    pub fn find_nonce_reuse(
+        &self,
+        corpus: &[Observation<AdaptorStatement, Sigma_prime>],
+    ) -> Vec<NonceReuse> {
+        let entries = corpus
+            .iter()
+            .enumerate()
+            .filter_map(|(index, observation)| {
+                let (R, s, P, m, rG) = match *observation {
+                    Observation::Signature { sigma, P, m } => (sigma.R, sigma.s, P, m, sigma.R),
+                    Observation::PreSignature {
+                        sigma_prime,
+                        P,
+                        m,
+                        T,
+                    } => {
+                        let R_prime = sigma_prime.R_prime;
+                        (R_prime, sigma_prime.s_prime, P, m, R_prime - T.as_point())
+                        // R' - T
+                    }
+                };
+                if rG == ProjectivePoint::IDENTITY {
+                    return None;
+                }
+                let e = self.hash_challenge_bytes(&R, P.as_point(), m);
+                Some(Entry {
+                    index,
+                    P: *P,
+                    nonce: Self::xonly(&rG).to_vec(),
+                    s,
+                    e,
+                    r: Scalar::ZERO,
+                })
+            });
+        find_reuse(entries.collect(), |first, second| {
+            [(second.s, second.e), (-second.s, -second.e)]
+                .into_iter()
+                .filter_map(|(s, e)| Some((first.s - s) * invert_scalar(&(first.e - e)).ok()?))
+                .flat_map(|d| [d, -d])
+                .find(|p| ProjectivePoint::mul_by_generator(p) == *first.P.as_point())
+        })
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> AumayrECDSA<Rel, Suite> {
+    /// Scans `corpus` for signatures and pre-signatures that share a nonce under the same
+    /// public key, and recovers the secret key from them.
+    ///
+    /// A pre-signature carries the commitment $K = kG$ to its nonce and a signature commits
+    /// to it with $R = kG$, both compared by their x-coordinate, whatever the statement. Each
+    /// entry satisfies $s_i k = e_i + r_i p$ with $r_i = x(kT_i)$ for a pre-signature and
+    /// $r_i = x(kG)$ for a signature. As $-k$ gives the same commitment, two entries with
+    /// different $(e_i, r_i)$ give $p = (s_1 e_2 - s_2' e_1) / (r_1 s_2' - s_1 r_2)$ for
+    /// $s_2' = \pm s_2$, checked against the public key for both signs.
+    ///
+    /// # Arguments
+    /// * `corpus` - Signatures and pre-signatures with their keys, messages and statements
+    ///
+    /// # Returns
+    /// * `Vec<NonceReuse>` - The groups of entries sharing a nonce, ordered by their first
+    ///   index, each with the recovered secret key
+    pub fn find_nonce_reuse(
+        &self,
+        corpus: &[Observation<Rel::Statement, Sigma_prime_Aumayr>],
+    ) -> Vec<NonceReuse> {
+        let entries = corpus
+            .iter()
+            .enumerate()
+            .filter_map(|(index, observation)| {
+                let (K, R, s, P, m) = match *observation {
+                    Observation::Signature { sigma, P, m } => (sigma.R, sigma.R, sigma.s, P, m),
+                    Observation::PreSignature {
+                        sigma_prime, P, m, ..
+                    } => (
+                        sigma_prime.K,
+                        sigma_prime.R_prime,
+                        sigma_prime.s_prime,
+                        P,
+                        m,
+                    ),
+                };
+                Some(Entry {
+                    index,
+                    P: *P,
+                    nonce: encode_scalar(&get_x(&K).ok()?).to_vec(),
+                    s,
+                    e: Self::ECDSA.hash_message(m).ok()?,
+                    r: get_x(&R).ok()?,
+                })
+            });
+        find_reuse(entries.collect(), |first, second| {
+            [second.s, -second.s].into_iter().find_map(|s| {
+                let det = invert_scalar(&(first.r * s - first.s * second.r)).ok()?;
+                let p = (first.s * second.e - s * first.e) * det;
+                (ProjectivePoint::mul_by_generator(&p) == *first.P.as_point()).then_some(p)
+            })
+        })
+    }
+}
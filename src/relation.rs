@@ -0,0 +1,84 @@
+use k256::{NonZeroScalar, ProjectivePoint, Scalar};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{AdaptorStatement, AdaptorWitness};
+
+/// `HardRelation` is a relation between statements and witnesses for which finding a witness
+/// from a statement alone is hard. Adaptor pre-signatures are locked to a statement and
+/// adapted with its witness.
+///
+/// The schemes of this crate sign on secp256k1, so a relation must map its statements to a
+/// secp256k1 point `T` and its witnesses to the scalar `t` of $T = tG$, which is what
+/// pre-signatures are locked to and adapted with. The statement and witness themselves can
+/// carry more, such as a second point with the same discrete log.
+///
+/// Relations whose witness is not a secp256k1 scalar, such as a discrete log on another
+/// curve, cannot implement this trait: adapting a pre-signature adds the witness to a
+/// secp256k1 signature scalar, so supporting them would take schemes generic over the curve.
+pub trait HardRelation {
+    /// Public statement, e.g. `T`.
+    type Statement;
+    /// Secret witness, e.g. `t`.
+    type Witness;
+
+    /// Generates a random witness and its statement.
+    ///
+    /// # Arguments
+    /// * `rng` - Cryptographically secure random number generator
+    ///
+    /// # Returns
+    /// * `(Self::Witness, Self::Statement)` - Fresh witness and its statement
+    fn gen_statement<R: RngCore + CryptoRng>(rng: &mut R) -> (Self::Witness, Self::Statement);
+
+    /// Computes the statement of `witness`.
+    fn statement(witness: &Self::Witness) -> Self::Statement;
+
+    /// Returns true if `witness` is a witness for `statement`.
+    fn verify_witness(statement: &Self::Statement, witness: &Self::Witness) -> bool;
+
+    /// Returns the secp256k1 point `T` pre-signatures under `statement` are locked to.
+    fn adaptor_point(statement: &Self::Statement) -> ProjectivePoint;
+
+    /// Returns the scalar `t` used to adapt pre-signatures with `witness`.
+    fn adaptor_scalar(witness: &Self::Witness) -> &Scalar;
+
+    /// Rebuilds a witness from the scalar `t` extracted from a signature. The result must
+    /// still be checked with [`HardRelation::verify_witness`].
+    fn witness_from_scalar(t: Scalar) -> Self::Witness;
+}
+
+/// `DiscreteLog` is the discrete-log relation on secp256k1: `t` is a witness for `T` when
+/// $T = tG$.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiscreteLog;
+
+impl HardRelation for DiscreteLog {
+    type Statement = AdaptorStatement;
+    type Witness = AdaptorWitness;
+
+    fn gen_statement<R: RngCore + CryptoRng>(rng: &mut R) -> (AdaptorWitness, AdaptorStatement) {
+        let t = AdaptorWitness::from(*NonZeroScalar::random(rng));
+        let T = t.statement();
+        (t, T)
+    }
+
+    fn statement(witness: &AdaptorWitness) -> AdaptorStatement {
+        witness.statement()
+    }
+
+    fn verify_witness(statement: &AdaptorStatement, witness: &AdaptorWitness) -> bool {
+        witness.statement() == *statement
+    }
+
+    fn adaptor_point(statement: &AdaptorStatement) -> ProjectivePoint {
+        *statement.as_point()
+    }
+
+    fn adaptor_scalar(witness: &AdaptorWitness) -> &Scalar {
+        witness.as_scalar()
+    }
+
+    fn witness_from_scalar(t: Scalar) -> AdaptorWitness {
+        AdaptorWitness::from(t)
+    }
+}
@@ -1,67 +1,438 @@
-use crate::{AS_scheme, Sigma, Sigma_prime, Schnorr, Sign_scheme, ECDSA};
+use crate::sealed::{KeyPair, PreSignWithKey};
+use crate::{
+    AS_scheme, AdaptorStatement, AdaptorWitness, AumayrECDSA, DiscreteLog, Error, PointTable,
+    PublicKey, Schnorr, SecretKey, Sigma, Sigma_prime, Sigma_prime_Aumayr, Sigma_prime_ECDSA,
+    Sign_scheme, VerifyError, BIP340, ECDSA,
+};
 use k256::{ProjectivePoint, Scalar};
 
 #[derive(Clone)]
 pub enum Scheme {
     Schnorr(Schnorr),
+    BIP340(BIP340),
     ECDSA(ECDSA),
+    AumayrECDSA(AumayrECDSA),
+}
+
+/// `SchemeSignature` is a signature produced through [`Scheme`], tagged with the scheme that
+/// produced it. Passing it to a different scheme fails with `SchemeMismatch`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SchemeSignature {
+    Schnorr(Sigma),
+    BIP340(Sigma),
+    ECDSA(Sigma),
+    #[cfg_attr(feature = "serde", serde(rename = "aumayr"))]
+    AumayrECDSA(Sigma),
+}
+
+impl SchemeSignature {
+    /// Returns the underlying signature `(s, R)`.
+    pub fn as_sigma(&self) -> &Sigma {
+        match self {
+            SchemeSignature::Schnorr(sigma)
+            | SchemeSignature::BIP340(sigma)
+            | SchemeSignature::ECDSA(sigma)
+            | SchemeSignature::AumayrECDSA(sigma) => sigma,
+        }
+    }
+}
+
+/// `SchemePreSignature` is a pre-signature produced through [`Scheme`], tagged with the scheme
+/// that produced it. Passing it to a different scheme fails with `SchemeMismatch`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SchemePreSignature {
+    Schnorr(Sigma_prime),
+    BIP340(Sigma_prime),
+    ECDSA(Sigma_prime_ECDSA),
+    #[cfg_attr(feature = "serde", serde(rename = "aumayr"))]
+    AumayrECDSA(Sigma_prime_Aumayr),
 }
 
 impl Sign_scheme for Scheme {
-    fn sign(&self, p: &Scalar, m: &str, k: &Scalar) -> Sigma {
+    type Signature = SchemeSignature;
+
+    fn nonce_domain(&self) -> &'static [&'static str] {
         match self {
-            Scheme::Schnorr(s) => s.sign(p, m, k),
-            Scheme::ECDSA(e) => e.sign(p, m, k),
+            Scheme::Schnorr(s) => s.nonce_domain(),
+            Scheme::BIP340(b) => b.nonce_domain(),
+            Scheme::ECDSA(e) => e.nonce_domain(),
+            Scheme::AumayrECDSA(a) => a.nonce_domain(),
         }
     }
 
-    fn verify_sign(&self, sigma: &Sigma, P: &ProjectivePoint, m: &str) -> bool {
+    fn sign_with_nonce(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        k: &Scalar,
+    ) -> Result<SchemeSignature, Error> {
         match self {
-            Scheme::Schnorr(s) => s.verify_sign(sigma, P, m),
-            Scheme::ECDSA(e) => e.verify_sign(sigma, P, m),
+            Scheme::Schnorr(s) => s.sign_with_nonce(p, m, k).map(SchemeSignature::Schnorr),
+            Scheme::BIP340(b) => b.sign_with_nonce(p, m, k).map(SchemeSignature::BIP340),
+            Scheme::ECDSA(e) => e.sign_with_nonce(p, m, k).map(SchemeSignature::ECDSA),
+            Scheme::AumayrECDSA(a) => a.sign_with_nonce(p, m, k).map(SchemeSignature::AumayrECDSA),
+        }
+    }
+
+    fn sign(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<SchemeSignature, Error> {
+        match self {
+            Scheme::Schnorr(s) => s.sign(p, m, aux_rand).map(SchemeSignature::Schnorr),
+            Scheme::BIP340(b) => b.sign(p, m, aux_rand).map(SchemeSignature::BIP340),
+            Scheme::ECDSA(e) => e.sign(p, m, aux_rand).map(SchemeSignature::ECDSA),
+            Scheme::AumayrECDSA(a) => a.sign(p, m, aux_rand).map(SchemeSignature::AumayrECDSA),
+        }
+    }
+
+    fn verify_sign_detailed(
+        &self,
+        sigma: &SchemeSignature,
+        P: &PublicKey,
+        m: &[u8],
+    ) -> Result<(), VerifyError> {
+        match (self, sigma) {
+            (Scheme::Schnorr(s), SchemeSignature::Schnorr(sigma)) => {
+                s.verify_sign_detailed(sigma, P, m)
+            }
+            (Scheme::BIP340(b), SchemeSignature::BIP340(sigma)) => {
+                b.verify_sign_detailed(sigma, P, m)
+            }
+            (Scheme::ECDSA(e), SchemeSignature::ECDSA(sigma)) => {
+                e.verify_sign_detailed(sigma, P, m)
+            }
+            (Scheme::AumayrECDSA(a), SchemeSignature::AumayrECDSA(sigma)) => {
+                a.verify_sign_detailed(sigma, P, m)
+            }
+            _ => Err(VerifyError::SchemeMismatch),
+        }
+    }
+
+    fn encode_signature(&self, sigma: &SchemeSignature) -> Result<Vec<u8>, Error> {
+        match (self, sigma) {
+            (Scheme::Schnorr(s), SchemeSignature::Schnorr(sigma)) => s.encode_signature(sigma),
+            (Scheme::BIP340(b), SchemeSignature::BIP340(sigma)) => b.encode_signature(sigma),
+            (Scheme::ECDSA(e), SchemeSignature::ECDSA(sigma)) => e.encode_signature(sigma),
+            (Scheme::AumayrECDSA(a), SchemeSignature::AumayrECDSA(sigma)) => {
+                a.encode_signature(sigma)
+            }
+            _ => Err(Error::SchemeMismatch),
+        }
+    }
+
+    fn decode_signature(&self, bytes: &[u8]) -> Result<SchemeSignature, Error> {
+        match self {
+            Scheme::Schnorr(s) => s.decode_signature(bytes).map(SchemeSignature::Schnorr),
+            Scheme::BIP340(b) => b.decode_signature(bytes).map(SchemeSignature::BIP340),
+            Scheme::ECDSA(e) => e.decode_signature(bytes).map(SchemeSignature::ECDSA),
+            Scheme::AumayrECDSA(a) => a.decode_signature(bytes).map(SchemeSignature::AumayrECDSA),
+        }
+    }
+
+    fn sign_prehash(
+        &self,
+        p: &SecretKey,
+        digest: &[u8; 32],
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<SchemeSignature, Error> {
+        match self {
+            Scheme::Schnorr(s) => s
+                .sign_prehash(p, digest, aux_rand)
+                .map(SchemeSignature::Schnorr),
+            Scheme::BIP340(b) => b
+                .sign_prehash(p, digest, aux_rand)
+                .map(SchemeSignature::BIP340),
+            Scheme::ECDSA(e) => e
+                .sign_prehash(p, digest, aux_rand)
+                .map(SchemeSignature::ECDSA),
+            Scheme::AumayrECDSA(a) => a
+                .sign_prehash(p, digest, aux_rand)
+                .map(SchemeSignature::AumayrECDSA),
+        }
+    }
+
+    fn verify_sign_prehash_detailed(
+        &self,
+        sigma: &SchemeSignature,
+        P: &PublicKey,
+        digest: &[u8; 32],
+    ) -> Result<(), VerifyError> {
+        match (self, sigma) {
+            (Scheme::Schnorr(s), SchemeSignature::Schnorr(sigma)) => {
+                s.verify_sign_prehash_detailed(sigma, P, digest)
+            }
+            (Scheme::BIP340(b), SchemeSignature::BIP340(sigma)) => {
+                b.verify_sign_prehash_detailed(sigma, P, digest)
+            }
+            (Scheme::ECDSA(e), SchemeSignature::ECDSA(sigma)) => {
+                e.verify_sign_prehash_detailed(sigma, P, digest)
+            }
+            (Scheme::AumayrECDSA(a), SchemeSignature::AumayrECDSA(sigma)) => {
+                a.verify_sign_prehash_detailed(sigma, P, digest)
+            }
+            _ => Err(VerifyError::SchemeMismatch),
         }
     }
 }
 
 impl AS_scheme for Scheme {
-    fn pre_sign(&self, p: &Scalar, m: &str, T: &ProjectivePoint, k: &Scalar) -> Sigma_prime {
+    type Relation = DiscreteLog;
+    type PreSignature = SchemePreSignature;
+
+    fn pre_sign_prehash(
+        &self,
+        p: &SecretKey,
+        digest: &[u8; 32],
+        T: &AdaptorStatement,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<SchemePreSignature, Error> {
         match self {
-            Scheme::Schnorr(s) => s.pre_sign(p, m, T, k),
-            Scheme::ECDSA(e) => e.pre_sign(p, m, T, k),
+            Scheme::Schnorr(s) => s
+                .pre_sign_prehash(p, digest, T, aux_rand)
+                .map(SchemePreSignature::Schnorr),
+            Scheme::BIP340(b) => b
+                .pre_sign_prehash(p, digest, T, aux_rand)
+                .map(SchemePreSignature::BIP340),
+            Scheme::ECDSA(e) => e
+                .pre_sign_prehash(p, digest, T, aux_rand)
+                .map(SchemePreSignature::ECDSA),
+            Scheme::AumayrECDSA(a) => a
+                .pre_sign_prehash(p, digest, T, aux_rand)
+                .map(SchemePreSignature::AumayrECDSA),
         }
     }
 
-    fn verify_pre_sign(
+    fn verify_pre_sign_prehash_detailed(
         &self,
-        P: &ProjectivePoint,
-        m: &str,
-        T: &ProjectivePoint,
-        sigma_prime: &Sigma_prime,
-    ) -> bool {
+        P: &PublicKey,
+        digest: &[u8; 32],
+        T: &AdaptorStatement,
+        sigma_prime: &SchemePreSignature,
+    ) -> Result<(), VerifyError> {
+        match (self, sigma_prime) {
+            (Scheme::Schnorr(s), SchemePreSignature::Schnorr(sigma_prime)) => {
+                s.verify_pre_sign_prehash_detailed(P, digest, T, sigma_prime)
+            }
+            (Scheme::BIP340(b), SchemePreSignature::BIP340(sigma_prime)) => {
+                b.verify_pre_sign_prehash_detailed(P, digest, T, sigma_prime)
+            }
+            (Scheme::ECDSA(e), SchemePreSignature::ECDSA(sigma_prime)) => {
+                e.verify_pre_sign_prehash_detailed(P, digest, T, sigma_prime)
+            }
+            (Scheme::AumayrECDSA(a), SchemePreSignature::AumayrECDSA(sigma_prime)) => {
+                a.verify_pre_sign_prehash_detailed(P, digest, T, sigma_prime)
+            }
+            _ => Err(VerifyError::SchemeMismatch),
+        }
+    }
+
+    fn pre_sign_with_nonce(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &AdaptorStatement,
+        k: &Scalar,
+    ) -> Result<SchemePreSignature, Error> {
         match self {
-            Scheme::Schnorr(s) => s.verify_pre_sign(P, m, T, sigma_prime),
-            Scheme::ECDSA(e) => e.verify_pre_sign(P, m, T, sigma_prime),
+            Scheme::Schnorr(s) => s
+                .pre_sign_with_nonce(p, m, T, k)
+                .map(SchemePreSignature::Schnorr),
+            Scheme::BIP340(b) => b
+                .pre_sign_with_nonce(p, m, T, k)
+                .map(SchemePreSignature::BIP340),
+            Scheme::ECDSA(e) => e
+                .pre_sign_with_nonce(p, m, T, k)
+                .map(SchemePreSignature::ECDSA),
+            Scheme::AumayrECDSA(a) => a
+                .pre_sign_with_nonce(p, m, T, k)
+                .map(SchemePreSignature::AumayrECDSA),
         }
     }
 
-    fn adapt_signature(&self, sigma_prime: &Sigma_prime, t: &Scalar) -> Sigma {
+    fn statement_table(&self, T: &AdaptorStatement) -> Option<PointTable> {
         match self {
-            Scheme::Schnorr(s) => s.adapt_signature(sigma_prime, t),
-            Scheme::ECDSA(e) => e.adapt_signature(sigma_prime, t),
+            Scheme::Schnorr(s) => s.statement_table(T),
+            Scheme::BIP340(b) => b.statement_table(T),
+            Scheme::ECDSA(e) => e.statement_table(T),
+            Scheme::AumayrECDSA(a) => a.statement_table(T),
+        }
+    }
+
+    fn pre_sign(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &AdaptorStatement,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<SchemePreSignature, Error> {
+        match self {
+            Scheme::Schnorr(s) => s
+                .pre_sign(p, m, T, aux_rand)
+                .map(SchemePreSignature::Schnorr),
+            Scheme::BIP340(b) => b
+                .pre_sign(p, m, T, aux_rand)
+                .map(SchemePreSignature::BIP340),
+            Scheme::ECDSA(e) => e.pre_sign(p, m, T, aux_rand).map(SchemePreSignature::ECDSA),
+            Scheme::AumayrECDSA(a) => a
+                .pre_sign(p, m, T, aux_rand)
+                .map(SchemePreSignature::AumayrECDSA),
+        }
+    }
+
+    fn verify_pre_sign_detailed(
+        &self,
+        P: &PublicKey,
+        m: &[u8],
+        T: &AdaptorStatement,
+        sigma_prime: &SchemePreSignature,
+    ) -> Result<(), VerifyError> {
+        match (self, sigma_prime) {
+            (Scheme::Schnorr(s), SchemePreSignature::Schnorr(sigma_prime)) => {
+                s.verify_pre_sign_detailed(P, m, T, sigma_prime)
+            }
+            (Scheme::BIP340(b), SchemePreSignature::BIP340(sigma_prime)) => {
+                b.verify_pre_sign_detailed(P, m, T, sigma_prime)
+            }
+            (Scheme::ECDSA(e), SchemePreSignature::ECDSA(sigma_prime)) => {
+                e.verify_pre_sign_detailed(P, m, T, sigma_prime)
+            }
+            (Scheme::AumayrECDSA(a), SchemePreSignature::AumayrECDSA(sigma_prime)) => {
+                a.verify_pre_sign_detailed(P, m, T, sigma_prime)
+            }
+            _ => Err(VerifyError::SchemeMismatch),
+        }
+    }
+
+    fn adapt_signature(
+        &self,
+        sigma_prime: &SchemePreSignature,
+        t: &AdaptorWitness,
+    ) -> Result<SchemeSignature, Error> {
+        match (self, sigma_prime) {
+            (Scheme::Schnorr(s), SchemePreSignature::Schnorr(sigma_prime)) => s
+                .adapt_signature(sigma_prime, t)
+                .map(SchemeSignature::Schnorr),
+            (Scheme::BIP340(b), SchemePreSignature::BIP340(sigma_prime)) => b
+                .adapt_signature(sigma_prime, t)
+                .map(SchemeSignature::BIP340),
+            (Scheme::ECDSA(e), SchemePreSignature::ECDSA(sigma_prime)) => e
+                .adapt_signature(sigma_prime, t)
+                .map(SchemeSignature::ECDSA),
+            (Scheme::AumayrECDSA(a), SchemePreSignature::AumayrECDSA(sigma_prime)) => a
+                .adapt_signature(sigma_prime, t)
+                .map(SchemeSignature::AumayrECDSA),
+            _ => Err(Error::SchemeMismatch),
         }
     }
 
-    fn extract_witness(&self, sigma: &Sigma, sigma_prime: &Sigma_prime) -> Scalar {
+    fn encode_pre_signature(&self, sigma_prime: &SchemePreSignature) -> Result<Vec<u8>, Error> {
+        match (self, sigma_prime) {
+            (Scheme::Schnorr(s), SchemePreSignature::Schnorr(sigma_prime)) => {
+                s.encode_pre_signature(sigma_prime)
+            }
+            (Scheme::BIP340(b), SchemePreSignature::BIP340(sigma_prime)) => {
+                b.encode_pre_signature(sigma_prime)
+            }
+            (Scheme::ECDSA(e), SchemePreSignature::ECDSA(sigma_prime)) => {
+                e.encode_pre_signature(sigma_prime)
+            }
+            (Scheme::AumayrECDSA(a), SchemePreSignature::AumayrECDSA(sigma_prime)) => {
+                a.encode_pre_signature(sigma_prime)
+            }
+            _ => Err(Error::SchemeMismatch),
+        }
+    }
+
+    fn decode_pre_signature(&self, bytes: &[u8]) -> Result<SchemePreSignature, Error> {
         match self {
-            Scheme::Schnorr(s) => s.extract_witness(sigma, sigma_prime),
-            Scheme::ECDSA(e) => e.extract_witness(sigma, sigma_prime),
+            Scheme::Schnorr(s) => s
+                .decode_pre_signature(bytes)
+                .map(SchemePreSignature::Schnorr),
+            Scheme::BIP340(b) => b
+                .decode_pre_signature(bytes)
+                .map(SchemePreSignature::BIP340),
+            Scheme::ECDSA(e) => e.decode_pre_signature(bytes).map(SchemePreSignature::ECDSA),
+            Scheme::AumayrECDSA(a) => a
+                .decode_pre_signature(bytes)
+                .map(SchemePreSignature::AumayrECDSA),
         }
     }
 
-    fn hash_challenge(&self, R: &ProjectivePoint, P: &ProjectivePoint, message: &str) -> Scalar {
+    fn extract_witness(
+        &self,
+        sigma: &SchemeSignature,
+        sigma_prime: &SchemePreSignature,
+        T: &AdaptorStatement,
+    ) -> Result<AdaptorWitness, Error> {
+        match (self, sigma, sigma_prime) {
+            (
+                Scheme::Schnorr(s),
+                SchemeSignature::Schnorr(sigma),
+                SchemePreSignature::Schnorr(sigma_prime),
+            ) => s.extract_witness(sigma, sigma_prime, T),
+            (
+                Scheme::BIP340(b),
+                SchemeSignature::BIP340(sigma),
+                SchemePreSignature::BIP340(sigma_prime),
+            ) => b.extract_witness(sigma, sigma_prime, T),
+            (
+                Scheme::ECDSA(e),
+                SchemeSignature::ECDSA(sigma),
+                SchemePreSignature::ECDSA(sigma_prime),
+            ) => e.extract_witness(sigma, sigma_prime, T),
+            (
+                Scheme::AumayrECDSA(a),
+                SchemeSignature::AumayrECDSA(sigma),
+                SchemePreSignature::AumayrECDSA(sigma_prime),
+            ) => a.extract_witness(sigma, sigma_prime, T),
+            _ => Err(Error::SchemeMismatch),
+        }
+    }
+
+    fn hash_challenge(
+        &self,
+        R: &ProjectivePoint,
+        P: &PublicKey,
+        message: &[u8],
+    ) -> Result<Scalar, Error> {
         match self {
             Scheme::Schnorr(s) => s.hash_challenge(R, P, message),
+            Scheme::BIP340(b) => b.hash_challenge(R, P, message),
             Scheme::ECDSA(e) => e.hash_challenge(R, P, message),
+            Scheme::AumayrECDSA(a) => a.hash_challenge(R, P, message),
+        }
+    }
+}
+
+impl PreSignWithKey<AdaptorStatement, SchemePreSignature> for Scheme {
+    fn pre_sign_with_key(
+        &self,
+        key: &KeyPair,
+        m: &[u8],
+        T: &AdaptorStatement,
+        k: &Scalar,
+        table: Option<&PointTable>,
+    ) -> Result<SchemePreSignature, Error> {
+        match self {
+            Scheme::Schnorr(s) => s
+                .pre_sign_with_key(key, m, T, k, table)
+                .map(SchemePreSignature::Schnorr),
+            Scheme::BIP340(b) => b
+                .pre_sign_with_key(key, m, T, k, table)
+                .map(SchemePreSignature::BIP340),
+            Scheme::ECDSA(e) => e
+                .pre_sign_with_key(key, m, T, k, table)
+                .map(SchemePreSignature::ECDSA),
+            Scheme::AumayrECDSA(a) => a
+                .pre_sign_with_key(key, m, T, k, table)
+                .map(SchemePreSignature::AumayrECDSA),
         }
     }
 }
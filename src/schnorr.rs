@@ -1,16 +1,52 @@
-use k256::{
-    elliptic_curve::{ops::Reduce, sec1::ToEncodedPoint},
-    ProjectivePoint, Scalar, U256,
-};
-use sha2::{Digest, Sha256};
+use k256::{elliptic_curve::ops::MulByGenerator, ProjectivePoint, Scalar};
+use std::marker::PhantomData;
 
-use crate::{AS_scheme, Sigma, Sigma_prime, Sign_scheme};
+use crate::ciphersuite::{Ciphersuite, Sha256Suite};
+use crate::msm::{msm_vartime, PointTable};
+use crate::sealed::{KeyPair, PreSignWithKey};
+use crate::{
+    AS_scheme, DiscreteLog, Error, HardRelation, PublicKey, SecretKey, Sigma, Sigma_prime,
+    Sign_scheme, VerifyError,
+};
 
-/// `Schnorr` implements the Schnorr digital signature scheme and its adaptor variant.
+/// `Schnorr` implements the Schnorr digital signature scheme and its adaptor variant, with
+/// pre-signatures locked to the statements of the hard relation `Rel` and challenges hashed
+/// with the ciphersuite `Suite`.
+///
+/// [`Schnorr::new`] returns the scheme over [`DiscreteLog`] with [`Sha256Suite`], and
+/// `Schnorr::<Rel, Suite>::default()` the scheme over another relation or ciphersuite.
 #[derive(Clone)]
-pub struct Schnorr;
+pub struct Schnorr<Rel = DiscreteLog, Suite = Sha256Suite> {
+    _relation: PhantomData<Rel>,
+    _suite: PhantomData<Suite>,
+}
+
+impl Schnorr {
+    /// Returns the scheme over the secp256k1 discrete-log relation with SHA-256 challenges.
+    pub const fn new() -> Self {
+        Self {
+            _relation: PhantomData,
+            _suite: PhantomData,
+        }
+    }
+}
+
+impl<Rel, Suite> Default for Schnorr<Rel, Suite> {
+    fn default() -> Self {
+        Self {
+            _relation: PhantomData,
+            _suite: PhantomData,
+        }
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> Sign_scheme for Schnorr<Rel, Suite> {
+    type Signature = Sigma;
+
+    fn nonce_domain(&self) -> &'static [&'static str] {
+        const { &["as_for_fde/Schnorr", Suite::ID] }
+    }
 
-impl Sign_scheme for Schnorr {
     /// Signs a message `m` using secret key `p` and nonce `k`.
     ///
     /// # Arguments
@@ -19,16 +55,19 @@ impl Sign_scheme for Schnorr {
     /// * `k` - Random nonce
     ///
     /// # Returns
-    /// * `Sigma` - Standard Schnorr signature `(s, R)`
-    fn sign(&self, p: &Scalar, m: &str, k: &Scalar) -> Sigma {
+    /// * `Result<Sigma, Error>` - Standard Schnorr signature `(s, R)`, or an error if `m` is
+    ///   empty or `k` is zero
+    fn sign_with_nonce(&self, p: &SecretKey, m: &[u8], k: &Scalar) -> Result<Sigma, Error> {
         if m.is_empty() {
-            panic!("Message cannot be empty.");
+            return Err(Error::EmptyMessage);
         }
-        let R = ProjectivePoint::GENERATOR * k;
-        let P = ProjectivePoint::GENERATOR * p;
-        let e = self.hash_challenge(&R, &P, m);
-        let s = k + e * p;
-        Sigma { s, R }
+        if *k == Scalar::ZERO {
+            return Err(Error::InvalidScalar);
+        }
+        let R = ProjectivePoint::mul_by_generator(k);
+        let e = self.hash_challenge(&R, &p.public_key(), m)?;
+        let s = k + e * p.as_scalar();
+        Ok(Sigma { s, R })
     }
 
     /// Verifies a standard Schnorr signature.
@@ -39,16 +78,54 @@ impl Sign_scheme for Schnorr {
     /// * `m` - Message that was signed
     ///
     /// # Returns
-    /// * `bool` - True if the signature is valid
-    fn verify_sign(&self, sigma: &Sigma, P: &ProjectivePoint, m: &str) -> bool {
-        let e = self.hash_challenge(&sigma.R, P, m); // compute hash
-        let lhs = ProjectivePoint::GENERATOR * sigma.s; // multiply pre-signature by curve generator
-        let rhs = sigma.R + *P * e; // compute R + H(R|P|m)P
-        lhs == rhs
+    /// * `Result<(), VerifyError>` - `Ok(())` if the signature is valid, otherwise the reason
+    ///   it is rejected
+    fn verify_sign_detailed(
+        &self,
+        sigma: &Sigma,
+        P: &PublicKey,
+        m: &[u8],
+    ) -> Result<(), VerifyError> {
+        if *P.as_point() == ProjectivePoint::IDENTITY || sigma.R == ProjectivePoint::IDENTITY {
+            return Err(VerifyError::IdentityPoint);
+        }
+        let e = self.hash_challenge(&sigma.R, P, m)?; // compute hash
+        let lhs = msm_vartime(&[(ProjectivePoint::GENERATOR, sigma.s), (*P.as_point(), -e)]); // compute sG - H(R|P|m)P
+        if lhs == sigma.R {
+            Ok(())
+        } else {
+            Err(VerifyError::EquationMismatch)
+        }
+    }
+
+    /// Serializes a signature as `R || s` (65 bytes), see [`Sigma::to_bytes`].
+    ///
+    /// # Arguments
+    /// * `sigma` - Signature `(s, R)`
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>, Error>` - Canonical encoding of the signature; never fails
+    fn encode_signature(&self, sigma: &Sigma) -> Result<Vec<u8>, Error> {
+        Ok(sigma.to_bytes().to_vec())
+    }
+
+    /// Parses a signature encoded as `R || s`, see [`Sigma::from_bytes`].
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded signature
+    ///
+    /// # Returns
+    /// * `Result<Sigma, Error>` - The decoded signature, or an error if the length is wrong,
+    ///   `R` is off-curve or the identity, or `s` is not below the group order
+    fn decode_signature(&self, bytes: &[u8]) -> Result<Sigma, Error> {
+        Sigma::from_bytes(bytes)
     }
 }
 
-impl AS_scheme for Schnorr {
+impl<Rel: HardRelation, Suite: Ciphersuite> AS_scheme for Schnorr<Rel, Suite> {
+    type Relation = Rel;
+    type PreSignature = Sigma_prime;
+
     /// Computes a challenge scalar using a hash of `(R || P || m)`.
     ///
     /// # Arguments
@@ -57,17 +134,17 @@ impl AS_scheme for Schnorr {
     /// * `message` - Message to sign
     ///
     /// # Returns
-    /// * `Scalar` - Challenge derived from hash
-    fn hash_challenge(&self, R: &ProjectivePoint, P: &ProjectivePoint, message: &str) -> Scalar {
+    /// * `Result<Scalar, Error>` - Challenge derived from hash, or `Error::EmptyMessage`
+    fn hash_challenge(
+        &self,
+        R: &ProjectivePoint,
+        P: &PublicKey,
+        message: &[u8],
+    ) -> Result<Scalar, Error> {
         if message.is_empty() {
-            panic!("Message cannot be empty.");
+            return Err(Error::EmptyMessage);
         }
-        let mut hasher = Sha256::new(); //init hasher
-        hasher.update(R.to_affine().to_encoded_point(false).as_bytes()); // add R
-        hasher.update(P.to_affine().to_encoded_point(false).as_bytes()); // add P
-        hasher.update(message.as_bytes()); // add message
-        let hash: [u8; 32] = hasher.finalize().into();
-        <Scalar as Reduce<U256>>::reduce_bytes(&hash.into())
+        Ok(Suite::challenge(R, P.as_point(), message))
     }
 
     /// Creates a Schnorr adaptor pre-signature `(s', R')` with a tweak point `T = tG`.
@@ -79,20 +156,16 @@ impl AS_scheme for Schnorr {
     /// * `r_prime` - Random nonce
     ///
     /// # Returns
-    /// * `Sigma_prime` - Adaptor pre-signature
-    fn pre_sign(&self, p: &Scalar, m: &str, T: &ProjectivePoint, r_prime: &Scalar) -> Sigma_prime {
-        if m.is_empty() {
-            panic!("Message cannot be empty.");
-        }
-        let R_prime = ProjectivePoint::GENERATOR * r_prime + T;
-        let P = ProjectivePoint::GENERATOR * p;
-        let e = self.hash_challenge(&R_prime, &P, m);
-        let s_prime = *r_prime + e * p;
-        Sigma_prime {
-            s_prime,
-            R_prime,
-            ..Default::default()
-        }
+    /// * `Result<Sigma_prime, Error>` - Adaptor pre-signature, or an error if `m` is empty,
+    ///   `r_prime` is zero or `R'` is the identity
+    fn pre_sign_with_nonce(
+        &self,
+        p: &SecretKey,
+        m: &[u8],
+        T: &Rel::Statement,
+        r_prime: &Scalar,
+    ) -> Result<Sigma_prime, Error> {
+        self.pre_sign_with_key(&KeyPair::new(p), m, T, r_prime, None)
     }
 
     /// Verifies a Schnorr adaptor pre-signature.
@@ -104,18 +177,31 @@ impl AS_scheme for Schnorr {
     /// * `sigma_prime` - Adaptor pre-signature `(s', R')`
     ///
     /// # Returns
-    /// * `bool` - True if pre-signature is valid
-    fn verify_pre_sign(
+    /// * `Result<(), VerifyError>` - `Ok(())` if the pre-signature is valid, otherwise the
+    ///   reason it is rejected
+    fn verify_pre_sign_detailed(
         &self,
-        P: &ProjectivePoint,
-        m: &str,
-        T: &ProjectivePoint,
+        P: &PublicKey,
+        m: &[u8],
+        T: &Rel::Statement,
         sigma_prime: &Sigma_prime,
-    ) -> bool {
-        let e = self.hash_challenge(&sigma_prime.R_prime, &P, m); // compute hash
-        let lhs = ProjectivePoint::GENERATOR * sigma_prime.s_prime; // multiply pre-signature by curve generator
-        let rhs = sigma_prime.R_prime - T + *P * e; // compute R'-T + H(R'|P|m)P
-        lhs == rhs
+    ) -> Result<(), VerifyError> {
+        if *P.as_point() == ProjectivePoint::IDENTITY
+            || sigma_prime.R_prime == ProjectivePoint::IDENTITY
+        {
+            return Err(VerifyError::IdentityPoint);
+        }
+        let e = self.hash_challenge(&sigma_prime.R_prime, P, m)?; // compute hash
+        let lhs = msm_vartime(&[
+            (ProjectivePoint::GENERATOR, sigma_prime.s_prime),
+            (*P.as_point(), -e),
+        ]); // compute s'G - H(R'|P|m)P
+        let rhs = sigma_prime.R_prime - Rel::adaptor_point(T); // compute R'-T
+        if lhs == rhs {
+            Ok(())
+        } else {
+            Err(VerifyError::EquationMismatch)
+        }
     }
 
     /// Adapts a pre-signature into a valid signature using secret witness `t`.
@@ -125,13 +211,13 @@ impl AS_scheme for Schnorr {
     /// * `t` - Secret tweak scalar
     ///
     /// # Returns
-    /// * `Sigma` - Final adapted signature `(s, R)` such that $s = s' + t$
-    fn adapt_signature(&self, sigma_prime: &Sigma_prime, t: &Scalar) -> Sigma {
-        let s = sigma_prime.s_prime + (*t);
-        Sigma {
+    /// * `Result<Sigma, Error>` - Final adapted signature `(s, R)` such that $s = s' + t$
+    fn adapt_signature(&self, sigma_prime: &Sigma_prime, t: &Rel::Witness) -> Result<Sigma, Error> {
+        let s = sigma_prime.s_prime + Rel::adaptor_scalar(t);
+        Ok(Sigma {
             s,
             R: sigma_prime.R_prime,
-        }
+        })
     }
 
     /// Extracts the witness `t` used to adapt the pre-signature into the full signature.
@@ -139,10 +225,75 @@ impl AS_scheme for Schnorr {
     /// # Arguments
     /// * `sigma` - Final signature `(s, R)`
     /// * `sigma_prime` - Pre-signature `(s', R')`
+    /// * `T` - Tweak point the witness must match
     ///
     /// # Returns
-    /// * `Scalar` - Extracted secret tweak `t` such that $t = s - s'$
-    fn extract_witness(&self, sigma: &Sigma, sigma_prime: &Sigma_prime) -> Scalar {
-        sigma.s - sigma_prime.s_prime
+    /// * `Result<Rel::Witness, Error>` - Extracted secret tweak `t` such that $t = s - s'$, or
+    ///   `Error::WitnessMismatch` if $tG \neq T$
+    fn extract_witness(
+        &self,
+        sigma: &Sigma,
+        sigma_prime: &Sigma_prime,
+        T: &Rel::Statement,
+    ) -> Result<Rel::Witness, Error> {
+        let t = Rel::witness_from_scalar(sigma.s - sigma_prime.s_prime);
+        if Rel::verify_witness(T, &t) {
+            Ok(t)
+        } else {
+            Err(Error::WitnessMismatch)
+        }
+    }
+
+    /// Serializes a pre-signature as `R' || s'` (65 bytes), see [`Sigma_prime::to_bytes`].
+    ///
+    /// # Arguments
+    /// * `sigma_prime` - Pre-signature `(s', R')`
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>, Error>` - Canonical encoding of the pre-signature; never fails
+    fn encode_pre_signature(&self, sigma_prime: &Sigma_prime) -> Result<Vec<u8>, Error> {
+        Ok(sigma_prime.to_bytes().to_vec())
+    }
+
+    /// Parses a pre-signature encoded as `R' || s'`.
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded pre-signature
+    ///
+    /// # Returns
+    /// * `Result<Sigma_prime, Error>` - The decoded pre-signature, or an error if the length is
+    ///   wrong, `R'` is off-curve or the identity, or `s'` is not below the group order
+    fn decode_pre_signature(&self, bytes: &[u8]) -> Result<Sigma_prime, Error> {
+        Sigma_prime::from_bytes(bytes)
+    }
+}
+
+impl<Rel: HardRelation, Suite: Ciphersuite> PreSignWithKey<Rel::Statement, Sigma_prime>
+    for Schnorr<Rel, Suite>
+{
+    /// Pre-signs `m` with nonce `r_prime` and the precomputed public key of `key`. `T` is
+    /// only added to `R'`, so `table` is not used.
+    fn pre_sign_with_key(
+        &self,
+        key: &KeyPair,
+        m: &[u8],
+        T: &Rel::Statement,
+        r_prime: &Scalar,
+        _table: Option<&PointTable>,
+    ) -> Result<Sigma_prime, Error> {
+        let (p, P) = (key.p, &key.P);
+        if m.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+        if *r_prime == Scalar::ZERO {
+            return Err(Error::InvalidScalar);
+        }
+        let R_prime = ProjectivePoint::mul_by_generator(r_prime) + Rel::adaptor_point(T);
+        if R_prime == ProjectivePoint::IDENTITY {
+            return Err(Error::InvalidPoint);
+        }
+        let e = self.hash_challenge(&R_prime, P, m)?;
+        let s_prime = *r_prime + e * p.as_scalar();
+        Ok(Sigma_prime { s_prime, R_prime })
     }
 }
@@ -0,0 +1,188 @@
+//! Serde support for points, scalars, keys and [`Scheme`], enabled by the `serde` feature.
+//!
+//! Points and scalars use the canonical encodings of [`crate::utils`]: hex strings in
+//! human-readable formats such as JSON, raw bytes in binary formats such as CBOR.
+//! Deserialisation goes through the same strict decoders, so off-curve or identity points
+//! and non-canonical scalars are rejected. [`SecretKey`] and [`AdaptorWitness`] go through
+//! their `from_bytes`, which also rejects zero.
+//!
+//! Raw `Scalar` and `ProjectivePoint` fields in other types can reuse the same encoding with
+//! `#[serde(with = "as_for_fde::serialization::point")]` or
+//! `#[serde(with = "as_for_fde::serialization::scalar")]`.
+
+use std::fmt;
+
+use k256::{ProjectivePoint, Scalar};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use zeroize::Zeroizing;
+
+use crate::utils::{decode_point, decode_scalar, encode_point, encode_scalar};
+use crate::{AdaptorWitness, AumayrECDSA, Scheme, Schnorr, SecretKey, BIP340, ECDSA};
+
+/// Writes `bytes` as a hex string or as raw bytes, depending on the format.
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+/// Reads bytes written by `serialize_bytes`.
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a byte string")
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(bytes)
+        }
+    }
+
+    if deserializer.is_human_readable() {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(s).map_err(de::Error::custom)
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+/// `#[serde(with = "...")]` module for non-identity points.
+pub mod point {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(W: &ProjectivePoint, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&encode_point(W), serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ProjectivePoint, D::Error> {
+        let bytes = deserialize_bytes(deserializer)?;
+        decode_point(&bytes).map_err(de::Error::custom)
+    }
+}
+
+/// `#[serde(with = "...")]` module for scalars.
+pub mod scalar {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(s: &Scalar, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&encode_scalar(s), serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Scalar, D::Error> {
+        let bytes = deserialize_bytes(deserializer)?;
+        decode_scalar(&bytes).map_err(de::Error::custom)
+    }
+}
+
+/// `#[serde(with = "...")]` module for secret keys, parsed with [`SecretKey::from_bytes`].
+pub mod secret_key {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(p: &SecretKey, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&Zeroizing::new(p.to_bytes())[..], serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SecretKey, D::Error> {
+        let bytes = Zeroizing::new(deserialize_bytes(deserializer)?);
+        SecretKey::from_bytes(&bytes).map_err(de::Error::custom)
+    }
+}
+
+/// `#[serde(with = "...")]` module for adaptor witnesses, parsed with
+/// [`AdaptorWitness::from_bytes`].
+pub mod adaptor_witness {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(t: &AdaptorWitness, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&Zeroizing::new(t.to_bytes())[..], serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<AdaptorWitness, D::Error> {
+        let bytes = Zeroizing::new(deserialize_bytes(deserializer)?);
+        AdaptorWitness::from_bytes(&bytes).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for SecretKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        secret_key::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        secret_key::deserialize(deserializer)
+    }
+}
+
+impl Serialize for AdaptorWitness {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        adaptor_witness::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AdaptorWitness {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        adaptor_witness::deserialize(deserializer)
+    }
+}
+
+impl Scheme {
+    /// Name of the scheme, as accepted by the protocol binaries.
+    fn name(&self) -> &'static str {
+        match self {
+            Scheme::Schnorr(_) => "schnorr",
+            Scheme::BIP340(_) => "bip340",
+            Scheme::ECDSA(_) => "ecdsa",
+            Scheme::AumayrECDSA(_) => "aumayr",
+        }
+    }
+}
+
+impl Serialize for Scheme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Scheme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        match name.as_str() {
+            "schnorr" => Ok(Scheme::Schnorr(Schnorr::new())),
+            "bip340" => Ok(Scheme::BIP340(BIP340)),
+            "ecdsa" => Ok(Scheme::ECDSA(ECDSA::new())),
+            "aumayr" => Ok(Scheme::AumayrECDSA(AumayrECDSA::new())),
+            _ => Err(de::Error::unknown_variant(
+                &name,
+                &["schnorr", "bip340", "ecdsa", "aumayr"],
+            )),
+        }
+    }
+}
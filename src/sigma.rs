@@ -0,0 +1,707 @@
+use k256::{elliptic_curve::ops::Reduce, ProjectivePoint, Scalar, U256};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::msm::{msm_vartime, mul_base, mul_with_table, PointTable};
+use crate::utils::{derive_nonce, encode_scalar};
+use crate::{Error, Transcript, VerifyError};
+
+/// `Proof` is a non-interactive Sigma-protocol proof `(e, z)`: the Fiat–Shamir challenge `e`
+/// and the prover's response `z`. The verifier recomputes the commitment from both and checks
+/// that it hashes back to `e`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof<Z> {
+    pub e: Scalar,
+    pub z: Z,
+}
+
+/// `NonceGen` derives the prover's nonces, and the challenges and responses of simulated
+/// branches, from the witness and the transcript with RFC 6979, optionally hedged with
+/// auxiliary randomness. Each call to [`NonceGen::next_scalar`] returns a fresh scalar.
+pub struct NonceGen<'a> {
+    key: Zeroizing<Scalar>,
+    transcript: [u8; 32],
+    aux_rand: Option<&'a [u8; 32]>,
+    counter: u32,
+}
+
+impl NonceGen<'_> {
+    /// Returns the next scalar of the sequence.
+    pub fn next_scalar(&mut self) -> Scalar {
+        let mut m = [0u8; 36];
+        m[..32].copy_from_slice(&self.transcript); // add transcript digest
+        m[32..].copy_from_slice(&self.counter.to_be_bytes()); // add counter
+        self.counter += 1;
+        derive_nonce(&self.key, &m, &[], "sigma", None, self.aux_rand)
+    }
+}
+
+/// `SigmaProtocol` is a three-move proof of knowledge (commitment, challenge, response) on
+/// secp256k1, made non-interactive with Fiat–Shamir over a [`Transcript`] by
+/// [`SigmaProtocol::prove`].
+///
+/// Proofs are sent as `(e, z)` and the verifier recomputes the commitment from them, so every
+/// protocol must be able to rebuild its commitment from a challenge and a response, and to
+/// simulate a transcript for a given challenge without the witness. The latter is what
+/// [`Or`] uses to hide which branch is true.
+pub trait SigmaProtocol {
+    /// Public statement, e.g. `(G, X)`.
+    type Statement;
+    /// Secret witness, e.g. `x` with $X = xG$.
+    type Witness;
+    /// Prover's first message, e.g. `R = rG`.
+    type Commitment;
+    /// Prover's answer to the challenge, e.g. `z = r + ex`.
+    type Response;
+    /// Secret prover state kept between [`SigmaProtocol::commit`] and
+    /// [`SigmaProtocol::respond`], e.g. the nonce `r`.
+    type State;
+
+    /// Returns true if `witness` is a witness for `statement`.
+    fn holds(&self, statement: &Self::Statement, witness: &Self::Witness) -> bool;
+
+    /// Appends the statement to the transcript.
+    fn absorb_statement(&self, statement: &Self::Statement, transcript: &mut Transcript);
+
+    /// Appends the commitment to the transcript.
+    fn absorb_commitment(&self, commitment: &Self::Commitment, transcript: &mut Transcript);
+
+    /// Appends the witness to the secret the nonces are derived from.
+    fn encode_witness(&self, witness: &Self::Witness, out: &mut Vec<u8>);
+
+    /// First move: draws the nonces from `nonces` and computes the commitment.
+    fn commit(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        nonces: &mut NonceGen,
+    ) -> (Self::State, Self::Commitment);
+
+    /// Third move: answers the challenge `e`.
+    fn respond(&self, witness: &Self::Witness, state: Self::State, e: &Scalar) -> Self::Response;
+
+    /// Produces an accepting commitment and response for the challenge `e` without the
+    /// witness.
+    fn simulate(
+        &self,
+        statement: &Self::Statement,
+        e: &Scalar,
+        nonces: &mut NonceGen,
+    ) -> (Self::Commitment, Self::Response);
+
+    /// Recomputes the commitment from the challenge `e` and the response, or returns `None`
+    /// if the response is malformed.
+    fn recompute_commitment(
+        &self,
+        statement: &Self::Statement,
+        e: &Scalar,
+        response: &Self::Response,
+    ) -> Option<Self::Commitment>;
+
+    /// Computes the Fiat–Shamir challenge `e` by appending the statement and the commitment
+    /// to a copy of `transcript`.
+    fn challenge(
+        &self,
+        transcript: &Transcript,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+    ) -> Scalar {
+        let mut transcript = transcript.clone();
+        self.absorb_statement(statement, &mut transcript); // add statement
+        self.absorb_commitment(commitment, &mut transcript); // add commitment
+        transcript.challenge_scalar("challenge")
+    }
+
+    /// Proves knowledge of `witness` for `statement`.
+    ///
+    /// # Arguments
+    /// * `transcript` - Transcript holding the domain label and the session context
+    /// * `statement` - Public statement
+    /// * `witness` - Secret witness
+    /// * `aux_rand` - Optional randomness hedging the nonces
+    ///
+    /// # Returns
+    /// * `Result<Proof<Self::Response>, Error>` - The proof `(e, z)`, or
+    ///   `Error::WitnessMismatch` if `witness` is not a witness for `statement`
+    fn prove(
+        &self,
+        transcript: &Transcript,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        aux_rand: Option<&[u8; 32]>,
+    ) -> Result<Proof<Self::Response>, Error> {
+        if !self.holds(statement, witness) {
+            return Err(Error::WitnessMismatch);
+        }
+        Ok(prove_unchecked(
+            self, transcript, statement, witness, aux_rand,
+        ))
+    }
+
+    /// Verifies `proof` by recomputing the commitment and checking that it hashes to `e`.
+    ///
+    /// # Returns
+    /// * `Result<(), VerifyError>` - `Ok(())` if the proof is valid, otherwise
+    ///   `VerifyError::InvalidProof`
+    fn verify_detailed(
+        &self,
+        transcript: &Transcript,
+        statement: &Self::Statement,
+        proof: &Proof<Self::Response>,
+    ) -> Result<(), VerifyError> {
+        let commitment = self
+            .recompute_commitment(statement, &proof.e, &proof.z)
+            .ok_or(VerifyError::InvalidProof)?;
+        if self.challenge(transcript, statement, &commitment) == proof.e {
+            Ok(())
+        } else {
+            Err(VerifyError::InvalidProof)
+        }
+    }
+
+    fn verify(
+        &self,
+        transcript: &Transcript,
+        statement: &Self::Statement,
+        proof: &Proof<Self::Response>,
+    ) -> bool {
+        self.verify_detailed(transcript, statement, proof).is_ok()
+    }
+}
+
+/// Statement `(G, X)` of [`DLog`]: the prover knows `x` with $X = xG$ for an arbitrary base
+/// `G`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLogStatement {
+    pub G: ProjectivePoint,
+    pub X: ProjectivePoint,
+}
+
+/// `DLog` is the Schnorr protocol for knowledge of a discrete log: $R = rG$, $z = r + ex$,
+/// checked as $zG - eX = R$.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DLog;
+
+impl SigmaProtocol for DLog {
+    type Statement = DLogStatement;
+    type Witness = Scalar;
+    type Commitment = ProjectivePoint;
+    type Response = Scalar;
+    type State = Zeroizing<Scalar>;
+
+    fn holds(&self, statement: &DLogStatement, x: &Scalar) -> bool {
+        mul_base(&statement.G, x) == statement.X
+    }
+
+    fn absorb_statement(&self, statement: &DLogStatement, transcript: &mut Transcript) {
+        transcript.append_point("G", &statement.G);
+        transcript.append_point("X", &statement.X);
+    }
+
+    fn absorb_commitment(&self, R: &ProjectivePoint, transcript: &mut Transcript) {
+        transcript.append_point("R", R);
+    }
+
+    fn encode_witness(&self, x: &Scalar, out: &mut Vec<u8>) {
+        out.extend_from_slice(&encode_scalar(x));
+    }
+
+    fn commit(
+        &self,
+        statement: &DLogStatement,
+        _x: &Scalar,
+        nonces: &mut NonceGen,
+    ) -> (Zeroizing<Scalar>, ProjectivePoint) {
+        let r = Zeroizing::new(nonces.next_scalar());
+        let R = mul_base(&statement.G, &r);
+        (r, R)
+    }
+
+    fn respond(&self, x: &Scalar, r: Zeroizing<Scalar>, e: &Scalar) -> Scalar {
+        *r + e * x
+    }
+
+    fn simulate(
+        &self,
+        statement: &DLogStatement,
+        e: &Scalar,
+        nonces: &mut NonceGen,
+    ) -> (ProjectivePoint, Scalar) {
+        let z = nonces.next_scalar();
+        (mul_base(&statement.G, &z) - statement.X * e, z)
+    }
+
+    fn recompute_commitment(
+        &self,
+        statement: &DLogStatement,
+        e: &Scalar,
+        z: &Scalar,
+    ) -> Option<ProjectivePoint> {
+        Some(msm_vartime(&[(statement.G, *z), (statement.X, -e)]))
+    }
+}
+
+/// Statement `(G, X, H, Y)` of [`Dleq`]: the prover knows `x` with $X = xG$ and $Y = xH$,
+/// i.e. $\log_G(X) = \log_H(Y)$, for arbitrary bases `G` and `H`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DleqStatement {
+    pub G: ProjectivePoint,
+    pub X: ProjectivePoint,
+    pub H: ProjectivePoint,
+    pub Y: ProjectivePoint,
+}
+
+/// `Dleq` is the Chaum–Pedersen protocol for equality of discrete logs: $J = jG$, $J' = jH$,
+/// $z = j + ex$, checked as $zG - eX = J$ and $zH - eY = J'$.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Dleq;
+
+impl SigmaProtocol for Dleq {
+    type Statement = DleqStatement;
+    type Witness = Scalar;
+    type Commitment = (ProjectivePoint, ProjectivePoint);
+    type Response = Scalar;
+    type State = Zeroizing<Scalar>;
+
+    fn holds(&self, statement: &DleqStatement, x: &Scalar) -> bool {
+        mul_base(&statement.G, x) == statement.X && statement.H * x == statement.Y
+    }
+
+    fn absorb_statement(&self, statement: &DleqStatement, transcript: &mut Transcript) {
+        transcript.append_point("G", &statement.G);
+        transcript.append_point("X", &statement.X);
+        transcript.append_point("H", &statement.H);
+        transcript.append_point("Y", &statement.Y);
+    }
+
+    fn absorb_commitment(
+        &self,
+        (J, J_prime): &(ProjectivePoint, ProjectivePoint),
+        transcript: &mut Transcript,
+    ) {
+        transcript.append_point("J", J);
+        transcript.append_point("J'", J_prime);
+    }
+
+    fn encode_witness(&self, x: &Scalar, out: &mut Vec<u8>) {
+        out.extend_from_slice(&encode_scalar(x));
+    }
+
+    fn commit(
+        &self,
+        statement: &DleqStatement,
+        _x: &Scalar,
+        nonces: &mut NonceGen,
+    ) -> (Zeroizing<Scalar>, (ProjectivePoint, ProjectivePoint)) {
+        let j = Zeroizing::new(nonces.next_scalar());
+        let J = mul_base(&statement.G, &j);
+        let J_prime = statement.H * *j;
+        (j, (J, J_prime))
+    }
+
+    fn respond(&self, x: &Scalar, j: Zeroizing<Scalar>, e: &Scalar) -> Scalar {
+        *j + e * x
+    }
+
+    fn simulate(
+        &self,
+        statement: &DleqStatement,
+        e: &Scalar,
+        nonces: &mut NonceGen,
+    ) -> ((ProjectivePoint, ProjectivePoint), Scalar) {
+        let z = nonces.next_scalar();
+        let J = mul_base(&statement.G, &z) - statement.X * e;
+        let J_prime = statement.H * z - statement.Y * e;
+        ((J, J_prime), z)
+    }
+
+    fn recompute_commitment(
+        &self,
+        statement: &DleqStatement,
+        e: &Scalar,
+        z: &Scalar,
+    ) -> Option<(ProjectivePoint, ProjectivePoint)> {
+        let J = msm_vartime(&[(statement.G, *z), (statement.X, -e)]);
+        let J_prime = msm_vartime(&[(statement.H, *z), (statement.Y, -e)]);
+        Some((J, J_prime))
+    }
+}
+
+/// `DleqWithTable` is [`Dleq`] with a precomputed [`PointTable`] of the second base `H`,
+/// for a prover making many proofs against the same `H`. Its proofs are the same as those of
+/// [`Dleq`] and verify with it.
+#[derive(Clone, Copy)]
+pub struct DleqWithTable<'a>(pub &'a PointTable);
+
+impl DleqWithTable<'_> {
+    /// Computes $kH$ with the table if it is the table of `H`.
+    fn mul_H(&self, statement: &DleqStatement, k: &Scalar) -> ProjectivePoint {
+        let table = (*self.0.point() == statement.H).then_some(self.0);
+        mul_with_table(&statement.H, table, k)
+    }
+}
+
+impl SigmaProtocol for DleqWithTable<'_> {
+    type Statement = DleqStatement;
+    type Witness = Scalar;
+    type Commitment = (ProjectivePoint, ProjectivePoint);
+    type Response = Scalar;
+    type State = Zeroizing<Scalar>;
+
+    fn holds(&self, statement: &DleqStatement, x: &Scalar) -> bool {
+        mul_base(&statement.G, x) == statement.X && self.mul_H(statement, x) == statement.Y
+    }
+
+    fn absorb_statement(&self, statement: &DleqStatement, transcript: &mut Transcript) {
+        Dleq.absorb_statement(statement, transcript)
+    }
+
+    fn absorb_commitment(
+        &self,
+        commitment: &(ProjectivePoint, ProjectivePoint),
+        transcript: &mut Transcript,
+    ) {
+        Dleq.absorb_commitment(commitment, transcript)
+    }
+
+    fn encode_witness(&self, x: &Scalar, out: &mut Vec<u8>) {
+        Dleq.encode_witness(x, out)
+    }
+
+    fn commit(
+        &self,
+        statement: &DleqStatement,
+        _x: &Scalar,
+        nonces: &mut NonceGen,
+    ) -> (Zeroizing<Scalar>, (ProjectivePoint, ProjectivePoint)) {
+        let j = Zeroizing::new(nonces.next_scalar());
+        let J = mul_base(&statement.G, &j);
+        let J_prime = self.mul_H(statement, &j);
+        (j, (J, J_prime))
+    }
+
+    fn respond(&self, x: &Scalar, j: Zeroizing<Scalar>, e: &Scalar) -> Scalar {
+        Dleq.respond(x, j, e)
+    }
+
+    fn simulate(
+        &self,
+        statement: &DleqStatement,
+        e: &Scalar,
+        nonces: &mut NonceGen,
+    ) -> ((ProjectivePoint, ProjectivePoint), Scalar) {
+        let z = nonces.next_scalar();
+        let J = mul_base(&statement.G, &z) - statement.X * e;
+        let J_prime = self.mul_H(statement, &z) - statement.Y * e;
+        ((J, J_prime), z)
+    }
+
+    fn recompute_commitment(
+        &self,
+        statement: &DleqStatement,
+        e: &Scalar,
+        z: &Scalar,
+    ) -> Option<(ProjectivePoint, ProjectivePoint)> {
+        Dleq.recompute_commitment(statement, e, z)
+    }
+}
+
+/// `And` proves both of its sub-statements with a single challenge shared by the two
+/// sub-protocols. Statements, witnesses and responses are pairs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct And<A, B>(pub A, pub B);
+
+impl<A: SigmaProtocol, B: SigmaProtocol> SigmaProtocol for And<A, B> {
+    type Statement = (A::Statement, B::Statement);
+    type Witness = (A::Witness, B::Witness);
+    type Commitment = (A::Commitment, B::Commitment);
+    type Response = (A::Response, B::Response);
+    type State = (A::State, B::State);
+
+    fn holds(&self, statement: &Self::Statement, witness: &Self::Witness) -> bool {
+        self.0.holds(&statement.0, &witness.0) && self.1.holds(&statement.1, &witness.1)
+    }
+
+    fn absorb_statement(&self, statement: &Self::Statement, transcript: &mut Transcript) {
+        self.0.absorb_statement(&statement.0, transcript);
+        self.1.absorb_statement(&statement.1, transcript);
+    }
+
+    fn absorb_commitment(&self, commitment: &Self::Commitment, transcript: &mut Transcript) {
+        self.0.absorb_commitment(&commitment.0, transcript);
+        self.1.absorb_commitment(&commitment.1, transcript);
+    }
+
+    fn encode_witness(&self, witness: &Self::Witness, out: &mut Vec<u8>) {
+        self.0.encode_witness(&witness.0, out);
+        self.1.encode_witness(&witness.1, out);
+    }
+
+    fn commit(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        nonces: &mut NonceGen,
+    ) -> (Self::State, Self::Commitment) {
+        let (state_a, commitment_a) = self.0.commit(&statement.0, &witness.0, nonces);
+        let (state_b, commitment_b) = self.1.commit(&statement.1, &witness.1, nonces);
+        ((state_a, state_b), (commitment_a, commitment_b))
+    }
+
+    fn respond(&self, witness: &Self::Witness, state: Self::State, e: &Scalar) -> Self::Response {
+        (
+            self.0.respond(&witness.0, state.0, e),
+            self.1.respond(&witness.1, state.1, e),
+        )
+    }
+
+    fn simulate(
+        &self,
+        statement: &Self::Statement,
+        e: &Scalar,
+        nonces: &mut NonceGen,
+    ) -> (Self::Commitment, Self::Response) {
+        let (commitment_a, response_a) = self.0.simulate(&statement.0, e, nonces);
+        let (commitment_b, response_b) = self.1.simulate(&statement.1, e, nonces);
+        ((commitment_a, commitment_b), (response_a, response_b))
+    }
+
+    fn recompute_commitment(
+        &self,
+        statement: &Self::Statement,
+        e: &Scalar,
+        response: &Self::Response,
+    ) -> Option<Self::Commitment> {
+        Some((
+            self.0.recompute_commitment(&statement.0, e, &response.0)?,
+            self.1.recompute_commitment(&statement.1, e, &response.1)?,
+        ))
+    }
+}
+
+/// Witness of an [`Or`] proof: a witness for the statement at `index`.
+#[derive(Debug, Clone)]
+pub struct OrWitness<W> {
+    pub index: usize,
+    pub witness: W,
+}
+
+/// Response of an [`Or`] proof: one challenge and one response per branch. The challenges sum
+/// to the challenge of the proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrResponse<Z> {
+    pub e: Vec<Scalar>,
+    pub z: Vec<Z>,
+}
+
+/// Prover state of an [`Or`] proof: the state of the true branch and the simulated
+/// challenges and responses of the others.
+pub struct OrState<T, Z> {
+    index: usize,
+    state: T,
+    e: Vec<Scalar>,
+    z: Vec<Option<Z>>,
+}
+
+/// `Or` proves that the prover knows a witness for one of several statements of the same
+/// protocol without revealing which (Cramer–Damgård–Schoenmakers). The other branches are
+/// simulated with challenges the prover picks, and the challenge of the true branch is fixed
+/// so that all of them sum to the Fiat–Shamir challenge.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Or<S>(pub S);
+
+impl<S: SigmaProtocol> SigmaProtocol for Or<S> {
+    type Statement = Vec<S::Statement>;
+    type Witness = OrWitness<S::Witness>;
+    type Commitment = Vec<S::Commitment>;
+    type Response = OrResponse<S::Response>;
+    type State = OrState<S::State, S::Response>;
+
+    fn holds(&self, statement: &Self::Statement, witness: &Self::Witness) -> bool {
+        statement
+            .get(witness.index)
+            .is_some_and(|statement| self.0.holds(statement, &witness.witness))
+    }
+
+    fn absorb_statement(&self, statement: &Self::Statement, transcript: &mut Transcript) {
+        transcript.append_message("branches", &(statement.len() as u64).to_be_bytes());
+        for branch in statement {
+            self.0.absorb_statement(branch, transcript);
+        }
+    }
+
+    fn absorb_commitment(&self, commitment: &Self::Commitment, transcript: &mut Transcript) {
+        for branch in commitment {
+            self.0.absorb_commitment(branch, transcript);
+        }
+    }
+
+    fn encode_witness(&self, witness: &Self::Witness, out: &mut Vec<u8>) {
+        out.extend_from_slice(&(witness.index as u64).to_be_bytes());
+        self.0.encode_witness(&witness.witness, out);
+    }
+
+    fn commit(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        nonces: &mut NonceGen,
+    ) -> (Self::State, Self::Commitment) {
+        let mut e = Vec::with_capacity(statement.len());
+        let mut z = Vec::with_capacity(statement.len());
+        let mut commitments = Vec::with_capacity(statement.len());
+        let mut state = None;
+        for (i, branch) in statement.iter().enumerate() {
+            if i == witness.index {
+                let (branch_state, commitment) = self.0.commit(branch, &witness.witness, nonces);
+                state = Some(branch_state);
+                e.push(Scalar::ZERO); // set in respond
+                z.push(None);
+                commitments.push(commitment);
+            } else {
+                let e_i = nonces.next_scalar();
+                let (commitment, z_i) = self.0.simulate(branch, &e_i, nonces);
+                e.push(e_i);
+                z.push(Some(z_i));
+                commitments.push(commitment);
+            }
+        }
+        let state = OrState {
+            index: witness.index,
+            state: state.expect("prove checks that the witness index is in range"),
+            e,
+            z,
+        };
+        (state, commitments)
+    }
+
+    fn respond(&self, witness: &Self::Witness, state: Self::State, e: &Scalar) -> Self::Response {
+        let OrState {
+            index,
+            state,
+            e: mut challenges,
+            z: mut responses,
+        } = state;
+        // e_index = e - sum of the simulated challenges
+        let e_index = challenges.iter().fold(*e, |acc, e_i| acc - e_i);
+        challenges[index] = e_index;
+        responses[index] = Some(self.0.respond(&witness.witness, state, &e_index));
+        OrResponse {
+            e: challenges,
+            z: responses.into_iter().flatten().collect(),
+        }
+    }
+
+    fn simulate(
+        &self,
+        statement: &Self::Statement,
+        e: &Scalar,
+        nonces: &mut NonceGen,
+    ) -> (Self::Commitment, Self::Response) {
+        let mut challenges = Vec::with_capacity(statement.len());
+        let mut responses = Vec::with_capacity(statement.len());
+        let mut commitments = Vec::with_capacity(statement.len());
+        let mut remaining = *e;
+        for (i, branch) in statement.iter().enumerate() {
+            let e_i = if i + 1 == statement.len() {
+                remaining
+            } else {
+                nonces.next_scalar()
+            };
+            remaining -= e_i;
+            let (commitment, z_i) = self.0.simulate(branch, &e_i, nonces);
+            challenges.push(e_i);
+            responses.push(z_i);
+            commitments.push(commitment);
+        }
+        (
+            commitments,
+            OrResponse {
+                e: challenges,
+                z: responses,
+            },
+        )
+    }
+
+    fn recompute_commitment(
+        &self,
+        statement: &Self::Statement,
+        e: &Scalar,
+        response: &Self::Response,
+    ) -> Option<Self::Commitment> {
+        if response.e.len() != statement.len() || response.z.len() != statement.len() {
+            return None;
+        }
+        if response.e.iter().fold(Scalar::ZERO, |acc, e_i| acc + e_i) != *e {
+            return None;
+        }
+        statement
+            .iter()
+            .zip(response.e.iter().zip(&response.z))
+            .map(|(branch, (e_i, z_i))| self.0.recompute_commitment(branch, e_i, z_i))
+            .collect()
+    }
+}
+
+/// Proves knowledge of `witness` for `statement` without checking that it holds, for callers
+/// that have just computed the statement from the witness, see [`SigmaProtocol::prove`].
+pub(crate) fn prove_unchecked<S: SigmaProtocol + ?Sized>(
+    protocol: &S,
+    transcript: &Transcript,
+    statement: &S::Statement,
+    witness: &S::Witness,
+    aux_rand: Option<&[u8; 32]>,
+) -> Proof<S::Response> {
+    prove_with_commitment(protocol, transcript, statement, witness, aux_rand).1
+}
+
+/// Proves like [`prove_unchecked`] and also returns the commitment, for proofs sent in
+/// commitment form.
+pub(crate) fn prove_with_commitment<S: SigmaProtocol + ?Sized>(
+    protocol: &S,
+    transcript: &Transcript,
+    statement: &S::Statement,
+    witness: &S::Witness,
+    aux_rand: Option<&[u8; 32]>,
+) -> (S::Commitment, Proof<S::Response>) {
+    let mut secret = Zeroizing::new(Vec::new());
+    protocol.encode_witness(witness, &mut secret);
+    let mut nonce_transcript = transcript.clone();
+    protocol.absorb_statement(statement, &mut nonce_transcript);
+    let mut nonces = NonceGen {
+        key: Zeroizing::new(<Scalar as Reduce<U256>>::reduce_bytes(&Sha256::digest(
+            &*secret,
+        ))),
+        transcript: nonce_transcript.challenge_bytes("nonce"),
+        aux_rand,
+        counter: 0,
+    };
+
+    let (state, commitment) = protocol.commit(statement, witness, &mut nonces);
+    let e = protocol.challenge(transcript, statement, &commitment);
+    let z = protocol.respond(witness, state, &e);
+    (commitment, Proof { e, z })
+}
+
+/// Builds the [`Or`] statements showing that `T` is one of the oracle outcome points
+/// `outcomes`, without revealing which. Branch `i` is $T - T_i = rG$, so the prover proves
+/// `T` with `OrWitness { index: i, witness: r }` where $T = T_i + rG$.
+///
+/// `r` must be random and non-zero: with $T = T_i$ the branch of the true outcome would be
+/// the identity and give it away.
+///
+/// # Arguments
+/// * `T` - Statement to prove, blinded as $T_i + rG$
+/// * `outcomes` - Points $T_i$ of the possible outcomes
+///
+/// # Returns
+/// * `Vec<DLogStatement>` - One [`DLog`] statement per outcome, in the same order
+pub fn outcome_statements(T: &ProjectivePoint, outcomes: &[ProjectivePoint]) -> Vec<DLogStatement> {
+    outcomes
+        .iter()
+        .map(|T_i| DLogStatement {
+            G: ProjectivePoint::GENERATOR,
+            X: *T - T_i,
+        })
+        .collect()
+}
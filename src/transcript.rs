@@ -0,0 +1,71 @@
+use k256::{elliptic_curve::ops::Reduce, ProjectivePoint, Scalar, U256};
+use sha2::{Digest, Sha256};
+
+use crate::utils::{encode_point, encode_scalar, tagged_hash};
+
+/// `Transcript` is the labelled Fiat–Shamir transcript every proof challenge is derived from.
+///
+/// It is a tagged SHA-256 over length-framed `(label, message)` pairs: every label and message
+/// is prefixed with its length as 8 big-endian bytes, so two different sequences of appends
+/// never hash the same. A transcript starts with a mandatory domain label naming the proof and
+/// an optional session context, such as a swap identifier or the pre-signature a proof is
+/// attached to, so that a proof made in one context does not verify in another.
+#[derive(Clone)]
+pub struct Transcript {
+    hasher: Sha256,
+}
+
+impl Transcript {
+    /// Starts a transcript for the proof named `label`, bound to `context` if given. An
+    /// empty context and no context give different transcripts.
+    ///
+    /// # Arguments
+    /// * `label` - Domain separation label of the proof (e.g. `"as_for_fde/PoK"`)
+    /// * `context` - Optional session context
+    ///
+    /// # Returns
+    /// * `Transcript` - The new transcript
+    pub fn new(label: &'static str, context: Option<&[u8]>) -> Self {
+        let mut transcript = Self {
+            hasher: tagged_hash("as_for_fde/transcript"),
+        };
+        transcript.append_message("domain", label.as_bytes());
+        if let Some(context) = context {
+            transcript.append_message("context", context);
+        }
+        transcript
+    }
+
+    /// Appends `message` under `label`.
+    pub fn append_message(&mut self, label: &'static str, message: &[u8]) {
+        self.hasher.update((label.len() as u64).to_be_bytes()); // add label length
+        self.hasher.update(label.as_bytes()); // add label
+        self.hasher.update((message.len() as u64).to_be_bytes()); // add message length
+        self.hasher.update(message); // add message
+    }
+
+    /// Appends the compressed encoding of `W` under `label`.
+    pub fn append_point(&mut self, label: &'static str, W: &ProjectivePoint) {
+        self.append_message(label, &encode_point(W));
+    }
+
+    /// Appends the big-endian encoding of `s` under `label`.
+    pub fn append_scalar(&mut self, label: &'static str, s: &Scalar) {
+        self.append_message(label, &encode_scalar(s));
+    }
+
+    /// Derives 32 bytes under `label` from everything appended so far, then appends them, so
+    /// that later outputs depend on earlier ones.
+    pub fn challenge_bytes(&mut self, label: &'static str) -> [u8; 32] {
+        self.append_message(label, &[]);
+        let output: [u8; 32] = self.hasher.clone().finalize().into();
+        self.append_message(label, &output);
+        output
+    }
+
+    /// Derives a challenge scalar under `label`, see [`Transcript::challenge_bytes`].
+    pub fn challenge_scalar(&mut self, label: &'static str) -> Scalar {
+        let bytes = self.challenge_bytes(label);
+        <Scalar as Reduce<U256>>::reduce_bytes(&bytes.into())
+    }
+}
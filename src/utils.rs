@@ -1,26 +1,33 @@
 use k256::{
-    elliptic_curve::{point::AffineCoordinates, PrimeField},
-    ProjectivePoint, Scalar,
+    elliptic_curve::{
+        ops::Reduce,
+        point::AffineCoordinates,
+        sec1::{FromEncodedPoint, ToEncodedPoint},
+        Curve, FieldBytesEncoding, PrimeField,
+    },
+    AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar, Secp256k1, U256,
 };
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::Error;
 
 /**
- * Extracts the x-coordinate from a projective point and converts it to a scalar.
+ * Extracts the x-coordinate from a projective point and reduces it modulo the group order,
+ * as ECDSA does to compute `r`.
  *
  * # Arguments
  * * `W` - ProjectivePoint to extract x-coordinate from.
  *
  * # Returns
- * * `Scalar` - The x-coordinate as a scalar, or `Scalar::ZERO` if invalid.
+ * * `Result<Scalar, Error>` - The x-coordinate modulo the group order, or
+ *   `Error::InvalidPoint` if `W` is the identity.
  */
-pub fn get_x(W: &ProjectivePoint) -> Scalar {
-    let w_x = match Scalar::from_repr(W.to_affine().x()).into_option() {
-        Some(s) => s,
-        None => {
-            eprintln!("Invalid x-coordinate of R': cannot convert to Scalar.");
-            return Scalar::ZERO;
-        }
-    };
-    return w_x;
+pub fn get_x(W: &ProjectivePoint) -> Result<Scalar, Error> {
+    if *W == ProjectivePoint::IDENTITY {
+        return Err(Error::InvalidPoint);
+    }
+    Ok(<Scalar as Reduce<U256>>::reduce_bytes(&W.to_affine().x()))
 }
 
 /**
@@ -30,15 +37,170 @@ pub fn get_x(W: &ProjectivePoint) -> Scalar {
  * * `s` - The scalar to invert.
  *
  * # Returns
- * * `Scalar` - The inverse of the scalar, or `Scalar::ZERO` if not invertible.
+ * * `Result<Scalar, Error>` - The inverse of the scalar, or `Error::NonInvertible` if it is zero.
+ */
+pub fn invert_scalar(s: &Scalar) -> Result<Scalar, Error> {
+    s.invert().into_option().ok_or(Error::NonInvertible)
+}
+
+/// Length of a compressed SEC1 point encoding.
+pub const POINT_LEN: usize = 33;
+/// Length of a big-endian scalar encoding.
+pub const SCALAR_LEN: usize = 32;
+
+/**
+ * Serializes a point in compressed SEC1 form. The identity has no compressed encoding and is
+ * written as 33 zero bytes, which `decode_point` rejects.
+ *
+ * # Arguments
+ * * `W` - Point to encode.
+ *
+ * # Returns
+ * * `[u8; 33]` - Compressed encoding `0x02/0x03 || x(W)`.
+ */
+pub fn encode_point(W: &ProjectivePoint) -> [u8; POINT_LEN] {
+    let mut bytes = [0u8; POINT_LEN];
+    if *W != ProjectivePoint::IDENTITY {
+        bytes.copy_from_slice(W.to_affine().to_encoded_point(true).as_bytes());
+    }
+    bytes
+}
+
+/**
+ * Parses a compressed SEC1 point, rejecting wrong lengths, uncompressed or identity
+ * encodings and x-coordinates that are not on the curve.
+ *
+ * # Arguments
+ * * `bytes` - Exactly 33 bytes `0x02/0x03 || x`.
+ *
+ * # Returns
+ * * `Result<ProjectivePoint, Error>` - The decoded point, or `Error::InvalidLength` /
+ *   `Error::InvalidPoint`.
+ */
+pub fn decode_point(bytes: &[u8]) -> Result<ProjectivePoint, Error> {
+    if bytes.len() != POINT_LEN {
+        return Err(Error::InvalidLength);
+    }
+    if bytes[0] != 0x02 && bytes[0] != 0x03 {
+        return Err(Error::InvalidPoint);
+    }
+    let encoded = EncodedPoint::from_bytes(bytes).map_err(|_| Error::InvalidPoint)?;
+    AffinePoint::from_encoded_point(&encoded)
+        .into_option()
+        .map(ProjectivePoint::from)
+        .ok_or(Error::InvalidPoint)
+}
+
+/**
+ * Serializes a scalar as 32 big-endian bytes.
+ *
+ * # Arguments
+ * * `s` - Scalar to encode.
+ *
+ * # Returns
+ * * `[u8; 32]` - Big-endian encoding of `s`.
  */
-pub fn invert_scalar(s: &Scalar) -> Scalar {
-    let s_inv = match s.invert().into_option() {
-        Some(inv) => inv,
-        None => {
-            eprintln!("s' is not invertible (possibly zero).");
-            return Scalar::ZERO;
+pub fn encode_scalar(s: &Scalar) -> [u8; SCALAR_LEN] {
+    s.to_bytes().into()
+}
+
+/**
+ * Parses a 32-byte big-endian scalar, rejecting values that are not below the group order.
+ *
+ * # Arguments
+ * * `bytes` - Exactly 32 bytes.
+ *
+ * # Returns
+ * * `Result<Scalar, Error>` - The decoded scalar, or `Error::InvalidLength` /
+ *   `Error::InvalidScalar`.
+ */
+pub fn decode_scalar(bytes: &[u8]) -> Result<Scalar, Error> {
+    if bytes.len() != SCALAR_LEN {
+        return Err(Error::InvalidLength);
+    }
+    Scalar::from_repr(*FieldBytes::from_slice(bytes))
+        .into_option()
+        .ok_or(Error::InvalidScalar)
+}
+
+/**
+ * Creates a SHA-256 hasher pre-loaded with a BIP-340 style tag prefix,
+ * i.e. `SHA256(tag) || SHA256(tag)`.
+ *
+ * # Arguments
+ * * `tag` - Domain separation tag (e.g. `"BIP0340/challenge"`).
+ *
+ * # Returns
+ * * `Sha256` - Hasher ready to absorb the tagged data.
+ */
+pub fn tagged_hash(tag: &str) -> Sha256 {
+    let tag_hash = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    hasher
+}
+
+/**
+ * Derives a nonce deterministically from a secret scalar and a message, following RFC 6979
+ * with HMAC-SHA256. The domain, the label, the optional statement `T` and the optional
+ * auxiliary randomness are passed as RFC 6979 additional data, so that different schemes and
+ * operations on the same key and message never share a nonce. Each optional field is preceded
+ * by a byte telling whether it is present, so no two combinations of them give the same
+ * additional data. Supplying `aux_rand` gives a hedged nonce.
+ *
+ * The message is hashed with SHA-256 whatever the ciphersuite of the scheme. The hash only
+ * binds the nonce to `m`; nonces of the same key and message under two ciphersuites are kept
+ * apart by the ciphersuite identifier, which the schemes include in `domain`.
+ *
+ * # Arguments
+ * * `x` - Secret scalar (signing key or proof witness).
+ * * `m` - Message or public data the nonce is bound to.
+ * * `domain` - Domain separation tags of the scheme (e.g. `["as_for_fde/Schnorr"]`).
+ * * `label` - Domain separation label of the operation (e.g. `"pre_sign"`).
+ * * `T` - Optional statement point the nonce is bound to.
+ * * `aux_rand` - Optional 32 bytes of extra randomness.
+ *
+ * # Returns
+ * * `Scalar` - A non-zero nonce.
+ */
+pub fn derive_nonce(
+    x: &Scalar,
+    m: &[u8],
+    domain: &[&str],
+    label: &str,
+    T: Option<&ProjectivePoint>,
+    aux_rand: Option<&[u8; 32]>,
+) -> Scalar {
+    // fixed to SHA-256 rather than the suite digest, see above
+    let h = <Scalar as Reduce<U256>>::reduce_bytes(&Sha256::digest(m));
+    let mut data = Vec::with_capacity(64 + 1 + label.len() + 1 + POINT_LEN + 1 + 32);
+    for tag in domain {
+        data.push(tag.len() as u8); // add tag length
+        data.extend_from_slice(tag.as_bytes()); // add tag
+    }
+    data.push(label.len() as u8); // add label length
+    data.extend_from_slice(label.as_bytes()); // add label
+    match T {
+        Some(T) => {
+            data.push(1); // T is present
+            data.extend_from_slice(&encode_point(T)); // add T
+        }
+        None => data.push(0), // T is absent
+    }
+    match aux_rand {
+        Some(aux_rand) => {
+            data.push(1); // auxiliary randomness is present
+            data.extend_from_slice(aux_rand); // add auxiliary randomness
         }
-    };
-    return s_inv;
+        None => data.push(0), // auxiliary randomness is absent
+    }
+    let x = Zeroizing::new(x.to_repr());
+    let k = Zeroizing::new(rfc6979::generate_k::<Sha256, _>(
+        &x,
+        &<U256 as FieldBytesEncoding<Secp256k1>>::encode_field_bytes(&Secp256k1::ORDER),
+        &h.to_repr(),
+        &data,
+    ));
+    Scalar::from_repr(*k).unwrap() // generate_k only returns k in [1, n)
 }
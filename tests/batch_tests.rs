@@ -0,0 +1,458 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{
+        AS_scheme, AdaptorStatement, AdaptorWitness, AumayrECDSA, DiscreteLog, Error, PiCommitment,
+        PreSignRequest, PreSignatureItem, ProofItem, PublicKey, Scheme, Schnorr, SecretKey, Sigma,
+        Sigma_prime, Sign_scheme, SignatureItem, VerifyError, BIP340, ECDSA, ZKP,
+    };
+    use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
+    use rand_core::OsRng;
+
+    const BATCH: usize = 16;
+
+    fn messages() -> Vec<Vec<u8>> {
+        (0..BATCH)
+            .map(|i| format!("message {i}").into_bytes())
+            .collect()
+    }
+
+    fn signatures(messages: &[Vec<u8>]) -> (Vec<PublicKey>, Vec<Sigma>) {
+        messages
+            .iter()
+            .map(|m| {
+                let (p, P) = Schnorr::new().keygen(&mut OsRng);
+                (P, Schnorr::new().sign(&p, m, None).unwrap())
+            })
+            .unzip()
+    }
+
+    fn pre_signatures(
+        messages: &[Vec<u8>],
+    ) -> (Vec<PublicKey>, Vec<AdaptorStatement>, Vec<Sigma_prime>) {
+        let mut keys = Vec::new();
+        let mut statements = Vec::new();
+        let mut pre_signatures = Vec::new();
+        for m in messages {
+            let (p, P) = Schnorr::new().keygen(&mut OsRng);
+            let (_, T, _) = Schnorr::new().gen_statement(&mut OsRng, false);
+            pre_signatures.push(Schnorr::new().pre_sign(&p, m, &T, None).unwrap());
+            keys.push(P);
+            statements.push(T);
+        }
+        (keys, statements, pre_signatures)
+    }
+
+    fn signature_items<'a>(
+        messages: &'a [Vec<u8>],
+        keys: &'a [PublicKey],
+        sigmas: &'a [Sigma],
+    ) -> Vec<SignatureItem<'a>> {
+        (0..messages.len())
+            .map(|i| SignatureItem {
+                sigma: &sigmas[i],
+                P: &keys[i],
+                m: &messages[i],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid_signatures_pass() {
+        let messages = messages();
+        let (keys, sigmas) = signatures(&messages);
+        let items = signature_items(&messages, &keys, &sigmas);
+
+        assert_eq!(Schnorr::new().verify_sign_batch(&items, &mut OsRng), Ok(()));
+        assert_eq!(
+            Schnorr::new().verify_sign_batch(&items[..1], &mut OsRng),
+            Ok(())
+        );
+        assert_eq!(Schnorr::new().verify_sign_batch(&[], &mut OsRng), Ok(()));
+    }
+
+    #[test]
+    fn invalid_signatures_are_identified() {
+        let mut messages = messages();
+        let (keys, mut sigmas) = signatures(&messages);
+        sigmas[3].s += Scalar::ONE; // tamper
+        messages[7] = b"another message".to_vec(); // wrong message
+        sigmas[11].R = ProjectivePoint::IDENTITY;
+        messages[12].clear();
+        let items = signature_items(&messages, &keys, &sigmas);
+
+        let expected = vec![
+            (3, VerifyError::EquationMismatch),
+            (7, VerifyError::EquationMismatch),
+            (11, VerifyError::IdentityPoint),
+            (12, VerifyError::EmptyMessage),
+        ];
+        assert_eq!(
+            Schnorr::new().verify_sign_batch(&items, &mut OsRng),
+            Err(expected)
+        );
+
+        // the batch agrees with the single-item path
+        for (i, item) in items.iter().enumerate() {
+            let single = Schnorr::new().verify_sign_detailed(item.sigma, item.P, item.m);
+            let batch = Schnorr::new()
+                .verify_sign_batch(&items[i..i + 1], &mut OsRng)
+                .map_err(|failures| failures[0].1);
+            assert_eq!(batch, single);
+        }
+    }
+
+    #[test]
+    fn valid_pre_signatures_pass() {
+        let messages = messages();
+        let (keys, statements, pre_signatures) = pre_signatures(&messages);
+        let items: Vec<_> = (0..BATCH)
+            .map(|i| PreSignatureItem {
+                P: &keys[i],
+                m: &messages[i][..],
+                T: &statements[i],
+                sigma_prime: &pre_signatures[i],
+            })
+            .collect();
+
+        assert_eq!(
+            Schnorr::new().verify_pre_sign_batch(&items, &mut OsRng),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn invalid_pre_signatures_are_identified() {
+        let messages = messages();
+        let (keys, mut statements, mut pre_signatures) = pre_signatures(&messages);
+        statements[0] = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement(); // wrong T
+        pre_signatures[BATCH - 1].s_prime += Scalar::ONE; // tamper
+        let items: Vec<_> = (0..BATCH)
+            .map(|i| PreSignatureItem {
+                P: &keys[i],
+                m: &messages[i][..],
+                T: &statements[i],
+                sigma_prime: &pre_signatures[i],
+            })
+            .collect();
+
+        assert_eq!(
+            Schnorr::new().verify_pre_sign_batch(&items, &mut OsRng),
+            Err(vec![
+                (0, VerifyError::EquationMismatch),
+                (BATCH - 1, VerifyError::EquationMismatch),
+            ])
+        );
+    }
+
+    fn dleq_pre_signatures<S>(
+        scheme: &S,
+        messages: &[Vec<u8>],
+    ) -> (Vec<PublicKey>, Vec<AdaptorStatement>, Vec<S::PreSignature>)
+    where
+        S: AS_scheme<Relation = DiscreteLog>,
+    {
+        let mut keys = Vec::new();
+        let mut statements = Vec::new();
+        let mut pre_signatures = Vec::new();
+        for m in messages {
+            let (p, P) = scheme.keygen(&mut OsRng);
+            let (_, T, _) = scheme.gen_statement(&mut OsRng, false);
+            pre_signatures.push(scheme.pre_sign(&p, m, &T, None).unwrap());
+            keys.push(P);
+            statements.push(T);
+        }
+        (keys, statements, pre_signatures)
+    }
+
+    fn pre_signature_items<'a, PreSignature>(
+        keys: &'a [PublicKey],
+        messages: &'a [Vec<u8>],
+        statements: &'a [AdaptorStatement],
+        pre_signatures: &'a [PreSignature],
+    ) -> Vec<PreSignatureItem<'a, AdaptorStatement, PreSignature>> {
+        (0..messages.len())
+            .map(|i| PreSignatureItem {
+                P: &keys[i],
+                m: &messages[i],
+                T: &statements[i],
+                sigma_prime: &pre_signatures[i],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ecdsa_pre_signatures_are_checked_with_their_proofs() {
+        let mut messages = messages();
+        let (keys, mut statements, mut pre_signatures) =
+            dleq_pre_signatures(&ECDSA::new(), &messages);
+        let items = pre_signature_items(&keys, &messages, &statements, &pre_signatures);
+        assert_eq!(
+            ECDSA::new().verify_pre_sign_batch(&items, &mut OsRng),
+            Ok(())
+        );
+
+        statements[0] = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement(); // wrong T
+        pre_signatures[3].s_prime += Scalar::ONE; // tamper
+        pre_signatures[5].pi.i += Scalar::ONE; // tamper the proof
+        pre_signatures[8].R_prime = -pre_signatures[8].R_prime; // same x-coordinate
+        pre_signatures[11].Z = ProjectivePoint::IDENTITY;
+        messages[13].clear();
+        let items = pre_signature_items(&keys, &messages, &statements, &pre_signatures);
+        assert_eq!(
+            ECDSA::new().verify_pre_sign_batch(&items, &mut OsRng),
+            Err(vec![
+                (0, VerifyError::EquationMismatch),
+                (3, VerifyError::EquationMismatch),
+                (5, VerifyError::EquationMismatch),
+                (8, VerifyError::EquationMismatch),
+                (11, VerifyError::IdentityPoint),
+                (13, VerifyError::EmptyMessage),
+            ])
+        );
+
+        // the batch accepts exactly the pre-signatures the single-item path accepts
+        for (i, item) in items.iter().enumerate() {
+            let single = ECDSA::new().verify_pre_sign(item.P, item.m, item.T, item.sigma_prime);
+            let batch = ECDSA::new().verify_pre_sign_batch(&items[i..i + 1], &mut OsRng);
+            assert_eq!(batch.is_ok(), single);
+        }
+    }
+
+    #[test]
+    fn aumayr_pre_signatures_are_checked_with_their_proofs() {
+        let messages = messages();
+        let (keys, mut statements, mut pre_signatures) =
+            dleq_pre_signatures(&AumayrECDSA::new(), &messages);
+        statements[2] = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement(); // wrong T
+        pre_signatures[6].K += ProjectivePoint::GENERATOR; // tamper
+        pre_signatures[9].pi.J_prime += ProjectivePoint::GENERATOR; // tamper the proof
+        let items = pre_signature_items(&keys, &messages, &statements, &pre_signatures);
+
+        assert_eq!(
+            AumayrECDSA::new().verify_pre_sign_batch(&items, &mut OsRng),
+            Err(vec![
+                (2, VerifyError::EquationMismatch),
+                (6, VerifyError::EquationMismatch),
+                (9, VerifyError::EquationMismatch),
+            ])
+        );
+        for (i, item) in items.iter().enumerate() {
+            let single =
+                AumayrECDSA::new().verify_pre_sign(item.P, item.m, item.T, item.sigma_prime);
+            let batch = AumayrECDSA::new().verify_pre_sign_batch(&items[i..i + 1], &mut OsRng);
+            assert_eq!(batch.is_ok(), single);
+        }
+        assert_eq!(
+            AumayrECDSA::new().verify_pre_sign_batch(&[], &mut OsRng),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn committed_proofs_match_challenge_proofs() {
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = *p.public_key().as_point();
+        let T = ProjectivePoint::GENERATOR * Scalar::random(&mut OsRng);
+        let Z = T * p.as_scalar();
+        let context: &[u8] = b"session";
+
+        let pi = ECDSA::new()
+            .gen_proof(p.as_scalar(), &Z, &P, &T, Some(context))
+            .unwrap();
+        let committed = ECDSA::new()
+            .gen_proof_committed(p.as_scalar(), &Z, &P, &T, Some(context))
+            .unwrap();
+        assert_eq!(committed.i, pi.i);
+        assert_eq!(
+            ECDSA::new().compute_challenge(
+                &P,
+                &Z,
+                &T,
+                &committed.J,
+                &committed.J_prime,
+                Some(context)
+            ),
+            pi.e
+        );
+        assert!(ECDSA::new().verify_proof_committed(&P, &Z, &T, &committed, Some(context)));
+        assert!(!ECDSA::new().verify_proof_committed(&P, &Z, &T, &committed, None));
+        assert_eq!(
+            PiCommitment::from_bytes(&committed.to_bytes()),
+            Ok(committed)
+        );
+
+        let Q = ProjectivePoint::GENERATOR * Scalar::random(&mut OsRng);
+        assert_eq!(
+            ECDSA::new().gen_proof_committed(p.as_scalar(), &Z, &Q, &T, None),
+            Err(Error::WitnessMismatch)
+        );
+    }
+
+    #[test]
+    fn batched_dleq_proofs_find_invalid_items() {
+        let contexts: Vec<[u8; 1]> = (0..BATCH).map(|i| [i as u8]).collect();
+        let mut statements = Vec::new();
+        let mut proofs = Vec::new();
+        for context in &contexts {
+            let p = SecretKey::from(Scalar::random(&mut OsRng));
+            let P = *p.public_key().as_point();
+            let T = ProjectivePoint::GENERATOR * Scalar::random(&mut OsRng);
+            let Z = T * p.as_scalar();
+            let pi = ECDSA::new().gen_proof_committed(p.as_scalar(), &Z, &P, &T, Some(context));
+            proofs.push(pi.unwrap());
+            statements.push((P, Z, T));
+        }
+        let mut items: Vec<_> = (0..BATCH)
+            .map(|i| ProofItem {
+                P: &statements[i].0,
+                Z: &statements[i].1,
+                T: &statements[i].2,
+                pi: &proofs[i],
+                context: Some(&contexts[i][..]),
+            })
+            .collect();
+        assert_eq!(ECDSA::new().verify_proof_batch(&items, &mut OsRng), Ok(()));
+        assert_eq!(ECDSA::new().verify_proof_batch(&[], &mut OsRng), Ok(()));
+
+        items[5].context = None; // proof made with another context
+        let tampered = PiCommitment {
+            i: proofs[9].i + Scalar::ONE,
+            ..proofs[9]
+        };
+        items[9].pi = &tampered;
+        let other_Z = statements[12].1 + ProjectivePoint::GENERATOR;
+        items[12].Z = &other_Z; // proof of another statement
+        assert_eq!(
+            ECDSA::new().verify_proof_batch(&items, &mut OsRng),
+            Err(vec![
+                (5, VerifyError::InvalidProof),
+                (9, VerifyError::InvalidProof),
+                (12, VerifyError::InvalidProof)
+            ])
+        );
+    }
+
+    /// Statements for a pre-signing batch: one shared by half of the requests, so that the
+    /// ECDSA schemes use a table for it, a few used once and the identity.
+    fn batch_statements() -> Vec<AdaptorStatement> {
+        let shared = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        (0..BATCH)
+            .map(|i| match i {
+                _ if i % 2 == 0 => shared,
+                5 => AdaptorWitness::from(Scalar::ZERO).statement(),
+                _ => AdaptorWitness::from(Scalar::random(&mut OsRng)).statement(),
+            })
+            .collect()
+    }
+
+    fn schemes() -> [Scheme; 4] {
+        [
+            Scheme::Schnorr(Schnorr::new()),
+            Scheme::BIP340(BIP340),
+            Scheme::ECDSA(ECDSA::new()),
+            Scheme::AumayrECDSA(AumayrECDSA::new()),
+        ]
+    }
+
+    /// Encodes a pre-signing result so that results can be compared.
+    fn encoded(
+        scheme: &Scheme,
+        result: Result<<Scheme as AS_scheme>::PreSignature, Error>,
+    ) -> Result<Vec<u8>, Error> {
+        result.and_then(|sigma_prime| scheme.encode_pre_signature(&sigma_prime))
+    }
+
+    #[test]
+    fn pre_sign_batch_matches_pre_sign() {
+        let mut messages = messages();
+        messages[3].clear();
+        let statements = batch_statements();
+        let requests: Vec<_> = (0..BATCH)
+            .map(|i| PreSignRequest {
+                m: &messages[i][..],
+                T: &statements[i],
+            })
+            .collect();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+
+        for scheme in schemes() {
+            for aux_rand in [None, Some(&[7u8; 32])] {
+                let results = scheme.pre_sign_batch(&p, &requests, aux_rand);
+                assert_eq!(results.len(), BATCH);
+                for (request, result) in requests.iter().zip(results) {
+                    let single = scheme.pre_sign(&p, request.m, request.T, aux_rand);
+                    assert_eq!(encoded(&scheme, result), encoded(&scheme, single));
+                }
+            }
+        }
+        assert!(Schnorr::new().pre_sign_batch(&p, &[], None).is_empty());
+    }
+
+    #[test]
+    fn pre_sign_batch_reports_errors_per_request() {
+        let mut messages = messages();
+        messages[3].clear();
+        let statements = batch_statements();
+        let requests: Vec<_> = (0..BATCH)
+            .map(|i| PreSignRequest {
+                m: &messages[i][..],
+                T: &statements[i],
+            })
+            .collect();
+        let (p, P) = ECDSA::new().keygen(&mut OsRng);
+
+        let results = ECDSA::new().pre_sign_batch(&p, &requests, None);
+        for (i, result) in results.iter().enumerate() {
+            match i {
+                3 => assert_eq!(result.as_ref().err(), Some(&Error::EmptyMessage)),
+                5 => assert_eq!(result.as_ref().err(), Some(&Error::InvalidPoint)),
+                _ => {
+                    let sigma_prime = result.as_ref().unwrap();
+                    assert!(ECDSA::new().verify_pre_sign(
+                        &P,
+                        &messages[i],
+                        &statements[i],
+                        sigma_prime
+                    ));
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_pre_sign_batch_matches_sequential() {
+        let messages = messages();
+        let statements = batch_statements();
+        let requests: Vec<_> = (0..BATCH)
+            .map(|i| PreSignRequest {
+                m: &messages[i][..],
+                T: &statements[i],
+            })
+            .collect();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        for scheme in schemes() {
+            let sequential: Vec<_> = scheme
+                .pre_sign_batch(&p, &requests, None)
+                .into_iter()
+                .map(|result| encoded(&scheme, result))
+                .collect();
+            for pool in [None, Some(&pool)] {
+                let parallel: Vec<_> = scheme
+                    .pre_sign_batch_parallel(&p, &requests, None, pool)
+                    .into_iter()
+                    .map(|result| encoded(&scheme, result))
+                    .collect();
+                assert_eq!(parallel, sequential);
+            }
+        }
+    }
+}
@@ -0,0 +1,357 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{
+        AS_scheme, AdaptorStatement, AdaptorWitness, PublicKey, SecretKey, Sign_scheme,
+        VerifyError, BIP340,
+    };
+    use k256::{
+        elliptic_curve::{ff::Field, point::AffineCoordinates, PrimeField},
+        schnorr::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey},
+        FieldBytes, ProjectivePoint, Scalar,
+    };
+    use rand_core::OsRng;
+
+    fn bytes<const N: usize>(s: &str) -> [u8; N] {
+        hex::decode(s).unwrap().try_into().unwrap()
+    }
+
+    fn scalar(s: &str) -> Scalar {
+        Scalar::from_repr(FieldBytes::from(bytes::<32>(s))).unwrap()
+    }
+
+    // Test vectors from https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv
+    // (index, secret key, public key, aux_rand, message, signature)
+    const SIGN_VECTORS: &[(u8, &str, &str, &str, &str, &str)] = &[
+        (
+            0,
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+        ),
+        (
+            1,
+            "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+        ),
+        (
+            2,
+            "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+            "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+            "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+            "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+            "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+        ),
+        (
+            3,
+            "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+            "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3",
+        ),
+        (
+            15,
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "",
+            "71535DB165ECD9FBBC046E5FFAEA61186BB6AD436732FCCC25291A55895464CF6069CE26BF03466228F19A3A62DB8A649F2D560FAC652827D1AF0574E427AB63",
+        ),
+        (
+            16,
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "11",
+            "08A20A0AFEF64124649232E0693C583AB1B9934AE63B4C3511F3AE1134C6A303EA3173BFEA6683BD101FA5AA5DBC1996FE7CACFC5A577D33EC14564CEC2BACBF",
+        ),
+        (
+            17,
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0102030405060708090A0B0C0D0E0F1011",
+            "5130F39A4059B43BC7CAC09A19ECE52B5D8699D1A71E3C52DA9AFDB6B50AC370C4A482B77BF960F8681540E25B6771ECE1E5A37FD80E5A51897C5566A97EA5A5",
+        ),
+    ];
+
+    // (index, public key, message, signature, valid)
+    const VERIFY_VECTORS: &[(u8, &str, &str, &str, bool)] = &[
+        (
+            4,
+            "D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9",
+            "4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703",
+            "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C6376AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4",
+            true,
+        ),
+        (
+            5,
+            "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            false,
+        ),
+        (
+            6,
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+            false,
+        ),
+        (
+            7,
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD",
+            false,
+        ),
+        (
+            8,
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6",
+            false,
+        ),
+        (
+            9,
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "0000000000000000000000000000000000000000000000000000000000000000123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051",
+            false,
+        ),
+        (
+            10,
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "00000000000000000000000000000000000000000000000000000000000000017615FBAF5AE28864013C099742DEADB4DBA87F11AC6754F93780D5A1837CF197",
+            false,
+        ),
+        (
+            11,
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "4A298DACAE57395A15D0795DDBFD1DCB564DA82B0F269BC70A74F8220429BA1D69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            false,
+        ),
+        (
+            12,
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            false,
+        ),
+        (
+            13,
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+            false,
+        ),
+        (
+            14,
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            false,
+        ),
+    ];
+
+    #[test]
+    fn bip340_sign_vectors() {
+        let bip340 = BIP340;
+        for (index, sk, pk, aux, msg, sig) in SIGN_VECTORS {
+            let p = SecretKey::from(scalar(sk));
+            let P = p.public_key();
+            let m = hex::decode(msg).unwrap();
+            assert_eq!(
+                BIP340::xonly(P.as_point()),
+                bytes::<32>(pk),
+                "wrong key for index {index}"
+            );
+
+            let sigma = bip340.sign_raw(&p, &m, &bytes::<32>(aux)).unwrap();
+            assert_eq!(
+                bip340.signature_to_bytes(&sigma),
+                bytes::<64>(sig),
+                "wrong signature for index {index}"
+            );
+            assert!(bip340.verify_raw(&bytes::<32>(pk), &m, &bytes::<64>(sig)));
+        }
+    }
+
+    #[test]
+    fn bip340_verify_vectors() {
+        let bip340 = BIP340;
+        for (index, pk, msg, sig, valid) in VERIFY_VECTORS {
+            let m = hex::decode(msg).unwrap();
+            assert_eq!(
+                bip340.verify_raw(&bytes::<32>(pk), &m, &bytes::<64>(sig)),
+                *valid,
+                "wrong verification result for index {index}"
+            );
+        }
+    }
+
+    #[test]
+    fn sign_works() {
+        let bip340 = BIP340;
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Testing message for bip340";
+
+        let sigma = bip340.sign_with_nonce(&p, message, &k).unwrap();
+        assert!(bip340.verify_sign(&sigma, &P, message));
+        assert!(bip340.verify_sign(&sigma, &PublicKey::from(-*P.as_point()), message));
+        // x-only key
+    }
+
+    #[test]
+    fn signature_is_accepted_by_k256() {
+        let bip340 = BIP340;
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Taproot key-path spend";
+
+        let sigma = bip340.sign_with_nonce(&p, message, &k).unwrap();
+        let vk = VerifyingKey::from_bytes(&BIP340::xonly(P.as_point())).unwrap();
+        let sig = Signature::try_from(bip340.signature_to_bytes(&sigma).as_slice()).unwrap();
+        assert!(vk.verify_prehash(message, &sig).is_ok());
+    }
+
+    #[test]
+    fn signature_fails_when_s_tampered() {
+        let bip340 = BIP340;
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Message";
+
+        let mut sigma = bip340.sign_with_nonce(&p, message, &k).unwrap();
+        sigma.s += Scalar::ONE; // tamper
+
+        assert!(!bip340.verify_sign(&sigma, &P, message));
+    }
+
+    #[test]
+    fn signature_fails_with_odd_R() {
+        let bip340 = BIP340;
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Message";
+
+        let mut sigma = bip340.sign_with_nonce(&p, message, &k).unwrap();
+        sigma.R = -sigma.R; // same x-coordinate, odd Y
+        sigma.s = -sigma.s;
+
+        assert!(!bip340.verify_sign(&sigma, &P, message));
+        assert_eq!(
+            bip340.verify_sign_detailed(&sigma, &P, message),
+            Err(VerifyError::NonCanonicalEncoding)
+        );
+    }
+
+    /// Returns the first nonce `r' = 1, 2, ...` for which `R' = r'G + T` has the requested parity.
+    fn nonce_with_parity(T: &AdaptorStatement, odd: bool) -> Scalar {
+        let mut r_prime = Scalar::ONE;
+        loop {
+            let R_prime = ProjectivePoint::GENERATOR * r_prime + T.as_point();
+            if bool::from(R_prime.to_affine().y_is_odd()) == odd {
+                return r_prime;
+            }
+            r_prime += Scalar::ONE;
+        }
+    }
+
+    fn adaptor_round_trip(odd: bool) {
+        let bip340 = BIP340;
+        let p = SecretKey::from(scalar(
+            "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+        ));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(scalar(
+            "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+        ));
+        let T = t.statement();
+        let r_prime = nonce_with_parity(&T, odd);
+        let message = b"BIP-340 adaptor signature";
+
+        let sigma_prime = bip340
+            .pre_sign_with_nonce(&p, message, &T, &r_prime)
+            .unwrap();
+        assert_eq!(bool::from(sigma_prime.R_prime.to_affine().y_is_odd()), odd);
+        assert!(bip340.verify_pre_sign(&P, message, &T, &sigma_prime));
+
+        let sigma = bip340.adapt_signature(&sigma_prime, &t).unwrap();
+        assert!(bip340.verify_sign(&sigma, &P, message));
+        assert!(bip340.verify_raw(
+            &BIP340::xonly(P.as_point()),
+            message,
+            &bip340.signature_to_bytes(&sigma)
+        ));
+
+        assert_eq!(bip340.extract_witness(&sigma, &sigma_prime, &T), Ok(t));
+    }
+
+    #[test]
+    fn adaptor_works_with_even_R_prime() {
+        adaptor_round_trip(false);
+    }
+
+    #[test]
+    fn adaptor_works_with_odd_R_prime() {
+        adaptor_round_trip(true);
+    }
+
+    #[test]
+    fn pre_sign_fails_with_wrong_T() {
+        let bip340 = BIP340;
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let r_prime = Scalar::random(&mut OsRng);
+        let message = b"Wrong statement";
+
+        let sigma_prime = bip340
+            .pre_sign_with_nonce(&p, message, &T, &r_prime)
+            .unwrap();
+        assert!(!bip340.verify_pre_sign(
+            &P,
+            message,
+            &AdaptorStatement::from(-*T.as_point()),
+            &sigma_prime
+        ));
+        assert!(!bip340.verify_pre_sign(
+            &P,
+            message,
+            &AdaptorStatement::from(*T.as_point() + ProjectivePoint::GENERATOR),
+            &sigma_prime
+        ));
+    }
+
+    #[test]
+    fn prehash_signs_the_digest_as_message() {
+        let bip340 = BIP340;
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let digest = [7u8; 32]; // e.g. a Taproot sighash
+
+        let sigma = bip340.sign_prehash(&p, &digest, None).unwrap();
+        assert!(bip340.verify_sign_prehash(&sigma, &P, &digest));
+
+        let vk = VerifyingKey::from_bytes(&BIP340::xonly(P.as_point())).unwrap();
+        let sig = Signature::try_from(bip340.signature_to_bytes(&sigma).as_slice()).unwrap();
+        assert!(vk.verify_prehash(&digest, &sig).is_ok());
+    }
+}
@@ -0,0 +1,208 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::utils::{decode_point, encode_point, encode_scalar};
+    use as_for_fde::{
+        AS_scheme, AdaptorWitness, AumayrECDSA, Ciphersuite, DiscreteLog, PublicKey, Schnorr,
+        SecretKey, Sha256Suite, Sha512Suite, Sign_scheme, ECDSA,
+    };
+    use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
+    use rand_core::OsRng;
+
+    const MESSAGE: &[u8] = b"as_for_fde";
+
+    fn point(hex: &str) -> ProjectivePoint {
+        decode_point(&hex::decode(hex).unwrap()).unwrap()
+    }
+
+    /// `R = 3G` and `P = 5G`.
+    fn R_and_P() -> (ProjectivePoint, ProjectivePoint) {
+        let R = point("02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9");
+        let P = point("022f8bde4d1a07209355b4a7250a5c5128e88b84bddc619ab7cba8d569b240efe4");
+        assert_eq!(R, ProjectivePoint::GENERATOR * Scalar::from(3u64));
+        assert_eq!(P, ProjectivePoint::GENERATOR * Scalar::from(5u64));
+        (R, P)
+    }
+
+    #[test]
+    fn default_suite_known_answers() {
+        let (R, P) = R_and_P();
+        // SHA-256(R || P || m) over uncompressed points
+        let e = Sha256Suite::challenge(&R, &P, MESSAGE);
+        assert_eq!(
+            hex::encode(encode_scalar(&e)),
+            "f58aa3a940f8e567005247f41b89b2d75a0930e393bd985c95fec0f5311f2067"
+        );
+        assert_eq!(
+            Schnorr::new().hash_challenge(&R, &PublicKey::from(P), MESSAGE),
+            Ok(e)
+        );
+
+        // SHA-256(m)
+        assert_eq!(
+            hex::encode(Sha256Suite::message_digest(MESSAGE)),
+            "5bb611e6b6167fb1526439190677f21ed832c44108a5c19be17fe5c8d0e08ba8"
+        );
+    }
+
+    #[test]
+    fn default_signatures_known_answers() {
+        let p = SecretKey::from(Scalar::from(7u64));
+        let k = Scalar::from(11u64);
+
+        let sigma = Schnorr::new().sign_with_nonce(&p, MESSAGE, &k).unwrap();
+        assert_eq!(
+            hex::encode(encode_point(&sigma.R)),
+            "03774ae7f858a9411e5ef4246b70c65aac5649980be5c17891bbec17895da008cb"
+        );
+        assert_eq!(
+            hex::encode(encode_scalar(&sigma.s)),
+            "b4189039f304dad57f1f84392824d3b0abca6840cc5fc5e3749a7ba2d2edaa3a"
+        );
+
+        let sigma = ECDSA::new().sign_with_nonce(&p, MESSAGE, &k).unwrap();
+        assert_eq!(
+            hex::encode(encode_scalar(&sigma.s)),
+            "f728f255ebe21dc65b301c5deb434fb57b8b73890289db90eee1ebac019cc774"
+        );
+    }
+
+    #[test]
+    fn sha512_suite_known_answers() {
+        let (R, P) = R_and_P();
+        assert_eq!(
+            hex::encode(encode_scalar(&Sha512Suite::challenge(&R, &P, MESSAGE))),
+            "37a21aacdc49fe71d85c1c5c8f1cff82f8ff149b6f64d2088a3db33c963bd46e"
+        );
+        assert_eq!(
+            hex::encode(Sha512Suite::message_digest(MESSAGE)),
+            "a9ff7c82b23f7767a63d4c2d6b461d2b80a67c4458ad5b30eab9d94f39a2ce75"
+        );
+    }
+
+    #[test]
+    fn aumayr_sha512_known_answers() {
+        let aumayr = AumayrECDSA::<DiscreteLog, Sha512Suite>::default();
+        let p = SecretKey::from(Scalar::from(7u64));
+        let t = AdaptorWitness::from(Scalar::from(13u64));
+        let T = t.statement();
+        let k = Scalar::from(11u64);
+
+        // K = kG, R' = kT and s' = k^-1 (H(m) + x(R') p) with the SHA-512 suite digest
+        let sigma_prime = aumayr.pre_sign_with_nonce(&p, MESSAGE, &T, &k).unwrap();
+        assert_eq!(
+            hex::encode(encode_point(&sigma_prime.K)),
+            "03774ae7f858a9411e5ef4246b70c65aac5649980be5c17891bbec17895da008cb"
+        );
+        assert_eq!(
+            hex::encode(encode_point(&sigma_prime.R_prime)),
+            "02a0b1cae06b0a847a3fea6e671aaf8adfdfe58ca2f768105c8082b2e449fce252"
+        );
+        assert_eq!(
+            hex::encode(encode_scalar(&sigma_prime.s_prime)),
+            "5e71180f0e80d385c36c35e8a65ecf63fd9150b609ee49f40fdc9445db938596"
+        );
+        assert!(aumayr.verify_pre_sign(&p.public_key(), MESSAGE, &T, &sigma_prime));
+        assert!(!AumayrECDSA::new().verify_pre_sign(&p.public_key(), MESSAGE, &T, &sigma_prime));
+
+        // s = s' / t, a signature of ECDSA with the same suite
+        let sigma = aumayr.adapt_signature(&sigma_prime, &t).unwrap();
+        assert_eq!(
+            hex::encode(encode_scalar(&sigma.s)),
+            "2ea63ced7744fc9422b98dfe342ead7da66128317e313208461da3cc449d8a78"
+        );
+        assert!(ECDSA::<DiscreteLog, Sha512Suite>::default().verify_sign(
+            &sigma,
+            &p.public_key(),
+            MESSAGE
+        ));
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn keccak_suite_known_answers() {
+        use as_for_fde::Keccak256Suite;
+        assert_eq!(
+            hex::encode(Keccak256Suite::message_digest(b"abc")),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+    }
+
+    #[cfg(feature = "blake2")]
+    #[test]
+    fn blake2s_suite_known_answers() {
+        use as_for_fde::Blake2sSuite;
+        let (R, P) = R_and_P();
+        assert_eq!(
+            hex::encode(encode_scalar(&Blake2sSuite::challenge(&R, &P, MESSAGE))),
+            "b20846b26506bc8a25a2c91b80026b36ca5be817eb458822b5f019076f741595"
+        );
+        assert_eq!(
+            hex::encode(Blake2sSuite::message_digest(MESSAGE)),
+            "62cd1f0020e1d5d95a06c4c218ba7c957f14ba2c79e1f5fed3314970ce741b84"
+        );
+    }
+
+    /// Signs, pre-signs and adapts with `scheme`, and checks that `other`, which uses another
+    /// suite, rejects the results.
+    fn round_trip<S, O>(scheme: &S, other: &O)
+    where
+        S: AS_scheme<Relation = DiscreteLog>,
+        O: AS_scheme<Relation = DiscreteLog, Signature = S::Signature>,
+    {
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+
+        let sigma = scheme.sign(&p, MESSAGE, None).unwrap();
+        assert!(scheme.verify_sign(&sigma, &P, MESSAGE));
+        assert!(!other.verify_sign(&sigma, &P, MESSAGE));
+
+        let sigma_prime = scheme.pre_sign(&p, MESSAGE, &T, None).unwrap();
+        assert!(scheme.verify_pre_sign(&P, MESSAGE, &T, &sigma_prime));
+        let sigma = scheme.adapt_signature(&sigma_prime, &t).unwrap();
+        assert!(scheme.verify_sign(&sigma, &P, MESSAGE));
+        assert_eq!(scheme.extract_witness(&sigma, &sigma_prime, &T), Ok(t));
+    }
+
+    #[test]
+    fn schemes_work_with_another_suite() {
+        round_trip(
+            &Schnorr::<DiscreteLog, Sha512Suite>::default(),
+            &Schnorr::new(),
+        );
+        round_trip(&ECDSA::<DiscreteLog, Sha512Suite>::default(), &ECDSA::new());
+        round_trip(
+            &AumayrECDSA::<DiscreteLog, Sha512Suite>::default(),
+            &AumayrECDSA::new(),
+        );
+    }
+
+    #[test]
+    fn suites_derive_different_nonces() {
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+
+        // the challenges differ, so a shared nonce would leak the key
+        let sha256 = Schnorr::new().sign(&p, MESSAGE, None).unwrap();
+        let sha512 = Schnorr::<DiscreteLog, Sha512Suite>::default()
+            .sign(&p, MESSAGE, None)
+            .unwrap();
+        assert_ne!(sha256.R, sha512.R);
+
+        let sha256 = ECDSA::new().sign(&p, MESSAGE, None).unwrap();
+        let sha512 = ECDSA::<DiscreteLog, Sha512Suite>::default()
+            .sign(&p, MESSAGE, None)
+            .unwrap();
+        assert_ne!(sha256.R, sha512.R);
+
+        let sha256 = AumayrECDSA::new().pre_sign(&p, MESSAGE, &T, None).unwrap();
+        let sha512 = AumayrECDSA::<DiscreteLog, Sha512Suite>::default()
+            .pre_sign(&p, MESSAGE, &T, None)
+            .unwrap();
+        assert_ne!(sha256.R_prime, sha512.R_prime);
+    }
+}
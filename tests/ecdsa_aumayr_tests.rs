@@ -0,0 +1,198 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{
+        utils::derive_nonce, AS_scheme, AdaptorStatement, AdaptorWitness, AumayrECDSA, Error,
+        SecretKey, Sign_scheme, VerifyError, ECDSA,
+    };
+    use k256::{
+        ecdsa::{signature::Verifier, VerifyingKey},
+        elliptic_curve::ff::Field,
+        ProjectivePoint, Scalar,
+    };
+    use rand_core::OsRng;
+
+    #[test]
+    fn pre_sign_works() {
+        let aumayr = AumayrECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Test message for Aumayr pre-sign";
+
+        let sigma_prime = aumayr.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        assert_eq!(sigma_prime.K, ProjectivePoint::GENERATOR * k);
+        assert!(aumayr.verify_pre_sign(&P, message, &T, &sigma_prime));
+    }
+
+    #[test]
+    fn adapt_sign_works() {
+        let aumayr = AumayrECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Adapting signature";
+
+        let sigma_prime = aumayr.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        let sigma = aumayr.adapt_signature(&sigma_prime, &t).unwrap();
+
+        assert!(aumayr.verify_sign(&sigma, &P, message));
+        let signature = ECDSA::new().to_signature(&sigma).unwrap();
+        let vk = VerifyingKey::from_affine(P.as_point().to_affine()).unwrap();
+        assert!(vk.verify(message, &signature).is_ok());
+    }
+
+    #[test]
+    fn witness_extraction_works() {
+        let aumayr = AumayrECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Extract witness test";
+
+        let sigma_prime = aumayr.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        let sigma = aumayr.adapt_signature(&sigma_prime, &t).unwrap();
+
+        assert_eq!(aumayr.extract_witness(&sigma, &sigma_prime, &T), Ok(t));
+    }
+
+    #[test]
+    fn verification_fails_if_K_tampered() {
+        let aumayr = AumayrECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"K test";
+
+        let mut sigma_prime = aumayr.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        sigma_prime.K += ProjectivePoint::GENERATOR; // tamper
+
+        assert!(!aumayr.verify_pre_sign(&P, message, &T, &sigma_prime));
+        assert_eq!(
+            aumayr.verify_pre_sign_detailed(&P, message, &T, &sigma_prime),
+            Err(VerifyError::EquationMismatch)
+        );
+    }
+
+    #[test]
+    fn proof_verification_fails_if_tampered() {
+        let aumayr = AumayrECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"ZK test";
+
+        let mut sigma_prime = aumayr.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        sigma_prime.pi.i += Scalar::ONE; // tamper the proof
+
+        assert!(!aumayr.verify_pre_sign(&P, message, &T, &sigma_prime));
+        assert_eq!(
+            aumayr.verify_pre_sign_detailed(&P, message, &T, &sigma_prime),
+            Err(VerifyError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn verification_fails_on_wrong_statement() {
+        let aumayr = AumayrECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Wrong statement";
+
+        let sigma_prime = aumayr.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        let other_T = AdaptorStatement::from(*T.as_point() + ProjectivePoint::GENERATOR);
+
+        assert!(!aumayr.verify_pre_sign(&P, message, &other_T, &sigma_prime));
+    }
+
+    #[test]
+    fn both_ecdsa_variants_produce_the_same_signature() {
+        let aumayr = AumayrECDSA::new();
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Comparison";
+
+        let sigma_a = aumayr
+            .adapt_signature(
+                &aumayr.pre_sign_with_nonce(&p, message, &T, &k).unwrap(),
+                &t,
+            )
+            .unwrap();
+        let sigma_e = ecdsa
+            .adapt_signature(&ecdsa.pre_sign_with_nonce(&p, message, &T, &k).unwrap(), &t)
+            .unwrap();
+
+        assert_eq!(sigma_a.s, sigma_e.s);
+        assert_eq!(sigma_a.R, sigma_e.R);
+    }
+
+    #[test]
+    fn presign_fails_on_empty_message() {
+        let aumayr = AumayrECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        assert!(matches!(
+            aumayr.pre_sign_with_nonce(&p, b"", &T, &k),
+            Err(Error::EmptyMessage)
+        ));
+    }
+
+    #[test]
+    fn prehash_adaptor_works() {
+        let aumayr = AumayrECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let digest = [7u8; 32];
+
+        let sigma_prime = aumayr.pre_sign_prehash(&p, &digest, &T, None).unwrap();
+        assert!(aumayr.verify_pre_sign_prehash(&P, &digest, &T, &sigma_prime));
+
+        let sigma = aumayr.adapt_signature(&sigma_prime, &t).unwrap();
+        assert!(aumayr.verify_sign_prehash(&sigma, &P, &digest));
+        assert_eq!(aumayr.extract_witness(&sigma, &sigma_prime, &T), Ok(t));
+    }
+
+    #[test]
+    fn prehash_signing_uses_the_aumayr_nonce_domain() {
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let digest = [7u8; 32];
+
+        let sigma = AumayrECDSA::new().sign_prehash(&p, &digest, None).unwrap();
+        assert!(AumayrECDSA::new().verify_sign_prehash(&sigma, &P, &digest));
+        let k = derive_nonce(
+            p.as_scalar(),
+            &digest,
+            AumayrECDSA::new().nonce_domain(),
+            "sign_prehash",
+            None,
+            None,
+        );
+        assert_eq!(ProjectivePoint::GENERATOR * k, sigma.R);
+        assert_ne!(
+            ECDSA::new().sign_prehash(&p, &digest, None).unwrap().R,
+            sigma.R
+        );
+    }
+}
@@ -1,152 +1,483 @@
-#![allow(non_snake_case)]
-#![allow(non_camel_case_types)]
-
-#[cfg(test)]
-mod tests {
-    use as_for_fde::{AS_scheme, Sigma, Sigma_prime, Sign_scheme, ECDSA};
-    use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
-    use rand_core::OsRng;
-
-    #[test]
-    fn sign_works() {
-        let ecdsa: ECDSA = ECDSA;
-        // Keys
-        let p: Scalar = Scalar::random(&mut OsRng); // secret key
-        let P: ProjectivePoint = ProjectivePoint::GENERATOR * &p; // public key
-
-        let t: Scalar = Scalar::random(&mut OsRng); // tweak
-        let _T: ProjectivePoint = ProjectivePoint::GENERATOR * &t; // tweak point
-
-        let k: Scalar = Scalar::random(&mut OsRng); // nonce
-
-        let message: &str = "Testing message for ecdsa"; //our message
-                                                         // Sign
-        let sigma: Sigma = ecdsa.sign(&p, message, &k);
-        assert!(ecdsa.verify_sign(&sigma, &P, message));
-        println!("Signature verified ✅");
-    }
-    #[test]
-    fn signature_fails_when_s_tampered() {
-        let ecdsa = ECDSA;
-        let p = Scalar::random(&mut OsRng);
-        let P = ProjectivePoint::GENERATOR * &p;
-        let k = Scalar::random(&mut OsRng);
-        let message = "Message";
-
-        let mut sigma = ecdsa.sign(&p, message, &k);
-        sigma.s += Scalar::ONE; // tamper
-
-        assert!(!ecdsa.verify_sign(&sigma, &P, message));
-    }
-    #[test]
-    fn signature_fails_when_R_tampered() {
-        let ecdsa = ECDSA;
-        let p = Scalar::random(&mut OsRng);
-        let P = ProjectivePoint::GENERATOR * &p;
-        let k = Scalar::random(&mut OsRng);
-        let message = "Another message";
-
-        let mut sigma = ecdsa.sign(&p, message, &k);
-        sigma.R = sigma.R + ProjectivePoint::GENERATOR; // tamper
-
-        assert!(!ecdsa.verify_sign(&sigma, &P, message));
-    }
-
-    #[test]
-    fn signature_fails_on_wrong_message() {
-        let ecdsa = ECDSA;
-        let p = Scalar::random(&mut OsRng);
-        let P = ProjectivePoint::GENERATOR * &p;
-        let k = Scalar::random(&mut OsRng);
-        let message = "Original";
-        let fake_message = "Tampered";
-
-        let sigma = ecdsa.sign(&p, message, &k);
-        assert!(!ecdsa.verify_sign(&sigma, &P, fake_message));
-    }
-    #[test]
-    fn proof_verification_fails_if_tampered() {
-        let ecdsa = ECDSA;
-        let p = Scalar::random(&mut OsRng);
-        let P = ProjectivePoint::GENERATOR * &p;
-        let t = Scalar::random(&mut OsRng);
-        let T = ProjectivePoint::GENERATOR * &t;
-        let k = Scalar::random(&mut OsRng);
-        let message = "ZK test";
-
-        let mut sigma_prime = ecdsa.pre_sign(&p, message, &T, &k);
-        sigma_prime.pi.e += Scalar::ONE; // tamper the proof
-
-        assert!(!ecdsa.verify_pre_sign(&P, message, &T, &sigma_prime));
-    }
-
-    #[test]
-    fn pre_sign_works() {
-        let ecdsa: ECDSA = ECDSA;
-        // Keys
-        let p: Scalar = Scalar::random(&mut OsRng); // secret key
-        let P: ProjectivePoint = ProjectivePoint::GENERATOR * &p; // public key
-
-        let t: Scalar = Scalar::random(&mut OsRng); // tweak
-        let T: ProjectivePoint = ProjectivePoint::GENERATOR * &t; // tweak point
-
-        let k: Scalar = Scalar::random(&mut OsRng); // nonce
-
-        let message: &str = "Test message for ecdsa pre-sign"; //our message
-                                                               // Pre-sign
-        let sigma_prime: Sigma_prime = ecdsa.pre_sign(&p, message, &T, &k);
-        assert!(ecdsa.verify_pre_sign(&P, message, &T, &sigma_prime,));
-        println!("Pre-signature verified ✅");
-    }
-
-    #[test]
-    fn adapt_sign_works() {
-        let ecdsa = ECDSA;
-        let p = Scalar::random(&mut OsRng);
-        let P = ProjectivePoint::GENERATOR * &p;
-        let t = Scalar::random(&mut OsRng);
-        let T = ProjectivePoint::GENERATOR * &t;
-        let k = Scalar::random(&mut OsRng);
-        let message = "Adapting signature";
-
-        let sigma_prime = ecdsa.pre_sign(&p, message, &T, &k);
-        let sigma = ecdsa.adapt_signature(&sigma_prime, &t);
-
-        assert!(ecdsa.verify_sign(&sigma, &P, message));
-    }
-    #[test]
-    fn witness_extraction_works() {
-        let ecdsa = ECDSA;
-        let p = Scalar::random(&mut OsRng);
-        let t = Scalar::random(&mut OsRng);
-        let T = ProjectivePoint::GENERATOR * &t;
-        let k = Scalar::random(&mut OsRng);
-        let message = "Extract witness test";
-
-        let sigma_prime = ecdsa.pre_sign(&p, message, &T, &k);
-        let sigma = ecdsa.adapt_signature(&sigma_prime, &t);
-        let extracted = ecdsa.extract_witness(&sigma, &sigma_prime);
-
-        assert_eq!(extracted, t);
-    }
-
-    #[test]
-    #[should_panic(expected = "Message cannot be empty.")]
-    fn sign_fails_on_empty_message() {
-        let ecdsa = ECDSA;
-        let p = Scalar::random(&mut OsRng);
-        let k = Scalar::random(&mut OsRng);
-        let _ = ecdsa.sign(&p, "", &k);
-    }
-
-    #[test]
-    #[should_panic(expected = "Message cannot be empty.")]
-    fn presign_fails_on_empty_message() {
-        let ecdsa = ECDSA;
-        let p = Scalar::random(&mut OsRng);
-        let t = Scalar::random(&mut OsRng);
-        let T = ProjectivePoint::GENERATOR * &t;
-        let k = Scalar::random(&mut OsRng);
-        let _ = ecdsa.pre_sign(&p, "", &T, &k);
-    }
-}
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{
+        AS_scheme, AdaptorStatement, AdaptorWitness, AumayrECDSA, Error, PublicKey, SecretKey,
+        Sigma, Sigma_prime_ECDSA, Sign_scheme, VerifyError, ECDSA,
+    };
+    use k256::{
+        ecdsa::{
+            signature::{hazmat::PrehashVerifier, Signer, Verifier},
+            Signature, SigningKey, VerifyingKey,
+        },
+        elliptic_curve::{ff::Field, ops::Reduce, scalar::IsHigh},
+        ProjectivePoint, Scalar, U256,
+    };
+    use rand_core::OsRng;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn sign_works() {
+        let ecdsa = ECDSA::new();
+        // Keys
+        let p = SecretKey::from(Scalar::random(&mut OsRng)); // secret key
+        let P = p.public_key(); // public key
+
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng)); // tweak
+        let _T = t.statement(); // tweak point
+
+        let k: Scalar = Scalar::random(&mut OsRng); // nonce
+
+        let message: &[u8] = b"Testing message for ecdsa"; //our message
+                                                           // Sign
+        let sigma: Sigma = ecdsa.sign_with_nonce(&p, message, &k).unwrap();
+        assert!(ecdsa.verify_sign(&sigma, &P, message));
+        println!("Signature verified ✅");
+    }
+    #[test]
+    fn signature_fails_when_s_tampered() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Message";
+
+        let mut sigma = ecdsa.sign_with_nonce(&p, message, &k).unwrap();
+        sigma.s += Scalar::ONE; // tamper
+
+        assert!(!ecdsa.verify_sign(&sigma, &P, message));
+        assert_eq!(
+            ecdsa.verify_sign_detailed(&sigma, &P, message),
+            Err(VerifyError::EquationMismatch)
+        );
+    }
+    #[test]
+    fn signature_fails_when_R_tampered() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Another message";
+
+        let mut sigma = ecdsa.sign_with_nonce(&p, message, &k).unwrap();
+        sigma.R += ProjectivePoint::GENERATOR; // tamper
+
+        assert!(!ecdsa.verify_sign(&sigma, &P, message));
+    }
+
+    #[test]
+    fn signature_fails_on_wrong_message() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Original";
+        let fake_message = b"Tampered";
+
+        let sigma = ecdsa.sign_with_nonce(&p, message, &k).unwrap();
+        assert!(!ecdsa.verify_sign(&sigma, &P, fake_message));
+    }
+    #[test]
+    fn proof_verification_fails_if_tampered() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"ZK test";
+
+        let mut sigma_prime = ecdsa.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        sigma_prime.pi.i += Scalar::ONE; // tamper the proof
+
+        assert!(!ecdsa.verify_pre_sign(&P, message, &T, &sigma_prime));
+        assert_eq!(
+            ecdsa.verify_pre_sign_detailed(&P, message, &T, &sigma_prime),
+            Err(VerifyError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn proof_is_bound_to_its_pre_signature() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+
+        let first = ecdsa.pre_sign(&p, b"Swap 1", &T, None).unwrap();
+        let mut second = ecdsa.pre_sign(&p, b"Swap 2", &T, None).unwrap();
+        assert!(ecdsa.verify_pre_sign(&P, b"Swap 2", &T, &second));
+
+        // same P, Z and T, but the proof was made for the other pre-signature
+        assert_eq!(first.Z, second.Z);
+        second.pi = first.pi;
+        assert_eq!(
+            ecdsa.verify_pre_sign_detailed(&P, b"Swap 2", &T, &second),
+            Err(VerifyError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn pre_sign_works() {
+        let ecdsa = ECDSA::new();
+        // Keys
+        let p = SecretKey::from(Scalar::random(&mut OsRng)); // secret key
+        let P = p.public_key(); // public key
+
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng)); // tweak
+        let T = t.statement(); // tweak point
+
+        let k: Scalar = Scalar::random(&mut OsRng); // nonce
+
+        let message: &[u8] = b"Test message for ecdsa pre-sign"; //our message
+                                                                 // Pre-sign
+        let sigma_prime: Sigma_prime_ECDSA =
+            ecdsa.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        assert!(ecdsa.verify_pre_sign(&P, message, &T, &sigma_prime,));
+        println!("Pre-signature verified ✅");
+    }
+
+    #[test]
+    fn adapt_sign_works() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Adapting signature";
+
+        let sigma_prime = ecdsa.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        let sigma = ecdsa.adapt_signature(&sigma_prime, &t).unwrap();
+
+        assert!(ecdsa.verify_sign(&sigma, &P, message));
+    }
+    #[test]
+    fn witness_extraction_works() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Extract witness test";
+
+        let sigma_prime = ecdsa.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        let sigma = ecdsa.adapt_signature(&sigma_prime, &t).unwrap();
+        let extracted = ecdsa.extract_witness(&sigma, &sigma_prime, &T).unwrap();
+
+        assert_eq!(extracted, t);
+    }
+
+    #[test]
+    fn sign_fails_on_empty_message() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let k = Scalar::random(&mut OsRng);
+        assert!(matches!(
+            ecdsa.sign_with_nonce(&p, b"", &k),
+            Err(Error::EmptyMessage)
+        ));
+    }
+
+    #[test]
+    fn presign_fails_on_empty_message() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        assert!(matches!(
+            ecdsa.pre_sign_with_nonce(&p, b"", &T, &k),
+            Err(Error::EmptyMessage)
+        ));
+    }
+
+    #[test]
+    fn verification_reports_zero_s() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Zero s";
+
+        let mut sigma = ecdsa.sign_with_nonce(&p, message, &k).unwrap();
+        sigma.s = Scalar::ZERO;
+
+        assert_eq!(
+            ecdsa.verify_sign_detailed(&sigma, &P, message),
+            Err(VerifyError::ZeroScalar)
+        );
+    }
+
+    #[test]
+    fn signatures_are_accepted_by_k256() {
+        let ecdsa = ECDSA::new();
+        for i in 0..64 {
+            let p = SecretKey::from(Scalar::random(&mut OsRng));
+            let P = p.public_key();
+            let k = Scalar::random(&mut OsRng);
+            let message = format!("Differential test message {i}").into_bytes();
+
+            let sigma = ecdsa.sign_with_nonce(&p, &message, &k).unwrap();
+            let signature = ecdsa.to_signature(&sigma).unwrap();
+            assert!(!bool::from(signature.s().is_high()));
+
+            let vk = VerifyingKey::from_affine(P.as_point().to_affine()).unwrap();
+            assert!(vk.verify(&message, &signature).is_ok());
+            assert!(ecdsa.verify_standard(&signature, &P, &message));
+        }
+    }
+
+    #[test]
+    fn adapted_signatures_are_accepted_by_k256() {
+        let ecdsa = ECDSA::new();
+        for i in 0..64 {
+            let p = SecretKey::from(Scalar::random(&mut OsRng));
+            let P = p.public_key();
+            let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+            let T = t.statement();
+            let k = Scalar::random(&mut OsRng);
+            let message = format!("Differential adaptor message {i}").into_bytes();
+
+            let sigma_prime = ecdsa.pre_sign_with_nonce(&p, &message, &T, &k).unwrap();
+            let sigma = ecdsa.adapt_signature(&sigma_prime, &t).unwrap();
+            let signature = ecdsa.to_signature(&sigma).unwrap();
+
+            let vk = VerifyingKey::from_affine(P.as_point().to_affine()).unwrap();
+            assert!(vk.verify(&message, &signature).is_ok());
+        }
+    }
+
+    /// Returns a point `R` whose x-coordinate is not below the group order `n`, which about
+    /// one point in 2^128 has, and its `r = x(R) - n`.
+    fn point_with_x_above_order() -> (ProjectivePoint, Scalar) {
+        let mut encoding = [0u8; 33];
+        encoding[0] = 0x02;
+        encoding[1..].copy_from_slice(
+            &hex::decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141")
+                .unwrap(),
+        );
+        for i in 1..=64u8 {
+            encoding[32] += 1;
+            if let Ok(R) = PublicKey::from_bytes(&encoding) {
+                return (*R.as_point(), Scalar::from(u64::from(i)));
+            }
+        }
+        unreachable!("half of the x-coordinates are on the curve");
+    }
+
+    #[test]
+    fn reduced_x_signatures_agree_with_k256() {
+        let ecdsa = ECDSA::new();
+        let (R, r) = point_with_x_above_order();
+        let message: &[u8] = b"Nonce with x above the order";
+        let e = <Scalar as Reduce<U256>>::reduce_bytes(&Sha256::digest(message));
+
+        // P = r⁻¹(sR - eG) makes (s, R) a valid signature of the message
+        let s = Scalar::random(&mut OsRng);
+        let P = PublicKey::from((R * s - ProjectivePoint::GENERATOR * e) * r.invert().unwrap());
+        let sigma = Sigma { s, R };
+        assert_eq!(ecdsa.verify_sign_detailed(&sigma, &P, message), Ok(()));
+
+        let signature = ecdsa.to_signature(&sigma).unwrap();
+        assert_eq!(*signature.r(), r);
+        let vk = VerifyingKey::from_affine(P.as_point().to_affine()).unwrap();
+        assert!(vk.verify(message, &signature).is_ok());
+        assert!(ecdsa.verify_standard(&signature, &P, message));
+    }
+
+    #[test]
+    fn reduced_x_pre_signatures_verify() {
+        let (R_prime, r) = point_with_x_above_order();
+        let message: &[u8] = b"Nonce with x above the order";
+        let e = <Scalar as Reduce<U256>>::reduce_bytes(&Sha256::digest(message));
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+
+        // with T = s'(e + rp)⁻¹R' and k = (e + rp)s'⁻¹, the nonce point kT is R'
+        let s_prime = Scalar::random(&mut OsRng);
+        let e_rp = e + r * p.as_scalar();
+        let T = AdaptorStatement::from(R_prime * (s_prime * e_rp.invert().unwrap()));
+        let k = e_rp * s_prime.invert().unwrap();
+
+        let sigma_prime = ECDSA::new()
+            .pre_sign_with_nonce(&p, message, &T, &k)
+            .unwrap();
+        assert_eq!(sigma_prime.R_prime, R_prime);
+        assert_eq!(
+            ECDSA::new().verify_pre_sign_detailed(&P, message, &T, &sigma_prime),
+            Ok(())
+        );
+
+        let sigma_prime = AumayrECDSA::new()
+            .pre_sign_with_nonce(&p, message, &T, &k)
+            .unwrap();
+        assert_eq!(sigma_prime.R_prime, R_prime);
+        assert_eq!(
+            AumayrECDSA::new().verify_pre_sign_detailed(&P, message, &T, &sigma_prime),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn normalize_s_keeps_signature_valid() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Low-S";
+
+        let sigma = ecdsa.sign_with_nonce(&p, message, &k).unwrap();
+        let negated = Sigma {
+            s: -sigma.s,
+            R: -sigma.R,
+        };
+        let normalized = ecdsa.normalize_s(&negated);
+
+        assert!(!bool::from(normalized.s.is_high()));
+        assert!(ecdsa.verify_sign(&normalized, &P, message));
+    }
+
+    #[test]
+    fn verify_standard_accepts_k256_signatures() {
+        let ecdsa = ECDSA::new();
+        let sk = SigningKey::random(&mut OsRng);
+        let P = PublicKey::from(ProjectivePoint::from(*sk.verifying_key().as_affine()));
+        let message = b"Signed by k256";
+
+        let signature: Signature = sk.sign(message);
+        assert!(ecdsa.verify_standard(&signature, &P, message));
+        assert!(!ecdsa.verify_standard(&signature, &P, b"Another message"));
+    }
+
+    #[test]
+    fn witness_extraction_works_with_negated_s() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Malleated signature";
+
+        let sigma_prime = ecdsa.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        let sigma = ecdsa.adapt_signature(&sigma_prime, &t).unwrap();
+        let malleated = Sigma {
+            s: -sigma.s,
+            R: -sigma.R,
+        };
+
+        assert_eq!(
+            ecdsa.extract_witness(&malleated, &sigma_prime, &T),
+            Ok(t.clone())
+        );
+        let normalized = ecdsa.normalize_s(&sigma);
+        assert_eq!(ecdsa.extract_witness(&normalized, &sigma_prime, &T), Ok(t));
+    }
+
+    #[test]
+    fn witness_extraction_fails_on_wrong_statement() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Wrong statement";
+
+        let sigma_prime = ecdsa.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        let sigma = ecdsa.adapt_signature(&sigma_prime, &t).unwrap();
+        let other_T = AdaptorStatement::from(*T.as_point() + ProjectivePoint::GENERATOR);
+
+        assert_eq!(
+            ecdsa.extract_witness(&sigma, &sigma_prime, &other_T),
+            Err(Error::WitnessMismatch)
+        );
+    }
+
+    #[test]
+    fn deterministic_sign_is_reproducible() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let message = b"Deterministic nonce";
+
+        let sigma_1 = ecdsa.sign(&p, message, None).unwrap();
+        let sigma_2 = ecdsa.sign(&p, message, None).unwrap();
+        assert_eq!(sigma_1.R, sigma_2.R);
+        assert_eq!(sigma_1.s, sigma_2.s);
+        assert!(ecdsa.verify_sign(&sigma_1, &P, message));
+
+        let sigma_3 = ecdsa.sign(&p, b"Another message", None).unwrap();
+        assert_ne!(sigma_1.R, sigma_3.R);
+    }
+
+    #[test]
+    fn hedged_pre_sign_works() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let message = b"Hedged nonce";
+
+        let sigma_prime_1 = ecdsa.pre_sign(&p, message, &T, Some(&[1u8; 32])).unwrap();
+        let sigma_prime_2 = ecdsa.pre_sign(&p, message, &T, Some(&[2u8; 32])).unwrap();
+        assert_ne!(sigma_prime_1.R_prime, sigma_prime_2.R_prime);
+        assert!(ecdsa.verify_pre_sign(&P, message, &T, &sigma_prime_1));
+
+        let sigma = ecdsa.adapt_signature(&sigma_prime_1, &t).unwrap();
+        assert!(ecdsa.verify_sign(&sigma, &P, message));
+    }
+
+    #[test]
+    fn pre_sign_nonce_depends_on_statement() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let other_T = AdaptorStatement::from(*T.as_point() + ProjectivePoint::GENERATOR);
+        let message = b"Same message";
+
+        let sigma_prime_1 = ecdsa.pre_sign(&p, message, &T, None).unwrap();
+        let sigma_prime_2 = ecdsa.pre_sign(&p, message, &other_T, None).unwrap();
+        assert_ne!(sigma_prime_1.s_prime, sigma_prime_2.s_prime);
+    }
+
+    #[test]
+    fn prehash_signatures_match_message_signatures() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let message = b"Prehashed message";
+        let digest: [u8; 32] = Sha256::digest(message).into();
+
+        let sigma = ecdsa.sign_prehash(&p, &digest, None).unwrap();
+        assert!(ecdsa.verify_sign_prehash(&sigma, &P, &digest));
+        assert!(ecdsa.verify_sign(&sigma, &P, message));
+
+        let vk = VerifyingKey::from_affine(P.as_point().to_affine()).unwrap();
+        let signature = ecdsa.to_signature(&sigma).unwrap();
+        assert!(vk.verify_prehash(&digest, &signature).is_ok());
+    }
+
+    #[test]
+    fn prehash_adaptor_works() {
+        let ecdsa = ECDSA::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let digest = [7u8; 32]; // e.g. a sighash, not the hash of any known message
+
+        let sigma_prime = ecdsa.pre_sign_prehash(&p, &digest, &T, None).unwrap();
+        assert!(ecdsa.verify_pre_sign_prehash(&P, &digest, &T, &sigma_prime));
+        assert!(!ecdsa.verify_pre_sign(&P, &digest, &T, &sigma_prime));
+
+        let sigma = ecdsa.adapt_signature(&sigma_prime, &t).unwrap();
+        let vk = VerifyingKey::from_affine(P.as_point().to_affine()).unwrap();
+        let signature = ecdsa.to_signature(&sigma).unwrap();
+        assert!(vk.verify_prehash(&digest, &signature).is_ok());
+        assert_eq!(ecdsa.extract_witness(&sigma, &sigma_prime, &T), Ok(t));
+    }
+}
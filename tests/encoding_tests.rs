@@ -0,0 +1,323 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{
+        AS_scheme, AdaptorStatement, AdaptorWitness, AumayrECDSA, Error, Pi, PoK, PublicKey,
+        Scheme, SchemePreSignature, SchemeSignature, Schnorr, SecretKey, Sigma, Sigma_prime,
+        Sigma_prime_ECDSA, Sign_scheme, VerifyError, BIP340, ECDSA,
+    };
+    use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
+    use rand_core::OsRng;
+    use zeroize::ZeroizeOnDrop;
+
+    /// Kind of a field in an encoding, and how to corrupt it.
+    #[derive(Clone, Copy)]
+    enum FieldKind {
+        Point,
+        XOnly,
+        Scalar,
+    }
+
+    fn all_schemes() -> Vec<Scheme> {
+        vec![
+            Scheme::Schnorr(Schnorr::new()),
+            Scheme::BIP340(BIP340),
+            Scheme::ECDSA(ECDSA::new()),
+            Scheme::AumayrECDSA(AumayrECDSA::new()),
+        ]
+    }
+
+    /// Returns an x-coordinate with no point on the curve.
+    fn off_curve_x() -> [u8; 32] {
+        let mut x = [0u8; 32];
+        for candidate in 1u8.. {
+            x[31] = candidate;
+            if BIP340::lift_x(&x).is_none() {
+                return x;
+            }
+        }
+        unreachable!()
+    }
+
+    /// Checks that `decode` rejects `bytes` with a wrong length and with each field corrupted.
+    fn assert_strict<T>(
+        bytes: &[u8],
+        fields: &[(usize, FieldKind)],
+        decode: impl Fn(&[u8]) -> Result<T, Error>,
+    ) {
+        assert!(decode(bytes).is_ok());
+        assert_eq!(
+            decode(&bytes[..bytes.len() - 1]).err(),
+            Some(Error::InvalidLength)
+        );
+        let mut trailing = bytes.to_vec();
+        trailing.push(0);
+        assert_eq!(decode(&trailing).err(), Some(Error::InvalidLength));
+
+        for &(offset, kind) in fields {
+            let mut corrupted = bytes.to_vec();
+            match kind {
+                FieldKind::Point => {
+                    corrupted[offset..offset + 33].copy_from_slice(&[0u8; 33]); // identity
+                    assert_eq!(decode(&corrupted).err(), Some(Error::InvalidPoint));
+                    corrupted[offset] = 0x02;
+                    corrupted[offset + 1..offset + 33].copy_from_slice(&off_curve_x());
+                    assert_eq!(decode(&corrupted).err(), Some(Error::InvalidPoint));
+                    corrupted[offset..offset + 33].copy_from_slice(&bytes[offset..offset + 33]);
+                    corrupted[offset] = 0x04; // not a compressed tag
+                    assert_eq!(decode(&corrupted).err(), Some(Error::InvalidPoint));
+                }
+                FieldKind::XOnly => {
+                    corrupted[offset..offset + 32].copy_from_slice(&off_curve_x());
+                    assert_eq!(decode(&corrupted).err(), Some(Error::InvalidPoint));
+                    corrupted[offset..offset + 32].copy_from_slice(&[0xFF; 32]); // x >= p
+                    assert_eq!(decode(&corrupted).err(), Some(Error::InvalidPoint));
+                }
+                FieldKind::Scalar => {
+                    corrupted[offset..offset + 32].copy_from_slice(&[0xFF; 32]); // s >= n
+                    assert_eq!(decode(&corrupted).err(), Some(Error::InvalidScalar));
+                }
+            }
+        }
+    }
+
+    fn fixtures(
+        scheme: &Scheme,
+    ) -> (
+        AdaptorStatement,
+        SecretKey,
+        SchemePreSignature,
+        SchemeSignature,
+    ) {
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let sigma_prime = scheme.pre_sign(&p, b"Encoding", &T, None).unwrap();
+        let sigma = scheme.adapt_signature(&sigma_prime, &t).unwrap();
+        (T, p, sigma_prime, sigma)
+    }
+
+    #[test]
+    fn signatures_round_trip() {
+        for scheme in all_schemes() {
+            let (_, p, _, sigma) = fixtures(&scheme);
+            let P = p.public_key();
+            let bytes = scheme.encode_signature(&sigma).unwrap();
+            let expected_len = match scheme {
+                Scheme::BIP340(_) => 64,
+                _ => Sigma::ENCODED_LEN,
+            };
+            assert_eq!(bytes.len(), expected_len);
+
+            let decoded = scheme.decode_signature(&bytes).unwrap();
+            assert_eq!(decoded.as_sigma().s, sigma.as_sigma().s);
+            assert_eq!(decoded.as_sigma().R, sigma.as_sigma().R);
+            assert!(scheme.verify_sign(&decoded, &P, b"Encoding"));
+        }
+    }
+
+    #[test]
+    fn pre_signatures_round_trip() {
+        for scheme in all_schemes() {
+            let (T, p, sigma_prime, _) = fixtures(&scheme);
+            let P = p.public_key();
+            let bytes = scheme.encode_pre_signature(&sigma_prime).unwrap();
+            let expected_len = match scheme {
+                Scheme::Schnorr(_) | Scheme::BIP340(_) => Sigma_prime::ENCODED_LEN,
+                _ => Sigma_prime_ECDSA::ENCODED_LEN,
+            };
+            assert_eq!(bytes.len(), expected_len);
+
+            let decoded = scheme.decode_pre_signature(&bytes).unwrap();
+            assert_eq!(scheme.encode_pre_signature(&decoded).unwrap(), bytes);
+            assert!(scheme.verify_pre_sign(&P, b"Encoding", &T, &decoded));
+        }
+    }
+
+    #[test]
+    fn ecdsa_pre_signature_always_carries_a_proof() {
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let sigma_prime = ECDSA::new().pre_sign(&p, b"Encoding", &T, None).unwrap();
+        let bytes = sigma_prime.to_bytes();
+        assert_eq!(bytes.len(), ECDSA::PRE_SIGNATURE_LEN);
+
+        let decoded = Sigma_prime_ECDSA::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.Z, sigma_prime.Z);
+        assert_eq!(decoded.pi, sigma_prime.pi);
+        assert!(ECDSA::new().verify_pre_sign(&p.public_key(), b"Encoding", &T, &decoded));
+    }
+
+    #[test]
+    fn schemes_reject_each_others_values() {
+        let (T, p, ecdsa_sigma_prime, ecdsa_sigma) = fixtures(&Scheme::ECDSA(ECDSA::new()));
+        let (_, _, aumayr_sigma_prime, _) = fixtures(&Scheme::AumayrECDSA(AumayrECDSA::new()));
+        let (_, _, schnorr_sigma_prime, _) = fixtures(&Scheme::Schnorr(Schnorr::new()));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+
+        let aumayr = Scheme::AumayrECDSA(AumayrECDSA::new());
+        assert_eq!(
+            aumayr.verify_sign_detailed(&ecdsa_sigma, &P, b"Encoding"),
+            Err(VerifyError::SchemeMismatch)
+        );
+        assert_eq!(
+            aumayr.verify_pre_sign_detailed(&P, b"Encoding", &T, &ecdsa_sigma_prime),
+            Err(VerifyError::SchemeMismatch)
+        );
+        assert_eq!(
+            aumayr.adapt_signature(&schnorr_sigma_prime, &t).err(),
+            Some(Error::SchemeMismatch)
+        );
+        assert_eq!(
+            aumayr
+                .extract_witness(&ecdsa_sigma, &aumayr_sigma_prime, &T)
+                .err(),
+            Some(Error::SchemeMismatch)
+        );
+        assert_eq!(
+            aumayr.encode_signature(&ecdsa_sigma).err(),
+            Some(Error::SchemeMismatch)
+        );
+        assert_eq!(
+            aumayr.encode_pre_signature(&schnorr_sigma_prime).err(),
+            Some(Error::SchemeMismatch)
+        );
+        assert_eq!(
+            Scheme::ECDSA(ECDSA::new())
+                .extract_witness(&ecdsa_sigma, &ecdsa_sigma_prime, &T)
+                .map(|t| t.statement()),
+            Ok(T)
+        );
+    }
+
+    #[test]
+    fn proof_round_trips() {
+        let pi = Pi {
+            e: Scalar::random(&mut OsRng),
+            i: Scalar::random(&mut OsRng),
+        };
+        let decoded = Pi::from_bytes(&pi.to_bytes()).unwrap();
+        assert_eq!(decoded.e, pi.e);
+        assert_eq!(decoded.i, pi.i);
+    }
+
+    #[test]
+    fn signature_decoding_is_strict() {
+        for scheme in all_schemes() {
+            let (_, _, _, sigma) = fixtures(&scheme);
+            let bytes = scheme.encode_signature(&sigma).unwrap();
+            let fields: &[(usize, FieldKind)] = match scheme {
+                Scheme::BIP340(_) => &[(0, FieldKind::XOnly), (32, FieldKind::Scalar)],
+                _ => &[(0, FieldKind::Point), (33, FieldKind::Scalar)],
+            };
+            assert_strict(&bytes, fields, |b| scheme.decode_signature(b));
+        }
+    }
+
+    #[test]
+    fn pre_signature_decoding_is_strict() {
+        for scheme in all_schemes() {
+            let (_, _, sigma_prime, _) = fixtures(&scheme);
+            let bytes = scheme.encode_pre_signature(&sigma_prime).unwrap();
+            let fields: &[(usize, FieldKind)] = match scheme {
+                Scheme::Schnorr(_) | Scheme::BIP340(_) => {
+                    &[(0, FieldKind::Point), (33, FieldKind::Scalar)]
+                }
+                _ => &[
+                    (0, FieldKind::Point),
+                    (33, FieldKind::Scalar),
+                    (65, FieldKind::Point),
+                    (98, FieldKind::Point),
+                    (131, FieldKind::Point),
+                    (164, FieldKind::Scalar),
+                ],
+            };
+            assert_strict(&bytes, fields, |b| scheme.decode_pre_signature(b));
+        }
+    }
+
+    #[test]
+    fn proof_of_knowledge_round_trips_and_decoding_is_strict() {
+        let x = Scalar::random(&mut OsRng);
+        let X = ProjectivePoint::GENERATOR * x;
+        let pi = PoK::prove(&x, &X, None, None).unwrap();
+        let decoded = PoK::from_bytes(&pi.to_bytes()).unwrap();
+        assert!(decoded.verify(&X, None));
+
+        assert_strict(
+            &pi.to_bytes(),
+            &[(0, FieldKind::Scalar), (32, FieldKind::Scalar)],
+            PoK::from_bytes,
+        );
+    }
+
+    #[test]
+    fn proof_decoding_is_strict() {
+        let pi = Pi {
+            e: Scalar::random(&mut OsRng),
+            i: Scalar::random(&mut OsRng),
+        };
+        assert_strict(
+            &pi.to_bytes(),
+            &[(0, FieldKind::Scalar), (32, FieldKind::Scalar)],
+            Pi::from_bytes,
+        );
+    }
+
+    #[test]
+    fn keys_round_trip_and_decoding_is_strict() {
+        let (T, p, _, _) = fixtures(&Scheme::Schnorr(Schnorr::new()));
+        let P = p.public_key();
+        assert_eq!(SecretKey::from_bytes(&p.to_bytes()), Ok(p.clone()));
+        assert_eq!(PublicKey::from_bytes(&P.to_bytes()), Ok(P));
+        assert_eq!(AdaptorStatement::from_bytes(&T.to_bytes()), Ok(T));
+
+        assert_strict(
+            &p.to_bytes(),
+            &[(0, FieldKind::Scalar)],
+            SecretKey::from_bytes,
+        );
+        assert_strict(
+            &P.to_bytes(),
+            &[(0, FieldKind::Point)],
+            PublicKey::from_bytes,
+        );
+        assert_strict(&T.to_bytes(), &[(0, FieldKind::Point)], |b| {
+            AdaptorStatement::from_bytes(b)
+        });
+        assert_eq!(
+            AdaptorWitness::from_bytes(&[0u8; 32]),
+            Err(Error::InvalidScalar)
+        );
+        assert_eq!(SecretKey::from_bytes(&[0u8; 32]), Err(Error::InvalidScalar));
+    }
+
+    #[test]
+    fn secrets_are_redacted_and_zeroized_on_drop() {
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<SecretKey>();
+        assert_zeroize_on_drop::<AdaptorWitness>();
+
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        for (shown, secret) in [
+            (format!("{:?} {}", p, p), p.to_bytes()),
+            (format!("{:?} {}", t, t), t.to_bytes()),
+        ] {
+            assert!(shown.contains("<redacted>"));
+            assert!(!shown.to_lowercase().contains(&hex::encode(secret)));
+        }
+    }
+
+    #[test]
+    fn identity_encodes_to_rejected_bytes() {
+        let sigma = Sigma::default();
+        assert_eq!(
+            Sigma::from_bytes(&sigma.to_bytes()).err(),
+            Some(Error::InvalidPoint)
+        );
+    }
+}
@@ -0,0 +1,143 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{
+        AS_scheme, AdaptorWitness, AumayrECDSA, Error, PoK, Scheme, Schnorr, Sign_scheme,
+        VerifyError, BIP340, ECDSA,
+    };
+    use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::{OsRng, SeedableRng};
+
+    fn all_schemes() -> Vec<Scheme> {
+        vec![
+            Scheme::Schnorr(Schnorr::new()),
+            Scheme::BIP340(BIP340),
+            Scheme::ECDSA(ECDSA::new()),
+            Scheme::AumayrECDSA(AumayrECDSA::new()),
+        ]
+    }
+
+    #[test]
+    fn keygen_produces_working_keys() {
+        for scheme in all_schemes() {
+            let (p, P) = scheme.keygen(&mut OsRng);
+            assert_eq!(p.public_key(), P);
+
+            let sigma = scheme.sign(&p, b"Keygen", None).unwrap();
+            assert!(scheme.verify_sign(&sigma, &P, b"Keygen"));
+        }
+    }
+
+    #[test]
+    fn keygen_uses_the_given_rng() {
+        let scheme = Scheme::Schnorr(Schnorr::new());
+        let (p1, P1) = scheme.keygen(&mut ChaCha20Rng::from_seed([7u8; 32]));
+        let (p2, P2) = scheme.keygen(&mut ChaCha20Rng::from_seed([7u8; 32]));
+        let (_, P3) = scheme.keygen(&mut ChaCha20Rng::from_seed([8u8; 32]));
+        assert_eq!((p1, P1), (p2, P2));
+        assert_ne!(P1, P3);
+    }
+
+    #[test]
+    fn gen_statement_works_with_pre_sign() {
+        for scheme in all_schemes() {
+            let (p, P) = scheme.keygen(&mut OsRng);
+            let (t, T, pi) = scheme.gen_statement(&mut OsRng, false);
+            assert!(pi.is_none());
+            assert_eq!(t.statement(), T);
+
+            let sigma_prime = scheme.pre_sign(&p, b"Statement", &T, None).unwrap();
+            assert!(scheme.verify_pre_sign(&P, b"Statement", &T, &sigma_prime));
+            let sigma = scheme.adapt_signature(&sigma_prime, &t).unwrap();
+            assert!(scheme.verify_sign(&sigma, &P, b"Statement"));
+        }
+    }
+
+    #[test]
+    fn gen_statement_proves_knowledge_of_t() {
+        let mut rng = ChaCha20Rng::from_seed([1u8; 32]);
+        let (_, T, pi) = Schnorr::new().gen_statement(&mut rng, true);
+        let pi = pi.unwrap();
+        assert!(pi.verify(T.as_point(), None));
+
+        let (_, other, _) = ECDSA::new().gen_statement(&mut rng, false);
+        assert_eq!(
+            pi.verify_detailed(other.as_point(), None),
+            Err(VerifyError::InvalidProof)
+        );
+        assert_eq!(
+            pi.verify_detailed(&ProjectivePoint::IDENTITY, None),
+            Err(VerifyError::IdentityPoint)
+        );
+    }
+
+    #[test]
+    fn verify_statement_checks_the_proof_against_the_statement() {
+        for scheme in all_schemes() {
+            let (_, T, pi) = scheme.gen_statement(&mut OsRng, true);
+            let pi = pi.unwrap();
+            assert_eq!(scheme.verify_statement(&T, &pi), Ok(()));
+
+            let (_, other, _) = scheme.gen_statement(&mut OsRng, false);
+            assert_eq!(
+                scheme.verify_statement(&other, &pi),
+                Err(VerifyError::InvalidProof)
+            );
+        }
+    }
+
+    #[test]
+    fn tampered_proof_is_rejected() {
+        let x = Scalar::random(&mut OsRng);
+        let X = ProjectivePoint::GENERATOR * x;
+        let pi = PoK::prove(&x, &X, None, None).unwrap();
+        assert!(pi.verify(&X, None));
+
+        let tampered = PoK {
+            e: pi.e,
+            s: pi.s + Scalar::ONE,
+        };
+        assert!(!tampered.verify(&X, None));
+
+        // bound to the session context
+        let pi = PoK::prove(&x, &X, Some(b"swap 1"), None).unwrap();
+        assert!(pi.verify(&X, Some(b"swap 1")));
+        assert!(!pi.verify(&X, Some(b"swap 2")));
+        assert!(!pi.verify(&X, None));
+
+        // the wrong discrete log is refused
+        assert_eq!(
+            PoK::prove(&(x + Scalar::ONE), &X, None, None).err(),
+            Some(Error::WitnessMismatch)
+        );
+    }
+
+    #[test]
+    fn schemes_derive_different_nonces() {
+        let (p, _) = Schnorr::new().keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let m = b"Same key, same message";
+        let differ = |a: ProjectivePoint, b: ProjectivePoint| a != b && a != -b;
+
+        // a shared nonce under two challenges leaks the key
+        let schnorr = Schnorr::new().sign(&p, m, None).unwrap().R;
+        let bip340 = BIP340.sign(&p, m, None).unwrap().R;
+        let ecdsa = ECDSA::new().sign(&p, m, None).unwrap().R;
+        assert!(differ(schnorr, bip340));
+        assert!(differ(schnorr, ecdsa));
+        assert!(differ(bip340, ecdsa));
+
+        let schnorr = Schnorr::new().pre_sign(&p, m, &T, None).unwrap().R_prime;
+        let bip340 = BIP340.pre_sign(&p, m, &T, None).unwrap().R_prime;
+        assert!(differ(schnorr, bip340));
+        let ecdsa = ECDSA::new().pre_sign(&p, m, &T, None).unwrap().R_prime;
+        let aumayr = AumayrECDSA::new()
+            .pre_sign(&p, m, &T, None)
+            .unwrap()
+            .R_prime;
+        assert!(differ(ecdsa, aumayr));
+    }
+}
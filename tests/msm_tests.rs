@@ -0,0 +1,153 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::msm::msm_vartime;
+    use as_for_fde::sigma::{Dleq, DleqStatement, DleqWithTable};
+    use as_for_fde::{
+        AS_scheme, AdaptorWitness, AumayrECDSA, Error, PointTable, SecretKey, SigmaProtocol,
+        Transcript, ECDSA,
+    };
+    use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
+    use rand_core::OsRng;
+
+    const MESSAGE: &[u8] = b"as_for_fde";
+
+    fn random_point() -> ProjectivePoint {
+        ProjectivePoint::GENERATOR * Scalar::random(&mut OsRng)
+    }
+
+    /// Scalars with all-zero, all-one and alternating radix-16 digits, plus random ones.
+    fn scalars() -> Vec<Scalar> {
+        let mut scalars = vec![
+            Scalar::ZERO,
+            Scalar::ONE,
+            -Scalar::ONE,
+            Scalar::from(8u64),
+            Scalar::from(0x8888_8888_8888_8888u64),
+            -Scalar::from(0x8888_8888_8888_8888u64),
+        ];
+        scalars.extend((0..8).map(|_| Scalar::random(&mut OsRng)));
+        scalars
+    }
+
+    #[test]
+    fn table_multiplication_matches_scalar_multiplication() {
+        let B = random_point();
+        let table = PointTable::new(&B);
+        assert_eq!(*table.point(), B);
+        for k in scalars() {
+            assert_eq!(table.mul(&k), B * k);
+            assert_eq!(table.mul_vartime(&k), B * k);
+        }
+
+        let identity = PointTable::new(&ProjectivePoint::IDENTITY);
+        let k = Scalar::random(&mut OsRng);
+        assert_eq!(identity.mul(&k), ProjectivePoint::IDENTITY);
+        assert_eq!(identity.mul_vartime(&k), ProjectivePoint::IDENTITY);
+    }
+
+    #[test]
+    fn generator_table() {
+        let table = PointTable::generator();
+        assert_eq!(*table.point(), ProjectivePoint::GENERATOR);
+        for k in scalars() {
+            assert_eq!(table.mul(&k), ProjectivePoint::GENERATOR * k);
+        }
+    }
+
+    #[test]
+    fn msm_matches_sum_of_products() {
+        // below and above the switch to Pippenger's method
+        for n in [0, 1, 2, 3, 47, 48, 200] {
+            let mut terms: Vec<(ProjectivePoint, Scalar)> = (0..n)
+                .map(|_| (random_point(), Scalar::random(&mut OsRng)))
+                .collect();
+            terms.push((ProjectivePoint::GENERATOR, Scalar::random(&mut OsRng)));
+            terms.push((ProjectivePoint::GENERATOR, -Scalar::ONE));
+            let expected: ProjectivePoint = terms.iter().map(|(X, k)| X * k).sum();
+            assert_eq!(msm_vartime(&terms), expected);
+        }
+        assert_eq!(msm_vartime(&[]), ProjectivePoint::IDENTITY);
+    }
+
+    #[test]
+    fn ecdsa_pre_sign_with_table_matches_pre_sign() {
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let table = PointTable::new(T.as_point());
+
+        let sigma_prime = ECDSA::new()
+            .pre_sign_with_table(&p, MESSAGE, &T, &table, None)
+            .unwrap();
+        assert!(ECDSA::new().verify_pre_sign(&P, MESSAGE, &T, &sigma_prime));
+        let expected = ECDSA::new().pre_sign(&p, MESSAGE, &T, None).unwrap();
+        assert_eq!(sigma_prime.to_bytes(), expected.to_bytes());
+
+        let other = PointTable::new(&random_point());
+        assert_eq!(
+            ECDSA::new()
+                .pre_sign_with_table(&p, MESSAGE, &T, &other, None)
+                .err(),
+            Some(Error::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn aumayr_pre_sign_with_table_matches_pre_sign() {
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let table = PointTable::new(T.as_point());
+
+        let sigma_prime = AumayrECDSA::new()
+            .pre_sign_with_table(&p, MESSAGE, &T, &table, Some(&[7u8; 32]))
+            .unwrap();
+        assert!(AumayrECDSA::new().verify_pre_sign(&P, MESSAGE, &T, &sigma_prime));
+        let expected = AumayrECDSA::new()
+            .pre_sign(&p, MESSAGE, &T, Some(&[7u8; 32]))
+            .unwrap();
+        assert_eq!(sigma_prime.to_bytes(), expected.to_bytes());
+
+        let other = PointTable::new(&random_point());
+        assert_eq!(
+            AumayrECDSA::new()
+                .pre_sign_with_table(&p, MESSAGE, &T, &other, None)
+                .err(),
+            Some(Error::InvalidPoint)
+        );
+    }
+
+    #[test]
+    fn dleq_with_table_proofs_match_dleq() {
+        let transcript = Transcript::new("as_for_fde/test", None);
+        let x = Scalar::random(&mut OsRng);
+        let H = random_point();
+        let statement = DleqStatement {
+            G: ProjectivePoint::GENERATOR,
+            X: ProjectivePoint::GENERATOR * x,
+            H,
+            Y: H * x,
+        };
+        let table = PointTable::new(&H);
+
+        let proof = DleqWithTable(&table)
+            .prove(&transcript, &statement, &x, None)
+            .unwrap();
+        assert_eq!(
+            Some(&proof),
+            Dleq.prove(&transcript, &statement, &x, None).as_ref().ok()
+        );
+        assert!(Dleq.verify(&transcript, &statement, &proof));
+        assert!(DleqWithTable(&table).verify(&transcript, &statement, &proof));
+
+        // a table of another point is not used
+        let other = PointTable::new(&random_point());
+        let proof = DleqWithTable(&other)
+            .prove(&transcript, &statement, &x, None)
+            .unwrap();
+        assert!(Dleq.verify(&transcript, &statement, &proof));
+    }
+}
@@ -0,0 +1,201 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{
+        AS_scheme, AdaptorWitness, DiscreteLog, NonceManager, NonceManagerError, Schnorr,
+        Sha512Suite, Sign_scheme, BIP340, ECDSA,
+    };
+    use k256::{elliptic_curve::ff::Field, Scalar};
+    use rand_core::{OsRng, RngCore};
+    use std::fs::{self, OpenOptions};
+    use std::io::ErrorKind;
+    use std::path::PathBuf;
+
+    const M1: &[u8] = b"pay 1 BTC to Bob";
+    const M2: &[u8] = b"pay 2 BTC to Carol";
+    /// Length of the magic string and version that start a store.
+    const HEADER_LEN: u64 = 9;
+
+    /// A fresh store file in the temporary directory, removed when dropped.
+    struct Store(PathBuf);
+
+    impl Store {
+        fn new() -> Self {
+            let name = format!("as_for_fde_nonce_manager_test_{:016x}", OsRng.next_u64());
+            Self(std::env::temp_dir().join(name))
+        }
+    }
+
+    impl Drop for Store {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn same_tuple_returns_the_stored_pre_signature() {
+        let store = Store::new();
+        let (p, P) = ECDSA::new().keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+
+        let mut nonces = NonceManager::open(&store.0).unwrap();
+        let first = nonces
+            .pre_sign(&ECDSA::new(), &p, M1, &T, Some(&[1u8; 32]))
+            .unwrap();
+        assert!(ECDSA::new().verify_pre_sign(&P, M1, &T, &first));
+        let again = nonces
+            .pre_sign(&ECDSA::new(), &p, M1, &T, Some(&[2u8; 32]))
+            .unwrap();
+        assert_eq!(again.to_bytes(), first.to_bytes());
+
+        // another message or statement gets a pre-signature of its own
+        let other = nonces
+            .pre_sign(&ECDSA::new(), &p, M2, &T, Some(&[1u8; 32]))
+            .unwrap();
+        assert!(ECDSA::new().verify_pre_sign(&P, M2, &T, &other));
+        let T2 = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let other = nonces
+            .pre_sign(&ECDSA::new(), &p, M1, &T2, Some(&[1u8; 32]))
+            .unwrap();
+        assert_ne!(other.to_bytes(), first.to_bytes());
+    }
+
+    #[test]
+    fn stored_pre_signatures_survive_a_restart() {
+        let store = Store::new();
+        let (p, _) = Schnorr::new().keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+
+        let first = NonceManager::open(&store.0)
+            .unwrap()
+            .pre_sign(&Schnorr::new(), &p, M1, &T, None)
+            .unwrap();
+        let mut nonces = NonceManager::open(&store.0).unwrap();
+        let again = nonces.pre_sign(&Schnorr::new(), &p, M1, &T, None).unwrap();
+        assert_eq!(again.to_bytes(), first.to_bytes());
+    }
+
+    #[test]
+    fn torn_record_is_discarded() {
+        let store = Store::new();
+        let (p, _) = Schnorr::new().keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+
+        let mut nonces = NonceManager::open(&store.0).unwrap();
+        let first = nonces.pre_sign(&Schnorr::new(), &p, M1, &T, None).unwrap();
+        nonces.pre_sign(&Schnorr::new(), &p, M2, &T, None).unwrap();
+        drop(nonces);
+
+        // a crash in the middle of writing the second record
+        let len = fs::metadata(&store.0).unwrap().len();
+        let file = OpenOptions::new().write(true).open(&store.0).unwrap();
+        file.set_len(len - 10).unwrap();
+        drop(file);
+
+        let mut nonces = NonceManager::open(&store.0).unwrap();
+        let record = (len - HEADER_LEN) / 2;
+        assert_eq!(fs::metadata(&store.0).unwrap().len(), HEADER_LEN + record);
+        let again = nonces.pre_sign(&Schnorr::new(), &p, M1, &T, None).unwrap();
+        assert_eq!(again.to_bytes(), first.to_bytes());
+        nonces.pre_sign(&Schnorr::new(), &p, M2, &T, None).unwrap();
+        assert_eq!(fs::metadata(&store.0).unwrap().len(), len);
+    }
+
+    #[test]
+    fn schemes_sharing_a_store_get_their_own_pre_signatures() {
+        let store = Store::new();
+        let (p, P) = Schnorr::new().keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+
+        let mut nonces = NonceManager::open(&store.0).unwrap();
+        let schnorr = nonces.pre_sign(&Schnorr::new(), &p, M1, &T, None).unwrap();
+        let bip340 = nonces.pre_sign(&BIP340, &p, M1, &T, None).unwrap();
+        assert!(BIP340.verify_pre_sign(&P, M1, &T, &bip340));
+        assert_ne!(schnorr.to_bytes(), bip340.to_bytes());
+
+        // the same scheme under another suite is another scheme
+        let sha512 = Schnorr::<DiscreteLog, Sha512Suite>::default();
+        let other = nonces.pre_sign(&sha512, &p, M1, &T, None).unwrap();
+        assert!(sha512.verify_pre_sign(&P, M1, &T, &other));
+        drop(nonces);
+
+        let mut nonces = NonceManager::open(&store.0).unwrap();
+        let again = nonces.pre_sign(&BIP340, &p, M1, &T, None).unwrap();
+        assert_eq!(again.to_bytes(), bip340.to_bytes());
+        let again = nonces.pre_sign(&Schnorr::new(), &p, M1, &T, None).unwrap();
+        assert_eq!(again.to_bytes(), schnorr.to_bytes());
+    }
+
+    /// Stores two Schnorr pre-signatures, flips a bit of the byte at `offset` in the second
+    /// record and returns the length of the file.
+    fn damage(store: &Store, offset: usize) -> u64 {
+        let (p, _) = Schnorr::new().keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let mut nonces = NonceManager::open(&store.0).unwrap();
+        nonces.pre_sign(&Schnorr::new(), &p, M1, &T, None).unwrap();
+        nonces.pre_sign(&Schnorr::new(), &p, M2, &T, None).unwrap();
+        drop(nonces);
+
+        let mut bytes = fs::read(&store.0).unwrap();
+        let record = (bytes.len() - HEADER_LEN as usize) / 2;
+        bytes[HEADER_LEN as usize + record + offset] ^= 1;
+        fs::write(&store.0, &bytes).unwrap();
+        bytes.len() as u64
+    }
+
+    #[test]
+    fn damaged_record_is_reported_not_discarded() {
+        // a byte of the encoding
+        let store = Store::new();
+        let len = damage(&store, 50);
+        assert!(matches!(
+            NonceManager::open(&store.0),
+            Err(NonceManagerError::Corrupt)
+        ));
+        assert_eq!(fs::metadata(&store.0).unwrap().len(), len);
+
+        // the length, which would otherwise make the final record look torn
+        let store = Store::new();
+        let len = damage(&store, 32);
+        assert!(matches!(
+            NonceManager::open(&store.0),
+            Err(NonceManagerError::Corrupt)
+        ));
+        assert_eq!(fs::metadata(&store.0).unwrap().len(), len);
+    }
+
+    #[test]
+    fn file_that_is_not_a_store_is_rejected() {
+        let store = Store::new();
+        fs::write(&store.0, b"not a nonce store").unwrap();
+        assert!(matches!(
+            NonceManager::open(&store.0),
+            Err(NonceManagerError::Corrupt)
+        ));
+        assert_eq!(fs::read(&store.0).unwrap(), b"not a nonce store");
+    }
+
+    #[test]
+    fn store_is_locked_while_open() {
+        let store = Store::new();
+        let nonces = NonceManager::open(&store.0).unwrap();
+        assert!(matches!(
+            NonceManager::open(&store.0),
+            Err(NonceManagerError::Io(err)) if err.kind() == ErrorKind::WouldBlock
+        ));
+        drop(nonces);
+        NonceManager::open(&store.0).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symbolic_link_is_not_followed() {
+        let target = Store::new();
+        let link = Store::new();
+        std::os::unix::fs::symlink(&target.0, &link.0).unwrap();
+        assert!(NonceManager::open(&link.0).is_err());
+        assert!(!target.0.exists());
+    }
+}
@@ -0,0 +1,58 @@
+#![cfg(unix)]
+#![allow(non_snake_case)]
+
+// The file size limit below applies to the whole process, so this test has a binary of its own.
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{AS_scheme, AdaptorWitness, NonceManager, Schnorr, Sign_scheme};
+    use k256::{elliptic_curve::ff::Field, Scalar};
+    use rand_core::{OsRng, RngCore};
+    use std::fs;
+
+    const M1: &[u8] = b"pay 1 BTC to Bob";
+    const M2: &[u8] = b"pay 2 BTC to Carol";
+
+    /// Sets the soft limit on the size of files the process writes.
+    fn limit_file_size(limit: libc::rlim_t) {
+        let mut rlim = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        unsafe {
+            assert_eq!(libc::getrlimit(libc::RLIMIT_FSIZE, &mut rlim), 0);
+            rlim.rlim_cur = limit.min(rlim.rlim_max);
+            assert_eq!(libc::setrlimit(libc::RLIMIT_FSIZE, &rlim), 0);
+        }
+    }
+
+    #[test]
+    fn short_write_is_cut_off_before_the_next_record() {
+        let name = format!("as_for_fde_nonce_manager_test_{:016x}", OsRng.next_u64());
+        let path = std::env::temp_dir().join(name);
+        let (p, P) = Schnorr::new().keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+
+        let mut nonces = NonceManager::open(&path).unwrap();
+        let first = nonces.pre_sign(&Schnorr::new(), &p, M1, &T, None).unwrap();
+
+        // let the next record stop a few bytes in, failing with EFBIG rather than a signal
+        let len = fs::metadata(&path).unwrap().len();
+        unsafe { libc::signal(libc::SIGXFSZ, libc::SIG_IGN) };
+        limit_file_size(len + 10);
+        let failed = nonces.pre_sign(&Schnorr::new(), &p, M2, &T, None);
+        limit_file_size(libc::RLIM_INFINITY);
+        assert!(failed.is_err());
+        assert_eq!(fs::metadata(&path).unwrap().len(), len);
+
+        let second = nonces.pre_sign(&Schnorr::new(), &p, M2, &T, None).unwrap();
+        drop(nonces);
+        let mut nonces = NonceManager::open(&path).unwrap();
+        let again = nonces.pre_sign(&Schnorr::new(), &p, M1, &T, None).unwrap();
+        assert_eq!(again.to_bytes(), first.to_bytes());
+        let again = nonces.pre_sign(&Schnorr::new(), &p, M2, &T, None).unwrap();
+        assert_eq!(again.to_bytes(), second.to_bytes());
+        assert!(Schnorr::new().verify_pre_sign(&P, M2, &T, &again));
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,305 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{
+        AS_scheme, AdaptorWitness, AumayrECDSA, Observation, Schnorr, Sign_scheme, BIP340, ECDSA,
+    };
+    use k256::{elliptic_curve::ff::Field, Scalar};
+    use rand_core::OsRng;
+
+    const M1: &[u8] = b"pay 1 BTC to Bob";
+    const M2: &[u8] = b"pay 2 BTC to Carol";
+
+    #[test]
+    fn schnorr_signatures_with_a_reused_nonce_leak_the_key() {
+        let (p, P) = Schnorr::new().keygen(&mut OsRng);
+        let (q, Q) = Schnorr::new().keygen(&mut OsRng);
+        let k = Scalar::random(&mut OsRng);
+        let sigma_1 = Schnorr::new().sign_with_nonce(&p, M1, &k).unwrap();
+        let sigma_2 = Schnorr::new().sign_with_nonce(&p, M2, &k).unwrap();
+        let honest = Schnorr::new().sign(&p, M2, None).unwrap();
+        let other_key = Schnorr::new().sign_with_nonce(&q, M2, &k).unwrap();
+
+        let corpus = [
+            Observation::Signature {
+                sigma: &honest,
+                P: &P,
+                m: M2,
+            },
+            Observation::Signature {
+                sigma: &sigma_1,
+                P: &P,
+                m: M1,
+            },
+            Observation::Signature {
+                sigma: &other_key,
+                P: &Q,
+                m: M2,
+            },
+            Observation::Signature {
+                sigma: &sigma_2,
+                P: &P,
+                m: M2,
+            },
+        ];
+        let findings = Schnorr::new().find_nonce_reuse(&corpus);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].indices, vec![1, 3]);
+        assert_eq!(findings[0].P, P);
+        assert_eq!(findings[0].secret_key, Some(p));
+
+        // the key of the other signer is not leaked by a single use of the nonce
+        assert!(Schnorr::new().find_nonce_reuse(&corpus[..3]).is_empty());
+    }
+
+    #[test]
+    fn schnorr_pre_signature_and_signature_with_a_reused_nonce_leak_the_key() {
+        let (p, P) = Schnorr::new().keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let k = Scalar::random(&mut OsRng);
+        // R' = kG + T commits to the same nonce as R = kG, even for the same message
+        let sigma_prime = Schnorr::new().pre_sign_with_nonce(&p, M1, &T, &k).unwrap();
+        let sigma = Schnorr::new().sign_with_nonce(&p, M1, &k).unwrap();
+
+        let corpus = [
+            Observation::PreSignature {
+                sigma_prime: &sigma_prime,
+                P: &P,
+                m: M1,
+                T: &T,
+            },
+            Observation::Signature {
+                sigma: &sigma,
+                P: &P,
+                m: M1,
+            },
+        ];
+        let findings = Schnorr::new().find_nonce_reuse(&corpus);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].indices, vec![0, 1]);
+        assert_eq!(findings[0].secret_key, Some(p));
+    }
+
+    #[test]
+    fn repeated_signatures_are_not_reuse() {
+        let (p, P) = Schnorr::new().keygen(&mut OsRng);
+        let sigma = Schnorr::new().sign(&p, M1, None).unwrap();
+        let again = Schnorr::new().sign(&p, M1, None).unwrap();
+        let corpus = [
+            Observation::Signature {
+                sigma: &sigma,
+                P: &P,
+                m: M1,
+            },
+            Observation::Signature {
+                sigma: &again,
+                P: &P,
+                m: M1,
+            },
+        ];
+        assert!(Schnorr::new().find_nonce_reuse(&corpus).is_empty());
+
+        let (p, P) = ECDSA::new().keygen(&mut OsRng);
+        let sigma = ECDSA::new().sign(&p, M1, None).unwrap();
+        let corpus = [
+            Observation::Signature {
+                sigma: &sigma,
+                P: &P,
+                m: M1,
+            },
+            Observation::Signature {
+                sigma: &sigma,
+                P: &P,
+                m: M1,
+            },
+        ];
+        assert!(ECDSA::new().find_nonce_reuse(&corpus).is_empty());
+    }
+
+    #[test]
+    fn ecdsa_signatures_with_a_reused_nonce_leak_the_key() {
+        let (p, P) = ECDSA::new().keygen(&mut OsRng);
+        let k = Scalar::random(&mut OsRng);
+        let sigma_1 = ECDSA::new().sign_with_nonce(&p, M1, &k).unwrap();
+        let sigma_2 = ECDSA::new().sign_with_nonce(&p, M2, &k).unwrap();
+        // -k commits to the same r
+        let sigma_3 = ECDSA::new().sign_with_nonce(&p, b"third", &-k).unwrap();
+
+        let corpus = [
+            Observation::Signature {
+                sigma: &sigma_1,
+                P: &P,
+                m: M1,
+            },
+            Observation::Signature {
+                sigma: &sigma_2,
+                P: &P,
+                m: M2,
+            },
+        ];
+        let findings = ECDSA::new().find_nonce_reuse(&corpus);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].indices, vec![0, 1]);
+        assert_eq!(findings[0].secret_key, Some(p.clone()));
+
+        let corpus = [
+            corpus[0],
+            Observation::Signature {
+                sigma: &sigma_3,
+                P: &P,
+                m: b"third",
+            },
+        ];
+        assert_eq!(
+            ECDSA::new().find_nonce_reuse(&corpus)[0].secret_key,
+            Some(p)
+        );
+    }
+
+    #[test]
+    fn ecdsa_pre_signatures_with_a_reused_nonce_leak_the_key() {
+        let (p, P) = ECDSA::new().keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let k = Scalar::random(&mut OsRng);
+        let sigma_prime_1 = ECDSA::new().pre_sign_with_nonce(&p, M1, &T, &k).unwrap();
+        let sigma_prime_2 = ECDSA::new().pre_sign_with_nonce(&p, M2, &T, &k).unwrap();
+        let sigma = ECDSA::new().sign_with_nonce(&p, M2, &k).unwrap();
+
+        let corpus = [
+            Observation::PreSignature {
+                sigma_prime: &sigma_prime_1,
+                P: &P,
+                m: M1,
+                T: &T,
+            },
+            Observation::Signature {
+                sigma: &sigma,
+                P: &P,
+                m: M2,
+            },
+            Observation::PreSignature {
+                sigma_prime: &sigma_prime_2,
+                P: &P,
+                m: M2,
+                T: &T,
+            },
+        ];
+        let findings = ECDSA::new().find_nonce_reuse(&corpus);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].indices, vec![0, 2]);
+        assert_eq!(findings[0].secret_key, Some(p));
+    }
+
+    #[test]
+    fn ecdsa_pre_signatures_under_different_statements_are_not_grouped() {
+        let (p, P) = ECDSA::new().keygen(&mut OsRng);
+        let T1 = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let T2 = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let k = Scalar::random(&mut OsRng);
+        let sigma_prime_1 = ECDSA::new().pre_sign_with_nonce(&p, M1, &T1, &k).unwrap();
+        let sigma_prime_2 = ECDSA::new().pre_sign_with_nonce(&p, M2, &T2, &k).unwrap();
+
+        // r = x(kT) differs, and nothing ties the two to one nonce
+        let corpus = [
+            Observation::PreSignature {
+                sigma_prime: &sigma_prime_1,
+                P: &P,
+                m: M1,
+                T: &T1,
+            },
+            Observation::PreSignature {
+                sigma_prime: &sigma_prime_2,
+                P: &P,
+                m: M2,
+                T: &T2,
+            },
+        ];
+        assert!(ECDSA::new().find_nonce_reuse(&corpus).is_empty());
+    }
+
+    #[test]
+    fn bip340_signatures_and_pre_signatures_with_a_reused_nonce_leak_the_key() {
+        let (p, P) = BIP340.keygen(&mut OsRng);
+        let T = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let k = Scalar::random(&mut OsRng);
+        let sigma_1 = BIP340.sign_with_nonce(&p, M1, &k).unwrap();
+        let sigma_2 = BIP340.sign_with_nonce(&p, M2, &k).unwrap();
+        let sigma_prime = BIP340.pre_sign_with_nonce(&p, M2, &T, &k).unwrap();
+
+        let corpus = [
+            Observation::Signature {
+                sigma: &sigma_1,
+                P: &P,
+                m: M1,
+            },
+            Observation::Signature {
+                sigma: &sigma_2,
+                P: &P,
+                m: M2,
+            },
+        ];
+        let findings = BIP340.find_nonce_reuse(&corpus);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].indices, vec![0, 1]);
+        assert_eq!(findings[0].secret_key, Some(p.clone()));
+
+        // the nonce of the pre-signature is negated whenever R' has an odd Y coordinate
+        let corpus = [
+            corpus[0],
+            Observation::PreSignature {
+                sigma_prime: &sigma_prime,
+                P: &P,
+                m: M2,
+                T: &T,
+            },
+        ];
+        let findings = BIP340.find_nonce_reuse(&corpus);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].secret_key, Some(p));
+    }
+
+    #[test]
+    fn aumayr_pre_signatures_with_a_reused_nonce_leak_the_key() {
+        let (p, P) = AumayrECDSA::new().keygen(&mut OsRng);
+        let T1 = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let T2 = AdaptorWitness::from(Scalar::random(&mut OsRng)).statement();
+        let k = Scalar::random(&mut OsRng);
+        let sigma_prime_1 = AumayrECDSA::new()
+            .pre_sign_with_nonce(&p, M1, &T1, &k)
+            .unwrap();
+        let sigma_prime_2 = AumayrECDSA::new()
+            .pre_sign_with_nonce(&p, M1, &T2, &k)
+            .unwrap();
+        let sigma = AumayrECDSA::new().sign_with_nonce(&p, M2, &k).unwrap();
+
+        // K = kG exposes the shared nonce even under different statements and one message
+        let corpus = [
+            Observation::PreSignature {
+                sigma_prime: &sigma_prime_1,
+                P: &P,
+                m: M1,
+                T: &T1,
+            },
+            Observation::PreSignature {
+                sigma_prime: &sigma_prime_2,
+                P: &P,
+                m: M1,
+                T: &T2,
+            },
+            Observation::Signature {
+                sigma: &sigma,
+                P: &P,
+                m: M2,
+            },
+        ];
+        let findings = AumayrECDSA::new().find_nonce_reuse(&corpus);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].indices, vec![0, 1, 2]);
+        assert_eq!(findings[0].secret_key, Some(p.clone()));
+
+        let findings = AumayrECDSA::new().find_nonce_reuse(&corpus[1..]);
+        assert_eq!(findings[0].secret_key, Some(p));
+    }
+}
@@ -0,0 +1,161 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::{
+        AS_scheme, AdaptorWitness, AumayrECDSA, DiscreteLog, Error, HardRelation, Schnorr,
+        SecretKey, Sign_scheme, ECDSA,
+    };
+    use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
+    use rand_core::{CryptoRng, OsRng, RngCore};
+
+    /// Second base `H` of the `DleqPair` relation.
+    fn H() -> ProjectivePoint {
+        ProjectivePoint::GENERATOR * Scalar::from(7u64)
+    }
+
+    /// Relation between `t` and the pair `(tG, tH)`, e.g. an oracle attestation published on
+    /// two bases.
+    struct DleqPair;
+
+    impl HardRelation for DleqPair {
+        type Statement = (ProjectivePoint, ProjectivePoint);
+        type Witness = AdaptorWitness;
+
+        fn gen_statement<R: RngCore + CryptoRng>(
+            rng: &mut R,
+        ) -> (AdaptorWitness, (ProjectivePoint, ProjectivePoint)) {
+            let t = AdaptorWitness::from(Scalar::random(rng));
+            let T = Self::statement(&t);
+            (t, T)
+        }
+
+        fn statement(t: &AdaptorWitness) -> (ProjectivePoint, ProjectivePoint) {
+            (*t.statement().as_point(), H() * t.as_scalar())
+        }
+
+        fn verify_witness(T: &(ProjectivePoint, ProjectivePoint), t: &AdaptorWitness) -> bool {
+            Self::statement(t) == *T
+        }
+
+        fn adaptor_point(T: &(ProjectivePoint, ProjectivePoint)) -> ProjectivePoint {
+            T.0
+        }
+
+        fn adaptor_scalar(t: &AdaptorWitness) -> &Scalar {
+            t.as_scalar()
+        }
+
+        fn witness_from_scalar(t: Scalar) -> AdaptorWitness {
+            AdaptorWitness::from(t)
+        }
+    }
+
+    /// Discrete-log relation with the bare point and scalar as statement and witness.
+    struct BareDiscreteLog;
+
+    impl HardRelation for BareDiscreteLog {
+        type Statement = ProjectivePoint;
+        type Witness = Scalar;
+
+        fn gen_statement<R: RngCore + CryptoRng>(rng: &mut R) -> (Scalar, ProjectivePoint) {
+            let t = Scalar::random(rng);
+            (t, Self::statement(&t))
+        }
+
+        fn statement(t: &Scalar) -> ProjectivePoint {
+            ProjectivePoint::GENERATOR * t
+        }
+
+        fn verify_witness(T: &ProjectivePoint, t: &Scalar) -> bool {
+            Self::statement(t) == *T
+        }
+
+        fn adaptor_point(T: &ProjectivePoint) -> ProjectivePoint {
+            *T
+        }
+
+        fn adaptor_scalar(t: &Scalar) -> &Scalar {
+            t
+        }
+
+        fn witness_from_scalar(t: Scalar) -> Scalar {
+            t
+        }
+    }
+
+    /// Pre-signs, adapts and extracts with `scheme`, and checks that extraction is bound to
+    /// the whole statement.
+    fn round_trip<S: AS_scheme<Relation = DleqPair>>(scheme: &S) {
+        let message: &[u8] = b"Relation";
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let (t, T) = DleqPair::gen_statement(&mut OsRng);
+
+        let sigma_prime = scheme.pre_sign(&p, message, &T, None).unwrap();
+        assert!(scheme.verify_pre_sign(&P, message, &T, &sigma_prime));
+
+        let sigma = scheme.adapt_signature(&sigma_prime, &t).unwrap();
+        assert!(scheme.verify_sign(&sigma, &P, message));
+
+        let extracted = scheme.extract_witness(&sigma, &sigma_prime, &T).unwrap();
+        assert_eq!(extracted, t);
+
+        // same T, different second point
+        let forged = (T.0, T.1 + ProjectivePoint::GENERATOR);
+        assert_eq!(
+            scheme.extract_witness(&sigma, &sigma_prime, &forged).err(),
+            Some(Error::WitnessMismatch)
+        );
+    }
+
+    #[test]
+    fn discrete_log_statement_matches_witness() {
+        let (t, T) = DiscreteLog::gen_statement(&mut OsRng);
+        assert!(DiscreteLog::verify_witness(&T, &t));
+        assert_eq!(DiscreteLog::statement(&t), T);
+        assert_eq!(DiscreteLog::adaptor_point(&T), *T.as_point());
+
+        let (other, _) = DiscreteLog::gen_statement(&mut OsRng);
+        assert!(!DiscreteLog::verify_witness(&T, &other));
+    }
+
+    #[test]
+    fn schemes_work_over_a_relation_with_bare_statements() {
+        type Rel = BareDiscreteLog;
+        let message: &[u8] = b"Relation";
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let (t, T) = Rel::gen_statement(&mut OsRng);
+
+        let schnorr = Schnorr::<Rel>::default();
+        let sigma_prime = schnorr.pre_sign(&p, message, &T, None).unwrap();
+        let sigma = schnorr.adapt_signature(&sigma_prime, &t).unwrap();
+        assert!(schnorr.verify_sign(&sigma, &p.public_key(), message));
+        assert_eq!(
+            schnorr.extract_witness(&sigma, &sigma_prime, &T).unwrap(),
+            t
+        );
+
+        let ecdsa = ECDSA::<Rel>::default();
+        let sigma_prime = ecdsa.pre_sign(&p, message, &T, None).unwrap();
+        let sigma = ecdsa.adapt_signature(&sigma_prime, &t).unwrap();
+        assert!(ecdsa.verify_sign(&sigma, &p.public_key(), message));
+        assert_eq!(ecdsa.extract_witness(&sigma, &sigma_prime, &T).unwrap(), t);
+    }
+
+    #[test]
+    fn schnorr_works_over_another_relation() {
+        round_trip(&Schnorr::<DleqPair>::default());
+    }
+
+    #[test]
+    fn ecdsa_works_over_another_relation() {
+        round_trip(&ECDSA::<DleqPair>::default());
+    }
+
+    #[test]
+    fn aumayr_ecdsa_works_over_another_relation() {
+        round_trip(&AumayrECDSA::<DleqPair>::default());
+    }
+}
@@ -3,135 +3,234 @@
 
 #[cfg(test)]
 mod tests {
-    use as_for_fde::{AS_scheme, Sigma, Sigma_prime, Schnorr, Sign_scheme};
+    use as_for_fde::{
+        AS_scheme, AdaptorStatement, AdaptorWitness, Error, PublicKey, Schnorr, SecretKey, Sigma,
+        Sigma_prime, Sign_scheme, VerifyError,
+    };
     use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
     use rand_core::OsRng;
 
     #[test]
     fn sign_works() {
-        let schnorr: Schnorr = Schnorr;
+        let schnorr = Schnorr::new();
         // Keys
-        let p: Scalar = Scalar::random(&mut OsRng); // secret key
-        let P: ProjectivePoint = ProjectivePoint::GENERATOR * &p; // public key
+        let p = SecretKey::from(Scalar::random(&mut OsRng)); // secret key
+        let P = p.public_key(); // public key
 
-        let t: Scalar = Scalar::random(&mut OsRng); // tweak
-        let _T: ProjectivePoint = ProjectivePoint::GENERATOR * &t; // tweak point
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng)); // tweak
+        let _T = t.statement(); // tweak point
 
         let k: Scalar = Scalar::random(&mut OsRng); // nonce
 
-        let message: &str = "Testing message for schnorr"; //our message
-                                                           // Sign
-        let sigma: Sigma = schnorr.sign(&p, message, &k);
+        let message: &[u8] = b"Testing message for schnorr"; //our message
+                                                             // Sign
+        let sigma: Sigma = schnorr.sign_with_nonce(&p, message, &k).unwrap();
         assert!(schnorr.verify_sign(&sigma, &P, message));
         println!("Signature verified ✅");
     }
     #[test]
     fn signature_fails_when_s_tampered() {
-        let schnorr = Schnorr;
-        let p = Scalar::random(&mut OsRng);
-        let P = ProjectivePoint::GENERATOR * &p;
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
         let k = Scalar::random(&mut OsRng);
-        let message = "Message";
+        let message = b"Message";
 
-        let mut sigma = schnorr.sign(&p, message, &k);
+        let mut sigma = schnorr.sign_with_nonce(&p, message, &k).unwrap();
         sigma.s += Scalar::ONE; // tamper
 
         assert!(!schnorr.verify_sign(&sigma, &P, message));
     }
     #[test]
     fn signature_fails_when_R_tampered() {
-        let schnorr = Schnorr;
-        let p = Scalar::random(&mut OsRng);
-        let P = ProjectivePoint::GENERATOR * &p;
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
         let k = Scalar::random(&mut OsRng);
-        let message = "Another message";
+        let message = b"Another message";
 
-        let mut sigma = schnorr.sign(&p, message, &k);
-        sigma.R = sigma.R + ProjectivePoint::GENERATOR; // tamper
+        let mut sigma = schnorr.sign_with_nonce(&p, message, &k).unwrap();
+        sigma.R += ProjectivePoint::GENERATOR; // tamper
 
         assert!(!schnorr.verify_sign(&sigma, &P, message));
+        assert_eq!(
+            schnorr.verify_sign_detailed(&sigma, &P, message),
+            Err(VerifyError::EquationMismatch)
+        );
+    }
+
+    #[test]
+    fn verification_reports_identity_and_empty_message() {
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let k = Scalar::random(&mut OsRng);
+        let message = b"Message";
+
+        let sigma = schnorr.sign_with_nonce(&p, message, &k).unwrap();
+        assert_eq!(
+            schnorr.verify_sign_detailed(
+                &sigma,
+                &PublicKey::from(ProjectivePoint::IDENTITY),
+                message
+            ),
+            Err(VerifyError::IdentityPoint)
+        );
+        assert_eq!(
+            schnorr.verify_sign_detailed(&sigma, &P, b""),
+            Err(VerifyError::EmptyMessage)
+        );
     }
 
     #[test]
     fn signature_fails_on_wrong_message() {
-        let schnorr = Schnorr;
-        let p = Scalar::random(&mut OsRng);
-        let P = ProjectivePoint::GENERATOR * &p;
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
         let k = Scalar::random(&mut OsRng);
-        let message = "Original";
-        let fake_message = "Tampered";
+        let message = b"Original";
+        let fake_message = b"Tampered";
 
-        let sigma = schnorr.sign(&p, message, &k);
+        let sigma = schnorr.sign_with_nonce(&p, message, &k).unwrap();
         assert!(!schnorr.verify_sign(&sigma, &P, fake_message));
     }
 
     #[test]
     fn pre_sign_works() {
-        let schnorr: Schnorr = Schnorr;
+        let schnorr = Schnorr::new();
         // Keys
-        let p: Scalar = Scalar::random(&mut OsRng); // secret key
-        let P: ProjectivePoint = ProjectivePoint::GENERATOR * &p; // public key
+        let p = SecretKey::from(Scalar::random(&mut OsRng)); // secret key
+        let P = p.public_key(); // public key
 
-        let t: Scalar = Scalar::random(&mut OsRng); // tweak
-        let T: ProjectivePoint = ProjectivePoint::GENERATOR * &t; // tweak point
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng)); // tweak
+        let T = t.statement(); // tweak point
 
         let k: Scalar = Scalar::random(&mut OsRng); // nonce
 
-        let message: &str = "Test message for schnorr pre-sign"; //our message
-                                                                 // Pre-sign
-        let sigma_prime: Sigma_prime = schnorr.pre_sign(&p, message, &T, &k);
+        let message: &[u8] = b"Test message for schnorr pre-sign"; //our message
+                                                                   // Pre-sign
+        let sigma_prime: Sigma_prime = schnorr.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
         assert!(schnorr.verify_pre_sign(&P, message, &T, &sigma_prime,));
         println!("Pre-signature verified ✅");
     }
 
     #[test]
     fn adapt_sign_works() {
-        let schnorr = Schnorr;
-        let p = Scalar::random(&mut OsRng);
-        let P = ProjectivePoint::GENERATOR * &p;
-        let t = Scalar::random(&mut OsRng);
-        let T = ProjectivePoint::GENERATOR * &t;
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
         let k = Scalar::random(&mut OsRng);
-        let message = "Adapting signature";
+        let message = b"Adapting signature";
 
-        let sigma_prime = schnorr.pre_sign(&p, message, &T, &k);
-        let sigma = schnorr.adapt_signature(&sigma_prime, &t);
+        let sigma_prime = schnorr.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        let sigma = schnorr.adapt_signature(&sigma_prime, &t).unwrap();
 
         assert!(schnorr.verify_sign(&sigma, &P, message));
     }
     #[test]
     fn witness_extraction_works() {
-        let schnorr = Schnorr;
-        let p = Scalar::random(&mut OsRng);
-        let t = Scalar::random(&mut OsRng);
-        let T = ProjectivePoint::GENERATOR * &t;
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
         let k = Scalar::random(&mut OsRng);
-        let message = "Extract witness test";
+        let message = b"Extract witness test";
 
-        let sigma_prime = schnorr.pre_sign(&p, message, &T, &k);
-        let sigma = schnorr.adapt_signature(&sigma_prime, &t);
-        let extracted = schnorr.extract_witness(&sigma, &sigma_prime);
+        let sigma_prime = schnorr.pre_sign_with_nonce(&p, message, &T, &k).unwrap();
+        let sigma = schnorr.adapt_signature(&sigma_prime, &t).unwrap();
+        let extracted = schnorr.extract_witness(&sigma, &sigma_prime, &T).unwrap();
 
         assert_eq!(extracted, t);
     }
 
     #[test]
-    #[should_panic(expected = "Message cannot be empty.")]
     fn sign_fails_on_empty_message() {
-        let schnorr = Schnorr;
-        let p = Scalar::random(&mut OsRng);
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
         let k = Scalar::random(&mut OsRng);
-        let _ = schnorr.sign(&p, "", &k);
+        assert!(matches!(
+            schnorr.sign_with_nonce(&p, b"", &k),
+            Err(Error::EmptyMessage)
+        ));
     }
 
     #[test]
-    #[should_panic(expected = "Message cannot be empty.")]
     fn presign_fails_on_empty_message() {
-        let schnorr = Schnorr;
-        let p = Scalar::random(&mut OsRng);
-        let t = Scalar::random(&mut OsRng);
-        let T = ProjectivePoint::GENERATOR * &t;
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
         let k = Scalar::random(&mut OsRng);
-        let _ = schnorr.pre_sign(&p, "", &T, &k);
+        assert!(matches!(
+            schnorr.pre_sign_with_nonce(&p, b"", &T, &k),
+            Err(Error::EmptyMessage)
+        ));
+    }
+
+    #[test]
+    fn deterministic_sign_is_reproducible() {
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let message = b"Deterministic nonce";
+
+        let sigma_1 = schnorr.sign(&p, message, None).unwrap();
+        let sigma_2 = schnorr.sign(&p, message, None).unwrap();
+        assert_eq!(sigma_1.R, sigma_2.R);
+        assert_eq!(sigma_1.s, sigma_2.s);
+        assert!(schnorr.verify_sign(&sigma_1, &P, message));
+
+        let sigma_3 = schnorr.sign(&p, b"Another message", None).unwrap();
+        assert_ne!(sigma_1.R, sigma_3.R);
+    }
+
+    #[test]
+    fn hedged_pre_sign_works() {
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let message = b"Hedged nonce";
+
+        let sigma_prime_1 = schnorr.pre_sign(&p, message, &T, Some(&[1u8; 32])).unwrap();
+        let sigma_prime_2 = schnorr.pre_sign(&p, message, &T, Some(&[2u8; 32])).unwrap();
+        assert_ne!(sigma_prime_1.R_prime, sigma_prime_2.R_prime);
+        assert!(schnorr.verify_pre_sign(&P, message, &T, &sigma_prime_1));
+
+        let sigma = schnorr.adapt_signature(&sigma_prime_1, &t).unwrap();
+        assert!(schnorr.verify_sign(&sigma, &P, message));
+    }
+
+    #[test]
+    fn pre_sign_nonce_depends_on_statement() {
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let other_T = AdaptorStatement::from(*T.as_point() + ProjectivePoint::GENERATOR);
+        let message = b"Same message";
+
+        let sigma_prime_1 = schnorr.pre_sign(&p, message, &T, None).unwrap();
+        let sigma_prime_2 = schnorr.pre_sign(&p, message, &other_T, None).unwrap();
+        assert_ne!(sigma_prime_1.s_prime, sigma_prime_2.s_prime);
+    }
+
+    #[test]
+    fn prehash_adaptor_works() {
+        let schnorr = Schnorr::new();
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let T = t.statement();
+        let digest = [7u8; 32];
+
+        let sigma_prime = schnorr.pre_sign_prehash(&p, &digest, &T, None).unwrap();
+        assert!(schnorr.verify_pre_sign_prehash(&P, &digest, &T, &sigma_prime));
+
+        let sigma = schnorr.adapt_signature(&sigma_prime, &t).unwrap();
+        assert!(schnorr.verify_sign_prehash(&sigma, &P, &digest));
     }
 }
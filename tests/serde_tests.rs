@@ -0,0 +1,223 @@
+#![cfg(feature = "serde")]
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::utils::{encode_point, encode_scalar};
+    use as_for_fde::{
+        AS_scheme, AdaptorStatement, AdaptorWitness, AumayrECDSA, Pi, PublicKey, Scheme,
+        SchemePreSignature, SchemeSignature, Schnorr, SecretKey, Sigma, Sign_scheme, BIP340, ECDSA,
+    };
+    use k256::{elliptic_curve::ff::Field, Scalar};
+    use rand_core::OsRng;
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::{json, Value};
+
+    fn all_schemes() -> Vec<Scheme> {
+        vec![
+            Scheme::Schnorr(Schnorr::new()),
+            Scheme::BIP340(BIP340),
+            Scheme::ECDSA(ECDSA::new()),
+            Scheme::AumayrECDSA(AumayrECDSA::new()),
+        ]
+    }
+
+    /// Returns an x-coordinate with no point on the curve.
+    fn off_curve_x() -> [u8; 32] {
+        let mut x = [0u8; 32];
+        for candidate in 1u8.. {
+            x[31] = candidate;
+            if BIP340::lift_x(&x).is_none() {
+                return x;
+            }
+        }
+        unreachable!()
+    }
+
+    fn to_cbor<T: Serialize>(value: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes).unwrap();
+        bytes
+    }
+
+    fn from_cbor<T: DeserializeOwned>(
+        bytes: &[u8],
+    ) -> Result<T, ciborium::de::Error<std::io::Error>> {
+        ciborium::from_reader(bytes)
+    }
+
+    fn sign_and_pre_sign(
+        scheme: &Scheme,
+    ) -> (
+        SchemeSignature,
+        SchemePreSignature,
+        PublicKey,
+        AdaptorStatement,
+    ) {
+        let message: &[u8] = b"serde round trip";
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let P = p.public_key();
+        let T = t.statement();
+
+        let sigma = scheme.sign(&p, message, None).unwrap();
+        let sigma_prime = scheme.pre_sign(&p, message, &T, None).unwrap();
+        (sigma, sigma_prime, P, T)
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let message: &[u8] = b"serde round trip";
+        for scheme in all_schemes() {
+            let (sigma, sigma_prime, P, T) = sign_and_pre_sign(&scheme);
+
+            let json = serde_json::to_string(&sigma).unwrap();
+            let sigma: SchemeSignature = serde_json::from_str(&json).unwrap();
+            assert!(scheme.verify_sign(&sigma, &P, message));
+
+            let json = serde_json::to_string(&sigma_prime).unwrap();
+            let sigma_prime: SchemePreSignature = serde_json::from_str(&json).unwrap();
+            assert!(scheme.verify_pre_sign(&P, message, &T, &sigma_prime));
+
+            let json = serde_json::to_string(&scheme).unwrap();
+            let decoded: Scheme = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_json_uses_hex() {
+        let (sigma, sigma_prime, _, _) = sign_and_pre_sign(&Scheme::ECDSA(ECDSA::new()));
+        let SchemePreSignature::ECDSA(sigma_prime) = sigma_prime else {
+            panic!("expected an ECDSA pre-signature");
+        };
+
+        let value = serde_json::to_value(&sigma).unwrap();
+        assert_eq!(
+            value["ecdsa"]["R"],
+            Value::String(hex::encode(encode_point(&sigma.as_sigma().R)))
+        );
+        assert_eq!(
+            value["ecdsa"]["s"],
+            Value::String(hex::encode(encode_scalar(&sigma.as_sigma().s)))
+        );
+
+        let value = serde_json::to_value(&sigma_prime).unwrap();
+        assert_eq!(
+            value["Z"],
+            Value::String(hex::encode(encode_point(&sigma_prime.Z)))
+        );
+        assert!(value.get("K").is_none());
+
+        assert_eq!(
+            serde_json::to_value(Scheme::AumayrECDSA(AumayrECDSA::new())).unwrap(),
+            json!("aumayr")
+        );
+    }
+
+    #[test]
+    fn test_cbor_round_trip() {
+        let message: &[u8] = b"serde round trip";
+        for scheme in all_schemes() {
+            let (sigma, sigma_prime, P, T) = sign_and_pre_sign(&scheme);
+
+            let sigma: SchemeSignature = from_cbor(&to_cbor(&sigma)).unwrap();
+            assert!(scheme.verify_sign(&sigma, &P, message));
+
+            let sigma_prime: SchemePreSignature = from_cbor(&to_cbor(&sigma_prime)).unwrap();
+            assert!(scheme.verify_pre_sign(&P, message, &T, &sigma_prime));
+
+            let pi = Pi {
+                e: Scalar::random(&mut OsRng),
+                i: Scalar::random(&mut OsRng),
+            };
+            let decoded: Pi = from_cbor(&to_cbor(&pi)).unwrap();
+            assert_eq!((decoded.e, decoded.i), (pi.e, pi.i));
+        }
+    }
+
+    #[test]
+    fn test_cbor_uses_raw_bytes() {
+        let (sigma, _, _, _) = sign_and_pre_sign(&Scheme::Schnorr(Schnorr::new()));
+        let sigma = sigma.as_sigma();
+
+        let value: ciborium::Value = from_cbor(&to_cbor(&sigma)).unwrap();
+        let fields = value.as_map().unwrap();
+        for (key, field) in fields {
+            let bytes = field.as_bytes().unwrap();
+            match key.as_text().unwrap() {
+                "R" => assert_eq!(bytes.as_slice(), encode_point(&sigma.R).as_slice()),
+                "s" => assert_eq!(bytes.as_slice(), encode_scalar(&sigma.s).as_slice()),
+                other => panic!("unexpected field {other}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_keys_round_trip() {
+        let p = SecretKey::from(Scalar::random(&mut OsRng));
+        let t = AdaptorWitness::from(Scalar::random(&mut OsRng));
+        let (P, T) = (p.public_key(), t.statement());
+
+        assert_eq!(
+            serde_json::to_value(P).unwrap(),
+            json!(hex::encode(P.to_bytes()))
+        );
+        assert_eq!(
+            serde_json::from_value::<PublicKey>(serde_json::to_value(P).unwrap()).unwrap(),
+            P
+        );
+        assert_eq!(from_cbor::<SecretKey>(&to_cbor(&p)).unwrap(), p);
+        assert_eq!(from_cbor::<AdaptorWitness>(&to_cbor(&t)).unwrap(), t);
+        assert_eq!(from_cbor::<AdaptorStatement>(&to_cbor(&T)).unwrap(), T);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_invalid_values() {
+        let (sigma, _, _, _) = sign_and_pre_sign(&Scheme::Schnorr(Schnorr::new()));
+        let sigma = sigma.as_sigma();
+        let valid = serde_json::to_value(sigma).unwrap();
+
+        // identity point
+        let mut value = valid.clone();
+        value["R"] = Value::String(hex::encode([0u8; 33]));
+        assert!(serde_json::from_value::<Sigma>(value).is_err());
+
+        // off-curve point
+        let mut value = valid.clone();
+        value["R"] = Value::String(format!("02{}", hex::encode(off_curve_x())));
+        assert!(serde_json::from_value::<Sigma>(value).is_err());
+
+        // scalar equal to the group order
+        let mut value = valid.clone();
+        value["s"] = Value::String(
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141".to_string(),
+        );
+        assert!(serde_json::from_value::<Sigma>(value).is_err());
+
+        // truncated scalar
+        let mut value = valid.clone();
+        value["s"] = Value::String(hex::encode(&encode_scalar(&sigma.s)[1..]));
+        assert!(serde_json::from_value::<Sigma>(value).is_err());
+
+        // not hex
+        let mut value = valid;
+        value["s"] = Value::String("zz".repeat(32));
+        assert!(serde_json::from_value::<Sigma>(value).is_err());
+
+        assert!(serde_json::from_value::<Scheme>(json!("rsa")).is_err());
+
+        // zero secret key and witness
+        let zero = json!(hex::encode([0u8; 32]));
+        assert!(serde_json::from_value::<SecretKey>(zero.clone()).is_err());
+        assert!(serde_json::from_value::<AdaptorWitness>(zero).is_err());
+        assert!(from_cbor::<SecretKey>(&to_cbor(&ciborium::Value::Bytes(vec![0u8; 32]))).is_err());
+
+        // pre-signature tagged with the wrong scheme
+        let (_, sigma_prime, _, _) = sign_and_pre_sign(&Scheme::Schnorr(Schnorr::new()));
+        let mut value = serde_json::to_value(&sigma_prime).unwrap();
+        value = json!({ "ecdsa": value["schnorr"].take() });
+        assert!(serde_json::from_value::<SchemePreSignature>(value).is_err());
+    }
+}
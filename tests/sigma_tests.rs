@@ -0,0 +1,230 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::sigma::{
+        outcome_statements, And, DLog, DLogStatement, Dleq, DleqStatement, Or, OrWitness, Proof,
+    };
+    use as_for_fde::{Error, SigmaProtocol, Transcript, VerifyError};
+    use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
+    use rand_core::OsRng;
+
+    fn transcript() -> Transcript {
+        Transcript::new("as_for_fde/test", None)
+    }
+
+    fn random_point() -> ProjectivePoint {
+        ProjectivePoint::GENERATOR * Scalar::random(&mut OsRng)
+    }
+
+    #[test]
+    fn dlog_over_an_arbitrary_base() {
+        let x = Scalar::random(&mut OsRng);
+        let G = random_point();
+        let statement = DLogStatement { G, X: G * x };
+
+        let proof = DLog.prove(&transcript(), &statement, &x, None).unwrap();
+        assert_eq!(
+            DLog.verify_detailed(&transcript(), &statement, &proof),
+            Ok(())
+        );
+
+        // bound to the label, the context and the base
+        let other_label = Transcript::new("as_for_fde/other", None);
+        assert!(!DLog.verify(&other_label, &statement, &proof));
+        let with_context = Transcript::new("as_for_fde/test", Some(b"session"));
+        assert!(!DLog.verify(&with_context, &statement, &proof));
+        let other_base = DLogStatement {
+            G: ProjectivePoint::GENERATOR,
+            X: ProjectivePoint::GENERATOR * x,
+        };
+        assert!(!DLog.verify(&transcript(), &other_base, &proof));
+
+        assert_eq!(
+            DLog.prove(&transcript(), &statement, &(x + Scalar::ONE), None)
+                .err(),
+            Some(Error::WitnessMismatch)
+        );
+    }
+
+    #[test]
+    fn proofs_are_deterministic_unless_hedged() {
+        let x = Scalar::random(&mut OsRng);
+        let statement = DLogStatement {
+            G: ProjectivePoint::GENERATOR,
+            X: ProjectivePoint::GENERATOR * x,
+        };
+        let first = DLog.prove(&transcript(), &statement, &x, None).unwrap();
+        let second = DLog.prove(&transcript(), &statement, &x, None).unwrap();
+        assert_eq!(first, second);
+
+        let hedged = DLog
+            .prove(&transcript(), &statement, &x, Some(&[1u8; 32]))
+            .unwrap();
+        assert_ne!(first, hedged);
+        assert!(DLog.verify(&transcript(), &statement, &hedged));
+    }
+
+    #[test]
+    fn dleq_across_arbitrary_bases() {
+        let x = Scalar::random(&mut OsRng);
+        let (G, H) = (random_point(), random_point());
+        let statement = DleqStatement {
+            G,
+            X: G * x,
+            H,
+            Y: H * x,
+        };
+        let proof = Dleq.prove(&transcript(), &statement, &x, None).unwrap();
+        assert!(Dleq.verify(&transcript(), &statement, &proof));
+
+        // Y with a different discrete log
+        let unequal = DleqStatement {
+            Y: H * (x + Scalar::ONE),
+            ..statement
+        };
+        assert_eq!(
+            Dleq.prove(&transcript(), &unequal, &x, None).err(),
+            Some(Error::WitnessMismatch)
+        );
+        assert_eq!(
+            Dleq.verify_detailed(&transcript(), &unequal, &proof),
+            Err(VerifyError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn and_needs_both_witnesses() {
+        let (x, y) = (Scalar::random(&mut OsRng), Scalar::random(&mut OsRng));
+        let H = random_point();
+        let protocol = And(DLog, Dleq);
+        let statement = (
+            DLogStatement {
+                G: ProjectivePoint::GENERATOR,
+                X: ProjectivePoint::GENERATOR * x,
+            },
+            DleqStatement {
+                G: ProjectivePoint::GENERATOR,
+                X: ProjectivePoint::GENERATOR * y,
+                H,
+                Y: H * y,
+            },
+        );
+
+        let proof = protocol
+            .prove(&transcript(), &statement, &(x, y), None)
+            .unwrap();
+        assert!(protocol.verify(&transcript(), &statement, &proof));
+        assert_eq!(
+            protocol
+                .prove(&transcript(), &statement, &(x, x), None)
+                .err(),
+            Some(Error::WitnessMismatch)
+        );
+
+        let tampered = Proof {
+            e: proof.e,
+            z: (proof.z.0, proof.z.1 + Scalar::ONE),
+        };
+        assert!(!protocol.verify(&transcript(), &statement, &tampered));
+    }
+
+    #[test]
+    fn or_proves_one_of_several_oracle_outcomes() {
+        let outcomes: Vec<ProjectivePoint> = (0..4).map(|_| random_point()).collect();
+        let r = Scalar::random(&mut OsRng);
+        let T = outcomes[2] + ProjectivePoint::GENERATOR * r;
+        let statement = outcome_statements(&T, &outcomes);
+        let protocol = Or(DLog);
+
+        let witness = OrWitness {
+            index: 2,
+            witness: r,
+        };
+        let proof = protocol
+            .prove(&transcript(), &statement, &witness, None)
+            .unwrap();
+        assert_eq!(proof.z.e.len(), outcomes.len());
+        assert_eq!(
+            protocol.verify_detailed(&transcript(), &statement, &proof),
+            Ok(())
+        );
+
+        // the proof is bound to the set of outcomes
+        let mut others = outcomes.clone();
+        others[0] = random_point();
+        assert!(!protocol.verify(&transcript(), &outcome_statements(&T, &others), &proof));
+        assert!(!protocol.verify(&transcript(), &statement[..3].to_vec(), &proof));
+
+        // wrong branch or out of range
+        for index in [1, 4] {
+            let witness = OrWitness { index, witness: r };
+            assert_eq!(
+                protocol
+                    .prove(&transcript(), &statement, &witness, None)
+                    .err(),
+                Some(Error::WitnessMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn or_challenges_must_sum_to_the_challenge() {
+        let outcomes: Vec<ProjectivePoint> = (0..3).map(|_| random_point()).collect();
+        let r = Scalar::random(&mut OsRng);
+        let statement =
+            outcome_statements(&(outcomes[0] + ProjectivePoint::GENERATOR * r), &outcomes);
+        let protocol = Or(DLog);
+        let witness = OrWitness {
+            index: 0,
+            witness: r,
+        };
+        let mut proof = protocol
+            .prove(&transcript(), &statement, &witness, None)
+            .unwrap();
+
+        proof.z.e[1] += Scalar::ONE;
+        assert!(!protocol.verify(&transcript(), &statement, &proof));
+        proof.z.e[1] -= Scalar::ONE;
+        proof.z.z.pop();
+        assert!(!protocol.verify(&transcript(), &statement, &proof));
+    }
+
+    #[test]
+    fn compositions_nest() {
+        let x = Scalar::random(&mut OsRng);
+        let H = random_point();
+        let dleq = DleqStatement {
+            G: ProjectivePoint::GENERATOR,
+            X: ProjectivePoint::GENERATOR * x,
+            H,
+            Y: H * x,
+        };
+        let decoy = DleqStatement {
+            G: ProjectivePoint::GENERATOR,
+            X: random_point(),
+            H,
+            Y: random_point(),
+        };
+        let t = Scalar::random(&mut OsRng);
+        let knows_t = DLogStatement {
+            G: ProjectivePoint::GENERATOR,
+            X: ProjectivePoint::GENERATOR * t,
+        };
+
+        let protocol = And(Or(Dleq), DLog);
+        let statement = (vec![decoy, dleq], knows_t);
+        let witness = (
+            OrWitness {
+                index: 1,
+                witness: x,
+            },
+            t,
+        );
+        let proof = protocol
+            .prove(&transcript(), &statement, &witness, None)
+            .unwrap();
+        assert!(protocol.verify(&transcript(), &statement, &proof));
+    }
+}
@@ -0,0 +1,50 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::Transcript;
+    use k256::ProjectivePoint;
+
+    #[test]
+    fn appends_are_length_framed() {
+        let mut first = Transcript::new("as_for_fde/test", None);
+        first.append_message("m", b"ab");
+        first.append_message("m", b"c");
+        let mut second = Transcript::new("as_for_fde/test", None);
+        second.append_message("m", b"a");
+        second.append_message("m", b"bc");
+        assert_ne!(first.challenge_bytes("e"), second.challenge_bytes("e"));
+
+        let mut first = Transcript::new("as_for_fde/test", None);
+        first.append_message("ab", b"c");
+        let mut second = Transcript::new("as_for_fde/test", None);
+        second.append_message("a", b"bc");
+        assert_ne!(first.challenge_bytes("e"), second.challenge_bytes("e"));
+    }
+
+    #[test]
+    fn label_and_context_separate_transcripts() {
+        let challenge = |label: &'static str, context: Option<&[u8]>| {
+            let mut transcript = Transcript::new(label, context);
+            transcript.append_point("G", &ProjectivePoint::GENERATOR);
+            transcript.challenge_scalar("e")
+        };
+        let base = challenge("as_for_fde/test", None);
+        assert_eq!(base, challenge("as_for_fde/test", None));
+        assert_ne!(base, challenge("as_for_fde/other", None));
+        assert_ne!(base, challenge("as_for_fde/test", Some(b"")));
+        assert_ne!(
+            challenge("as_for_fde/test", Some(b"swap 1")),
+            challenge("as_for_fde/test", Some(b"swap 2"))
+        );
+    }
+
+    #[test]
+    fn challenges_chain() {
+        let mut transcript = Transcript::new("as_for_fde/test", None);
+        let first = transcript.challenge_scalar("e");
+        let second = transcript.challenge_scalar("e");
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,46 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+
+#[cfg(test)]
+mod tests {
+    use as_for_fde::utils::{derive_nonce, encode_point};
+    use k256::{elliptic_curve::ff::Field, ProjectivePoint, Scalar};
+    use rand_core::OsRng;
+
+    /// Derives the `pre_sign` nonce of a fixed message under `x` with the optional statement
+    /// `T` and auxiliary randomness.
+    fn nonce(x: &Scalar, T: Option<&ProjectivePoint>, aux_rand: Option<&[u8; 32]>) -> Scalar {
+        derive_nonce(x, b"Message", &["as_for_fde/test"], "pre_sign", T, aux_rand)
+    }
+
+    #[test]
+    fn derive_nonce_is_deterministic() {
+        let x = Scalar::random(&mut OsRng);
+        let T = ProjectivePoint::GENERATOR * Scalar::random(&mut OsRng);
+        let aux_rand = [7u8; 32];
+        assert_eq!(
+            nonce(&x, Some(&T), Some(&aux_rand)),
+            nonce(&x, Some(&T), Some(&aux_rand))
+        );
+    }
+
+    #[test]
+    fn derive_nonce_frames_optional_fields() {
+        let x = Scalar::random(&mut OsRng);
+        let T = ProjectivePoint::GENERATOR * Scalar::random(&mut OsRng);
+        // the auxiliary randomness is the start of the encoding of T
+        let aux_rand: [u8; 32] = encode_point(&T)[..32].try_into().unwrap();
+
+        let nonces = [
+            nonce(&x, None, None),
+            nonce(&x, Some(&T), None),
+            nonce(&x, None, Some(&aux_rand)),
+            nonce(&x, Some(&T), Some(&aux_rand)),
+        ];
+        for (i, a) in nonces.iter().enumerate() {
+            for b in &nonces[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+}